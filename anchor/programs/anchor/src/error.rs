@@ -85,4 +85,321 @@ pub enum EcomError{
 
     #[msg("Member does not belong to this organization.")]
     InvalidOrganization,
+
+    // Purchase Errors
+    #[msg("PurchaseListFull: Too many open purchases, settle one before starting another.")]
+    PurchaseListFull,
+
+    #[msg("InvalidQuantity: Quantity must be greater than zero.")]
+    InvalidQuantity,
+
+    #[msg("InsufficientStock: The product does not have enough stock for this quantity.")]
+    InsufficientStock,
+
+    #[msg("CartProductMismatch: The cart entry does not belong to this product.")]
+    CartProductMismatch,
+
+    #[msg("SellerMismatch: The seller does not match the product being paid for.")]
+    SellerMismatch,
+
+    #[msg("ProductMintMismatch: The product is priced in a different token.")]
+    ProductMintMismatch,
+
+    #[msg("BuyerMismatch: The buyer does not match the owner of the payment.")]
+    BuyerMismatch,
+
+    #[msg("DepositAmountMismatch: The deposit does not match the amount due.")]
+    DepositAmountMismatch,
+
+    #[msg("InvalidBasisPoints: Basis points must be between 0 and 10,000.")]
+    InvalidBasisPoints,
+
+    // Coupon Errors
+    #[msg("InvalidCoupon: Coupons need a discount, a future expiry and non-zero redemption caps.")]
+    InvalidCoupon,
+
+    #[msg("InvalidCouponCode: The coupon code does not match the coupon account.")]
+    InvalidCouponCode,
+
+    #[msg("CouponExpired: The coupon has expired.")]
+    CouponExpired,
+
+    #[msg("CouponRedemptionLimit: The coupon has been redeemed the maximum number of times.")]
+    CouponRedemptionLimit,
+
+    #[msg("CouponWalletLimit: This wallet has already redeemed the coupon the maximum number of times.")]
+    CouponWalletLimit,
+
+    #[msg("CouponNotApplicable: The coupon does not apply to this product.")]
+    CouponNotApplicable,
+
+    // Loyalty Errors
+    #[msg("InvalidLoyaltyConfig: Points per unit of discount must be greater than zero.")]
+    InvalidLoyaltyConfig,
+
+    #[msg("LoyaltyAccountsRequired: Redeeming or earning points needs the loyalty program, points mint and the buyer's points account.")]
+    LoyaltyAccountsRequired,
+
+    #[msg("InsufficientPoints: Not enough loyalty points for a discount.")]
+    InsufficientPoints,
+
+    #[msg("PointsMintMismatch: The points mint is not the loyalty programme's.")]
+    PointsMintMismatch,
+    // Gift Card Errors
+    #[msg("InvalidGiftCard: Gift cards must be funded with a non-zero amount.")]
+    InvalidGiftCard,
+
+    #[msg("InvalidGiftCardSecret: The claim key does not match the gift card.")]
+    InvalidGiftCardSecret,
+
+    #[msg("InvalidGiftCardClaim: A gift card needs either a holder or a claim key, not both.")]
+    InvalidGiftCardClaim,
+
+    #[msg("GiftCardMintMismatch: The gift card holds a different token than the payment.")]
+    GiftCardMintMismatch,
+
+    #[msg("GiftCardAlreadyClaimed: The gift card already has a holder.")]
+    GiftCardAlreadyClaimed,
+
+    #[msg("GiftCardNotHolder: Only the gift card holder can use or transfer it.")]
+    GiftCardNotHolder,
+
+    #[msg("InsufficientGiftCardBalance: The gift card balance does not cover the requested amount.")]
+    InsufficientGiftCardBalance,
+
+    #[msg("GiftCardAccountsRequired: Drawing from a gift card needs the card and its vault.")]
+    GiftCardAccountsRequired,
+
+    // Relay Errors
+    #[msg("InvalidIntentSignature: The payment intent is not signed by the buyer through the ed25519 program.")]
+    InvalidIntentSignature,
+
+    #[msg("IntentExpired: The payment intent has expired.")]
+    IntentExpired,
+
+    #[msg("IntentNonceMismatch: The payment intent nonce is not the buyer's next nonce.")]
+    IntentNonceMismatch,
+
+    #[msg("IntentAmountExceeded: The amount pulled from the buyer, transfer fees included, is more than the intent allows.")]
+    IntentAmountExceeded,
+
+    #[msg("UnsupportedIntent: Only token-priced products can be bought through a relayer.")]
+    UnsupportedIntent,
+
+    // Payout Errors
+    #[msg("InvalidPayoutSplits: Payout splits need up to 5 distinct recipients whose shares add up to 10,000 bps.")]
+    InvalidPayoutSplits,
+
+    #[msg("MissingPayoutAccounts: Pass one token account per payout recipient, in split order.")]
+    MissingPayoutAccounts,
+
+    #[msg("PayoutRecipientMismatch: A payout token account is not writable or not owned by its recipient.")]
+    PayoutRecipientMismatch,
+
+    // Marketplace Errors
+    #[msg("Unauthorized: Only the marketplace admin can perform this action.")]
+    Unauthorized,
+
+    #[msg("InvalidTreasuryAccount: The token account is not owned by the marketplace treasury.")]
+    InvalidTreasuryAccount,
+
+    // Oracle Errors
+    #[msg("PriceFeedRequired: USD-priced products need the marketplace, accepted mint and price feed accounts.")]
+    PriceFeedRequired,
+
+    #[msg("UntrustedPriceFeed: The price feed is not the one registered for this mint.")]
+    UntrustedPriceFeed,
+
+    #[msg("InvalidPriceFeed: The price feed account could not be read.")]
+    InvalidPriceFeed,
+
+    #[msg("StalePrice: The price feed has not been updated recently enough.")]
+    StalePrice,
+
+    #[msg("PriceConfidenceTooWide: The price feed confidence interval is too wide.")]
+    PriceConfidenceTooWide,
+
+    #[msg("MockOracleDisabled: Mock price feeds are only available in localnet builds.")]
+    MockOracleDisabled,
+
+    // Refund Errors
+    #[msg("UnauthorizedRefund: Only the seller can refund.")]
+    UnauthorizedRefund,
+
+    #[msg("RefundExceedsEscrow: The refund is larger than the amount left in escrow.")]
+    RefundExceedsEscrow,
+
+    // Payment Plan Errors
+    #[msg("InvalidPaymentPlan: Instalments need 2 to 12 increasing due dates, none in the past.")]
+    InvalidPaymentPlan,
+
+    #[msg("PaymentPlanActive: This payment is paid through its instalment plan.")]
+    PaymentPlanActive,
+
+    #[msg("PaymentPlanNotActive: The payment plan is already completed or defaulted.")]
+    PaymentPlanNotActive,
+
+    #[msg("PaymentPlanNotInDefault: No instalment is overdue past the grace period.")]
+    PaymentPlanNotInDefault,
+
+    #[msg("InstallmentThresholdNotMet: Not enough of the instalment plan has been paid to ship.")]
+    InstallmentThresholdNotMet,
+
+    // Subscription Errors
+    #[msg("InvalidSubscription: Subscriptions need a token-priced product and an interval of at least a day.")]
+    InvalidSubscription,
+
+    #[msg("SubscriptionNotActive: The subscription is paused or cancelled.")]
+    SubscriptionNotActive,
+
+    #[msg("SubscriptionNotDue: The next billing period has not started yet.")]
+    SubscriptionNotDue,
+
+    #[msg("SubscriptionCancelled: A cancelled subscription cannot be changed.")]
+    SubscriptionCancelled,
+
+    // Release Errors
+    #[msg("UnauthorizedRelease: Only the buyer or, after delivery, the seller can release the escrow.")]
+    UnauthorizedRelease,
+
+    #[msg("OrderNotDelivered: The order has not been marked as delivered.")]
+    OrderNotDelivered,
+
+    #[msg("InspectionPeriodActive: The buyer's inspection window has not passed yet.")]
+    InspectionPeriodActive,
+
+    #[msg("InvalidInspectionPeriod: The inspection period cannot be negative.")]
+    InvalidInspectionPeriod,
+
+    // Expiry Errors
+    #[msg("InvalidShippingWindow: The shipping window must be positive.")]
+    InvalidShippingWindow,
+
+    #[msg("ShippingDeadlineNotReached: The seller still has time to ship.")]
+    ShippingDeadlineNotReached,
+
+    #[msg("OrderAlreadyShipped: The order has already shipped.")]
+    OrderAlreadyShipped,
+
+    #[msg("InvalidOrderAccount: The order account is not owned by this program.")]
+    InvalidOrderAccount,
+
+    #[msg("EscrowNotExpirable: Only funded, undisputed escrows can expire.")]
+    EscrowNotExpirable,
+
+    // Milestone Errors
+    #[msg("InvalidMilestoneSchedule: Milestones must advance, share 10,000 bps and end on delivery.")]
+    InvalidMilestoneSchedule,
+
+    #[msg("NoMilestoneDue: No milestone has been reached since the last release.")]
+    NoMilestoneDue,
+
+    // Close Errors
+    #[msg("PurchaseNotClosable: The payment and escrow have not reached a final state.")]
+    PurchaseNotClosable,
+
+    #[msg("PaymentNotCancellable: Only a pending payment with no plan and an unfunded escrow, if any, can be cancelled.")]
+    PaymentNotCancellable,
+
+    #[msg("EscrowAccountsRequired: Cancelling a payment with an escrow needs its vault, mint and token program.")]
+    EscrowAccountsRequired,
+
+    // Keeper Errors
+    #[msg("KeeperVaultRequired: Pass the keeper vault; part of the fee funds keepers.")]
+    KeeperVaultRequired,
+
+    #[msg("UnknownCrankTarget: The crank has no task for this account.")]
+    UnknownCrankTarget,
+
+    #[msg("TooManyCrankTasks: A crank handles at most 8 tasks.")]
+    TooManyCrankTasks,
+
+    #[msg("CrankTaskMismatch: A task's accounts don't match its target or keeper.")]
+    CrankTaskMismatch,
+
+    #[msg("InvalidKeeperVault: The keeper vault isn't the pool for the task's mint.")]
+    InvalidKeeperVault,
+
+    // Dispute Errors
+    #[msg("EscrowDisputed: The escrow is frozen by an open dispute.")]
+    EscrowDisputed,
+
+    #[msg("DisputeNotAllowed: Disputes can only be opened by the buyer while funds are escrowed.")]
+    DisputeNotAllowed,
+
+    #[msg("DisputeNotOpen: The dispute has already been resolved.")]
+    DisputeNotOpen,
+
+    #[msg("UnauthorizedEvidence: Only the buyer and seller can submit evidence.")]
+    UnauthorizedEvidence,
+
+    #[msg("EvidenceLimitReached: Too much evidence, or the URI is too long.")]
+    EvidenceLimitReached,
+
+    #[msg("InvalidArbiterPanel: The panel needs distinct arbiters, at most 7, and a threshold between 1 and its size.")]
+    InvalidArbiterPanel,
+
+    #[msg("PanelRulingRequired: This dispute is decided by the arbiter panel.")]
+    PanelRulingRequired,
+
+    #[msg("PanelVoteNotAllowed: This dispute is not decided by the arbiter panel.")]
+    PanelVoteNotAllowed,
+
+    #[msg("UnauthorizedArbiter: Only the marketplace arbiter or panel can rule on this dispute.")]
+    UnauthorizedArbiter,
+
+    #[msg("ReceiptAccountsRequired: A ruling that pays the seller mints the buyer's receipt; pass the receipt accounts.")]
+    ReceiptAccountsRequired,
+
+    // Order Errors
+    #[msg("InvalidOrderTransition: Order tracking can only move forward on a placed order.")]
+    InvalidOrderTransition,
+
+    // Token Account Errors
+    #[msg("InvalidEscrowVault: The escrow token account is not owned by the escrow.")]
+    InvalidEscrowVault,
+
+    #[msg("InvalidBuyerTokenAccount: The token account is not owned by the buyer.")]
+    InvalidBuyerTokenAccount,
+
+    #[msg("InvalidSellerTokenAccount: The token account is not owned by the seller.")]
+    InvalidSellerTokenAccount,
+
+    #[msg("InvalidUserTokenAccount: The paying token account is not owned by the signer.")]
+    InvalidUserTokenAccount,
+
+    #[msg("UserTokenMintMismatch: The signer's token account is not on the payment mint.")]
+    UserTokenMintMismatch,
+
+    #[msg("EscrowVaultMintMismatch: The escrow vault is not on the payment mint.")]
+    EscrowVaultMintMismatch,
+
+    #[msg("BuyerTokenMintMismatch: The buyer token account is not on the payment mint.")]
+    BuyerTokenMintMismatch,
+
+    #[msg("SellerTokenMintMismatch: The seller token account is not on the payment mint.")]
+    SellerTokenMintMismatch,
+
+    #[msg("TreasuryTokenMintMismatch: The treasury token account is not on the payment mint.")]
+    TreasuryTokenMintMismatch,
+
+    // Token Errors
+    #[msg("InvalidMint: The token mint does not match the payment mint.")]
+    InvalidMint,
+
+    #[msg("MathOverflow: Arithmetic overflow while computing token amounts.")]
+    MathOverflow,
+
+    // Escrow State Errors
+    #[msg("EscrowAlreadyFunded: The escrow has already been funded.")]
+    EscrowAlreadyFunded,
+
+    #[msg("EscrowNotFunded: The escrow has not been funded yet.")]
+    EscrowNotFunded,
+
+    #[msg("EscrowAlreadySettled: The escrow has already been released, refunded or failed.")]
+    EscrowAlreadySettled,
+
+    #[msg("InvalidEscrowTransition: The escrow cannot move to that state from its current one.")]
+    InvalidEscrowTransition,
 }
\ No newline at end of file
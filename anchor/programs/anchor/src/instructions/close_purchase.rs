@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    archive::{PaymentCancelled, PurchaseArchive, PurchaseClosed},
+    escrow::{Escrow, EscrowStatus},
+    order::Order,
+    payment::{Payment, PaymentStatus, PurchaseList},
+};
+use crate::utils::close_vault;
+
+#[derive(Accounts)]
+pub struct ClosePurchase<'info>{
+    /// Anyone; pays for the archive record when one is written.
+    #[account(mut)]
+    pub authority:Signer<'info>,
+
+    #[account(mut, address = payment.rent_payer)]
+    pub rent_payer:SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    ///CHECK: The payment's order PDA. Always required so it can't be left
+    /// behind; closed along with the rest if it has been created.
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump,
+    )]
+    pub order:UncheckedAccount<'info>,
+
+    /// Pass to keep a compact record of the purchase.
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"archive",payment.key().as_ref()],
+        bump,
+        space = 8 + PurchaseArchive::INIT_SPACE,
+    )]
+    pub archive:Option<Account<'info,PurchaseArchive>>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPayment<'info>{
+    /// Receives the rent of an unfunded escrow and its vault.
+    #[account(mut)]
+    pub owner:Signer<'info>,
+
+    #[account(mut, address = payment.rent_payer)]
+    pub rent_payer:SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"payment",owner.key().as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_list",owner.key().as_ref()],
+        bump = purchase_list.purchase_list_bump,
+    )]
+    pub purchase_list:Account<'info,PurchaseList>,
+
+    ///CHECK: The payment's escrow PDA; closed along with its vault if it
+    /// has been created but never funded.
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump,
+    )]
+    pub escrow:UncheckedAccount<'info>,
+
+    ///CHECK: The payment's order PDA, closed along with the payment if it
+    /// has been created.
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump,
+    )]
+    pub order:UncheckedAccount<'info>,
+
+    /// Required together with `mint` and `token_program` once the escrow
+    /// has been created.
+    #[account(
+        mut,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
+    #[account(mut)]
+    pub mint:Option<Box<InterfaceAccount<'info,Mint>>>,
+    pub token_program:Option<Interface<'info,TokenInterface>>,
+}
+
+/// Hands a program account's lamports to `destination` and wipes it, for
+/// accounts not loaded as `Account` and so not closable with `close`.
+fn close_account<'info>(account:&AccountInfo<'info>, destination:&AccountInfo<'info>) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(EcomError::MathOverflow)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+impl<'info> CancelPayment<'info> {
+    /// Lets the buyer drop a checkout they never funded, freeing its slot in
+    /// their purchase list. Coupon uses and points redeemed for it are not
+    /// given back.
+    pub fn cancel_payment(&mut self, escrow_bump:u8) -> Result<()> {
+        let payment = &self.payment;
+        require!(
+            payment.payment_status == PaymentStatus::Pending && payment.payment_plan.is_none(),
+            EcomError::PaymentNotCancellable
+        );
+
+        if !self.escrow.data_is_empty() {
+            require_keys_eq!(*self.escrow.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+            let escrow = {
+                let data = self.escrow.try_borrow_data()?;
+                Escrow::try_deserialize(&mut &data[..])?
+            };
+            require!(
+                escrow.escrow_status == EscrowStatus::SwapPending && escrow.deposited_amount == 0,
+                EcomError::PaymentNotCancellable
+            );
+            let (Some(escrow_ata), Some(mint), Some(token_program)) =
+                (&self.escrow_ata, &self.mint, &self.token_program)
+            else {
+                return err!(EcomError::EscrowAccountsRequired);
+            };
+            require_keys_eq!(mint.key(), escrow.mint, EcomError::InvalidMint);
+            require_keys_eq!(escrow_ata.mint, escrow.mint, EcomError::EscrowVaultMintMismatch);
+
+            let payment_key = payment.key();
+            let seeds: &[&[u8]] = &[b"escrow", payment_key.as_ref(), &[escrow_bump]];
+            close_vault(
+                token_program.to_account_info(),
+                escrow_ata.to_account_info(),
+                mint.to_account_info(),
+                self.owner.to_account_info(),
+                self.escrow.to_account_info(),
+                &[seeds],
+            )?;
+            close_account(&self.escrow.to_account_info(), &self.owner.to_account_info())?;
+        }
+
+        if !self.order.data_is_empty() {
+            require_keys_eq!(*self.order.owner, crate::ID, EcomError::InvalidOrderAccount);
+            close_account(&self.order.to_account_info(), &self.rent_payer.to_account_info())?;
+        }
+        let payment_key = payment.key();
+        self.purchase_list.open_purchases.retain(|key| key != &payment_key);
+
+        emit!(PaymentCancelled{
+            payment: payment_key,
+            buyer: payment.owner,
+            rent_payer: self.rent_payer.key(),
+        });
+        Ok(())
+    }
+}
+
+impl<'info> ClosePurchase<'info> {
+    /// Closes a settled or refunded purchase, returning the rent of its
+    /// payment, escrow and order to whoever paid it.
+    pub fn close_purchase(
+        &mut self,
+        archive_bump:Option<u8>,
+    ) -> Result<()> {
+        let payment = &self.payment;
+        let escrow = &self.escrow;
+        require!(
+            escrow.deposited_amount == 0
+                && matches!(
+                    (&payment.payment_status, &escrow.escrow_status),
+                    (PaymentStatus::Success | PaymentStatus::PartiallyRefunded, EscrowStatus::SwapSuccess)
+                        | (PaymentStatus::Refunded, EscrowStatus::Refunded)
+                        | (PaymentStatus::Failed, EscrowStatus::TransferFailed)
+                ),
+            EcomError::PurchaseNotClosable
+        );
+
+        // The escrow's final state settles the purchase whatever the order
+        // says: the buyer may release before delivery, and a ruling for the
+        // seller leaves the order as it was.
+        let order = if self.order.data_is_empty() {
+            None
+        } else {
+            require_keys_eq!(*self.order.owner, crate::ID, EcomError::InvalidOrderAccount);
+            let data = self.order.try_borrow_data()?;
+            Some(Order::try_deserialize(&mut &data[..])?)
+        };
+
+        let archived = if let (Some(archive), Some(archive_bump)) = (self.archive.as_mut(), archive_bump) {
+            archive.set_inner(PurchaseArchive {
+                payment: payment.key(),
+                buyer: payment.owner,
+                seller: payment.seller,
+                product: payment.product_pubkey,
+                mint: payment.mint,
+                order_id: order.as_ref().map(|order| order.order_id),
+                amount: payment.payment_amount,
+                refunded_amount: payment.refunded_amount,
+                marketplace_fee: payment.marketplace_fee,
+                outcome: payment.payment_status.clone(),
+                created_at: payment.time_stamp,
+                closed_at: Clock::get()?.unix_timestamp,
+                archive_bump,
+            });
+            true
+        } else {
+            false
+        };
+
+        if order.is_some() {
+            close_account(&self.order.to_account_info(), &self.rent_payer.to_account_info())?;
+        }
+
+        emit!(PurchaseClosed{
+            payment: payment.key(),
+            rent_payer: self.rent_payer.key(),
+            archived,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::error::EcomError;
+use crate::states::coupon::{Coupon, CouponCreated, CouponDiscount, CouponScope};
+use crate::states::marketplace::Marketplace;
+use crate::utils::BPS_DENOMINATOR;
+
+#[derive(Accounts)]
+#[instruction(code_hash: [u8; 32])]
+pub struct CreateCoupon<'info>{
+    #[account(mut)]
+    pub creator:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"coupon",creator.key().as_ref(),code_hash.as_ref()],
+        bump,
+        space = 8 + Coupon::INIT_SPACE,
+    )]
+    pub coupon:Account<'info,Coupon>,
+    pub system_program:Program<'info,System>,
+}
+
+impl<'info> CreateCoupon<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_coupon(
+        &mut self,
+        code_hash:[u8;32],
+        discount:CouponDiscount,
+        scope:CouponScope,
+        expires_at:i64,
+        max_redemptions:u32,
+        per_wallet_cap:u16,
+        coupon_bump:u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let creator = self.creator.key();
+
+        let valid_discount = match discount {
+            CouponDiscount::Percentage { bps } => bps > 0 && (bps as u64) <= BPS_DENOMINATOR,
+            CouponDiscount::Fixed { amount } => amount > 0,
+        };
+        require!(
+            valid_discount
+                && expires_at > now
+                && max_redemptions > 0
+                && per_wallet_cap > 0,
+            EcomError::InvalidCoupon
+        );
+
+        // Sellers can only discount their own listings.
+        let seller = if creator == self.marketplace.admin { None } else { Some(creator) };
+
+        self.coupon.set_inner(Coupon {
+            code_hash,
+            creator,
+            seller,
+            discount,
+            scope,
+            expires_at,
+            max_redemptions,
+            redemptions: 0,
+            per_wallet_cap,
+            created_at: now,
+            coupon_bump,
+        });
+        emit!(CouponCreated{
+            coupon: self.coupon.key(),
+            creator,
+            expires_at,
+            max_redemptions,
+        });
+        Ok(())
+    }
+}
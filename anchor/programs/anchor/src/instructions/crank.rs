@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::instructions::{
+    BillSubscription, BillSubscriptionBumps, ExpireEscrow, ExpireEscrowBumps, WithdrawlEscrow,
+    WithdrawlEscrowBumps,
+};
+use crate::states::{
+    escrow::Escrow,
+    marketplace::{CrankRun, CrankTask, KeeperRewarded, Marketplace},
+    subscription::Subscription,
+};
+use crate::utils::transfer_tokens;
+
+#[derive(Accounts)]
+pub struct Crank<'info>{
+    #[account(mut)]
+    pub keeper:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Box<Account<'info,Marketplace>>,
+}
+
+/// What one task left for the keeper's reward: the marketplace fee it
+/// produced, and the mint it was in.
+struct TaskOutcome<'info> {
+    fee: u64,
+    mint: InterfaceAccount<'info, Mint>,
+    token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Crank<'info> {
+    /// Runs each task in `tasks` against the accounts in `accounts`, paying
+    /// the keeper `keeper_reward` for each out of the keeper vault of that
+    /// task's mint, capped by the marketplace fee the task produced and by
+    /// what the vault holds. Only releases produce a fee, so a keeper can't
+    /// farm rewards by expiring or billing escrows they set up themselves.
+    ///
+    /// Each task's accounts are the account it acts on, then the accounts of
+    /// the instruction that handles it, in that instruction's order with the
+    /// keeper as its signer, then the keeper vault and the keeper's token
+    /// account for the task's mint:
+    /// - `ExpireEscrow`: `expire_escrow`
+    /// - `BillSubscription`: `bill_subscription`
+    /// - `ReleaseEscrow`: `withdraw_escrow`, followed by the payout
+    ///   recipients' token accounts for products with a split table
+    ///
+    /// Carts hold no funds or stock, so they have no task here.
+    pub fn crank(
+        &mut self,
+        tasks:Vec<CrankTask>,
+        accounts:&'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(tasks.len() <= Marketplace::MAX_CRANK_TASKS, EcomError::TooManyCrankTasks);
+        let keeper = self.keeper.key();
+        let mut remaining = accounts;
+        let mut rewarded: u8 = 0;
+        for task in tasks {
+            let Some((target, rest)) = remaining.split_first() else {
+                return err!(EcomError::CrankTaskMismatch);
+            };
+            require_keys_eq!(*target.owner, crate::ID, EcomError::UnknownCrankTarget);
+            let discriminator = {
+                let data = target.try_borrow_data()?;
+                data.get(..8).map(|bytes| bytes.to_vec()).unwrap_or_default()
+            };
+            let expected: &[u8] = match task {
+                CrankTask::ExpireEscrow | CrankTask::ReleaseEscrow => Escrow::DISCRIMINATOR,
+                CrankTask::BillSubscription => Subscription::DISCRIMINATOR,
+            };
+            require!(discriminator == expected, EcomError::UnknownCrankTarget);
+            remaining = rest;
+
+            let outcome = match task {
+                CrankTask::ExpireEscrow => Self::expire(keeper, target, &mut remaining)?,
+                CrankTask::BillSubscription => Self::bill(keeper, target, &mut remaining)?,
+                CrankTask::ReleaseEscrow => Self::release(keeper, target, &mut remaining)?,
+            };
+
+            let [keeper_vault, keeper_ata, rest @ ..] = remaining else {
+                return err!(EcomError::CrankTaskMismatch);
+            };
+            remaining = rest;
+            let reward = self.reward_keeper(keeper_vault, keeper_ata, &outcome)?;
+            if reward > 0 {
+                rewarded += 1;
+                emit!(KeeperRewarded{
+                    keeper,
+                    target: target.key(),
+                    mint: outcome.mint.key(),
+                    reward,
+                });
+            }
+        }
+        require!(remaining.is_empty(), EcomError::CrankTaskMismatch);
+
+        emit!(CrankRun{
+            keeper,
+            tasks: rewarded,
+        });
+        Ok(())
+    }
+
+    #[inline(never)]
+    fn expire(
+        keeper:Pubkey,
+        target:&AccountInfo<'info>,
+        remaining:&mut &'info [AccountInfo<'info>],
+    ) -> Result<TaskOutcome<'info>> {
+        let mut bumps = ExpireEscrowBumps::default();
+        let mut task = ExpireEscrow::try_accounts(
+            &crate::ID,
+            remaining,
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        require!(
+            task.escrow.key() == target.key() && task.caller.key() == keeper,
+            EcomError::CrankTaskMismatch
+        );
+        task.expire_escrow()?;
+        task.exit(&crate::ID)?;
+        Ok(TaskOutcome {
+            fee: 0,
+            mint: (*task.mint).clone(),
+            token_program: task.token_program.clone(),
+        })
+    }
+
+    #[inline(never)]
+    fn bill(
+        keeper:Pubkey,
+        target:&AccountInfo<'info>,
+        remaining:&mut &'info [AccountInfo<'info>],
+    ) -> Result<TaskOutcome<'info>> {
+        let mut bumps = BillSubscriptionBumps::default();
+        let mut task = BillSubscription::try_accounts(
+            &crate::ID,
+            remaining,
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        require!(
+            task.subscription.key() == target.key() && task.cranker.key() == keeper,
+            EcomError::CrankTaskMismatch
+        );
+        task.bill_subscription(bumps.payment, bumps.escrow, bumps.order, bumps.delegate)?;
+        task.exit(&crate::ID)?;
+        // The fee is only charged once the billed escrow is released.
+        Ok(TaskOutcome {
+            fee: 0,
+            mint: (*task.mint).clone(),
+            token_program: task.token_program.clone(),
+        })
+    }
+
+    #[inline(never)]
+    fn release(
+        keeper:Pubkey,
+        target:&AccountInfo<'info>,
+        remaining:&mut &'info [AccountInfo<'info>],
+    ) -> Result<TaskOutcome<'info>> {
+        let mut bumps = WithdrawlEscrowBumps::default();
+        let mut task = WithdrawlEscrow::try_accounts(
+            &crate::ID,
+            remaining,
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        require!(
+            task.escrow.key() == target.key() && task.authority.key() == keeper,
+            EcomError::CrankTaskMismatch
+        );
+        let splits = task.product.payout_splits.len();
+        require!(remaining.len() >= splits, EcomError::MissingPayoutAccounts);
+        let (payout_accounts, rest) = remaining.split_at(splits);
+        *remaining = rest;
+
+        let fee_before = task.payment.marketplace_fee;
+        task.withdrawl_escrow(bumps.escrow, bumps.receipt, payout_accounts)?;
+        task.exit(&crate::ID)?;
+        Ok(TaskOutcome {
+            fee: task.payment.marketplace_fee - fee_before,
+            mint: task.mint.clone(),
+            token_program: task.token_program.clone(),
+        })
+    }
+
+    /// Pays one task's reward from the keeper vault of its mint.
+    fn reward_keeper(
+        &self,
+        keeper_vault:&'info AccountInfo<'info>,
+        keeper_ata:&'info AccountInfo<'info>,
+        outcome:&TaskOutcome<'info>,
+    ) -> Result<u64> {
+        let mint = &outcome.mint;
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[b"keeper_vault", mint.key().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(keeper_vault.key(), vault_key, EcomError::InvalidKeeperVault);
+        let vault = InterfaceAccount::<TokenAccount>::try_from(keeper_vault)?;
+        let keeper_token = InterfaceAccount::<TokenAccount>::try_from(keeper_ata)?;
+        require_keys_eq!(keeper_token.mint, mint.key(), EcomError::UserTokenMintMismatch);
+        require_keys_eq!(keeper_token.owner, self.keeper.key(), EcomError::InvalidUserTokenAccount);
+
+        let reward = self.marketplace.keeper_reward
+            .min(outcome.fee)
+            .min(vault.amount);
+        if reward > 0 {
+            let seeds: &[&[u8]] = &[b"marketplace", &[self.marketplace.marketplace_bump]];
+            transfer_tokens(
+                outcome.token_program.to_account_info(),
+                keeper_vault.clone(),
+                mint.to_account_info(),
+                keeper_ata.clone(),
+                self.marketplace.to_account_info(),
+                reward,
+                mint.decimals,
+                &[seeds],
+            )?;
+        }
+        Ok(reward)
+    }
+}
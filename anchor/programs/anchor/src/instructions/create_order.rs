@@ -1,15 +1,23 @@
 use anchor_lang::prelude::*;
-use crate::states::{order::{Order, OrderStatus, OrderTracking}};
+use crate::error::EcomError;
+use crate::states::{order::{Order, OrderStatus, OrderTracking, OrderTrackingUpdated}, payment::Payment, payment_plan::PaymentPlan};
+use crate::utils::BPS_DENOMINATOR;
 use anchor_lang::solana_program::hash::{self};
 #[derive(Accounts)]
 pub struct CreateOrder<'info>{
     #[account(mut)]
     pub signer:Signer<'info>,
 
+    #[account(
+        seeds = [b"payment",signer.key().as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
     #[account(
         init,
         payer = signer,
-        seeds = [b"order",signer.key().as_ref()],
+        seeds = [b"order",payment.key().as_ref()],
         bump,
         space = 9 + Order::INIT_SPACE,
     )]
@@ -17,6 +25,30 @@ pub struct CreateOrder<'info>{
     pub system_program:Program<'info,System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateOrderTracking<'info>{
+    pub seller:Signer<'info>,
+
+    #[account(
+        constraint = payment.seller == seller.key() @ EcomError::SellerMismatch,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump = order.order_bump,
+    )]
+    pub order:Account<'info,Order>,
+
+    /// Required once the payment is on an instalment plan.
+    #[account(
+        seeds = [b"payment_plan",payment.key().as_ref()],
+        bump = payment_plan.payment_plan_bump,
+    )]
+    pub payment_plan:Option<Account<'info,PaymentPlan>>,
+}
+
 impl<'info> CreateOrder<'info> {
     pub fn create_order(
         &mut self,
@@ -26,7 +58,7 @@ impl<'info> CreateOrder<'info> {
         let clock = Clock::get()?;
 
         let seed_data = [
-            self.signer.key().as_ref(),
+            self.payment.key().as_ref(),
             &clock.unix_timestamp.to_le_bytes(),
         ].concat();
         
@@ -41,15 +73,52 @@ impl<'info> CreateOrder<'info> {
         .map_err(|_| ProgramError::InvalidInstructionData)?;
         
         self.order.set_inner(Order { 
-            order_id: order_id,
+            order_id,
+            payment: self.payment.key(),
             payment_id, 
             tracking_id, 
             order_status:OrderStatus::Placed, 
             order_tracking:OrderTracking::Booked, 
             created_at:clock.unix_timestamp, 
             updated_at:clock.unix_timestamp, 
+            delivered_at: None,
             order_bump,
         });
        Ok(()) 
     }
-}
\ No newline at end of file
+}
+
+impl<'info> UpdateOrderTracking<'info> {
+    pub fn update_order_tracking(
+        &mut self,
+        order_tracking:OrderTracking,
+    ) -> Result<()> {
+        let order = &mut self.order;
+        require!(
+            order.order_status == OrderStatus::Placed
+                && order_tracking > order.order_tracking,
+            EcomError::InvalidOrderTransition
+        );
+
+        // Instalment orders only ship once the marketplace's share is paid.
+        if order_tracking >= OrderTracking::InTransit && self.payment.payment_plan.is_some() {
+            let plan = self.payment_plan.as_ref().ok_or(EcomError::InstallmentThresholdNotMet)?;
+            let paid = (plan.paid_amount as u128) * (BPS_DENOMINATOR as u128);
+            let required = (plan.total_amount as u128) * (plan.release_share_bps as u128);
+            require!(paid >= required, EcomError::InstallmentThresholdNotMet);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        order.order_tracking = order_tracking;
+        order.updated_at = now;
+        if order_tracking == OrderTracking::Delivered {
+            order.delivered_at = Some(now);
+        }
+        emit!(OrderTrackingUpdated{
+            order: order.key(),
+            payment: self.payment.key(),
+            order_tracking,
+        });
+        Ok(())
+    }
+}
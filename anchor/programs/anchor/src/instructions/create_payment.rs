@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, Transfer};
-use crate::{error::EcomError, states::{escrow::{Escrow, EscrowStatus}, payment::{Payment, PaymentMethod, PaymentStatus}}};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Burn, Mint, MintTo, TokenAccount, TokenInterface};
+use crate::{error::EcomError, states::{escrow::{Escrow, EscrowSettled, EscrowStatus, MilestoneTerm}, coupon::{Coupon, CouponRedeemed, CouponRedemption}, loyalty::{LoyaltyProgram, PointsMinted, PointsRedeemed}, gift_card::{GiftCard, GiftCardDrawn}, receipt::Receipt, order::Order, payment::{Payment, PaymentMethod, PaymentStatus, PurchaseList}, cart::Cart, marketplace::{AcceptedMint, Marketplace}, PriceCurrency, Product}};
+use crate::utils::{amount_due, amount_with_fee, bps_of, close_vault, load_price, mint_receipt, plan_release, transfer_tokens, usd_cents_to_tokens, ReleaseAccounts};
 use anchor_lang::solana_program::hash::{self};
 
 
@@ -9,52 +11,169 @@ pub struct CreatePayment<'info>{
     #[account(mut)]
     pub signer:Signer<'info>,
 
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"purchase_list",signer.key().as_ref()],
+        bump,
+        space = 8 + PurchaseList::INIT_SPACE
+    )]
+    pub purchase_list:Account<'info,PurchaseList>,
+
     #[account(
         init,
         payer = signer,
-        seeds = [b"payment",signer.key().as_ref()],
+        seeds = [
+            b"payment",
+            signer.key().as_ref(),
+            &purchase_list.payment_count.to_le_bytes(),
+        ],
         bump,
         space = 8 + Payment::INIT_SPACE
     )]
     pub payments:Account<'info,Payment>,
+
+    pub product:Account<'info,Product>,
+
+    /// Optional cart entry for `product`; when given, its quantity is charged.
+    #[account(
+        seeds = [b"cart",signer.key().as_ref(),product.product_name.as_bytes()],
+        bump = cart.cart_bump,
+        constraint = cart.product_id == product.product_id @ EcomError::CartProductMismatch,
+    )]
+    pub cart:Option<Account<'info,Cart>>,
+
+    #[account(
+        constraint = product.price_currency == PriceCurrency::UsdCents || mint.key() == product.mint
+            @ EcomError::ProductMintMismatch,
+    )]
+    pub mint:InterfaceAccount<'info,Mint>,
+
+    /// Required for USD-priced products.
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Option<Account<'info,Marketplace>>,
+    #[account(
+        seeds = [b"accepted_mint",mint.key().as_ref()],
+        bump = accepted_mint.accepted_mint_bump,
+    )]
+    pub accepted_mint:Option<Account<'info,AcceptedMint>>,
+    ///CHECK: Must be the feed registered for `mint`, read by `load_price`
+    #[account(
+        constraint = accepted_mint.as_ref().is_some_and(|accepted| accepted.price_feed == price_feed.key())
+            @ EcomError::UntrustedPriceFeed,
+    )]
+    pub price_feed:Option<UncheckedAccount<'info>>,
+
+    /// Required together with `coupon_redemption` when a coupon code is given.
+    #[account(
+        mut,
+        seeds = [b"coupon",coupon.creator.as_ref(),coupon.code_hash.as_ref()],
+        bump = coupon.coupon_bump,
+    )]
+    pub coupon:Option<Box<Account<'info,Coupon>>>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            b"coupon_redemption",
+            coupon.as_ref().map(|coupon| coupon.key()).unwrap_or_default().as_ref(),
+            signer.key().as_ref(),
+        ],
+        bump,
+        space = 8 + CouponRedemption::INIT_SPACE,
+    )]
+    pub coupon_redemption:Option<Box<Account<'info,CouponRedemption>>>,
+
+    /// Required when loyalty points are burned for a discount.
+    #[account(
+        seeds = [b"loyalty"],
+        bump = loyalty.loyalty_bump,
+    )]
+    pub loyalty:Option<Box<Account<'info,LoyaltyProgram>>>,
+    #[account(
+        mut,
+        constraint = loyalty.as_ref().is_some_and(|loyalty| loyalty.points_mint == points_mint.key())
+            @ EcomError::PointsMintMismatch,
+    )]
+    pub points_mint:Option<Box<InterfaceAccount<'info,Mint>>>,
+    #[account(
+        mut,
+        token::mint = points_mint,
+        token::authority = signer,
+    )]
+    pub buyer_points:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
+    pub points_token_program:Option<Interface<'info,TokenInterface>>,
     pub system_program:Program<'info,System>
 }
 
 #[derive(Accounts)]
+#[instruction(buyer_pubkey: Pubkey, seller_pubkey: Pubkey)]
 pub struct CreateEscrow<'info>{
     #[account(mut)]
     pub owner:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",owner.key().as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+        constraint = payment.owner == buyer_pubkey @ EcomError::BuyerMismatch,
+    )]
+    pub payment:Account<'info,Payment>,
+
     #[account(
         init,
         payer = owner,
-        seeds = [b"escrow",owner.key().as_ref()],
+        seeds = [b"escrow",payment.key().as_ref()],
         bump,
         space = 8 + Escrow::INIT_SPACE,
     )]
     pub escrow: Account<'info,Escrow>,
 
     #[account(
-        mut,
-        seeds = [b"payment",owner.key().as_ref()],
-        bump,
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
     )]
-    pub payment:Account<'info,Payment>,
-
-    ///CHECK: User Token Account
-    #[account(mut)]
-    pub user_ata: AccountInfo<'info>,
-    ///CHECK: Escrow Token Account
-    #[account(mut)]
-    pub escrow_ata: AccountInfo<'info>,
-    ///CHECK: Buyer Token Account
-    #[account(mut)]
-    pub buyer_ata: AccountInfo<'info>,
-    ///CHECK: Seller Token Account
-    #[account(mut)]
-    pub seller_ata: AccountInfo<'info>,
+    pub marketplace:Box<Account<'info,Marketplace>>,
 
+    #[account(
+        mut,
+        constraint = user_ata.mint == mint.key() @ EcomError::UserTokenMintMismatch,
+        constraint = user_ata.owner == owner.key() @ EcomError::InvalidUserTokenAccount,
+    )]
+    pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Vault created here, with the escrow PDA as its authority. Anyone can
+    /// open an ATA, so one opened ahead of time is reused rather than
+    /// blocking the escrow.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == buyer_pubkey @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = seller_ata.mint == mint.key() @ EcomError::SellerTokenMintMismatch,
+        constraint = seller_ata.owner == seller_pubkey @ EcomError::InvalidSellerTokenAccount,
+    )]
+    pub seller_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        constraint = mint.key() == payment.mint @ EcomError::InvalidMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program:Program<'info,Token>,
+    pub token_program:Interface<'info,TokenInterface>,
+    pub associated_token_program:Program<'info,AssociatedToken>,
     pub system_program:Program<'info,System>
 }
 
@@ -65,67 +184,227 @@ pub struct CreateEscrow<'info>{
         pub owner: Signer<'info>,
         #[account(
             mut,
-            seeds = [b"escrow",owner.key().as_ref()],
-            bump,
+            seeds = [b"payment",owner.key().as_ref(),&payment.nonce.to_le_bytes()],
+            bump = payment.payment_bump,
         )]
-        pub escrow: Account<'info,Escrow>,
+        pub payment:Account<'info,Payment>,
 
         #[account(
             mut,
-            seeds = [b"payment",owner.key().as_ref()],
+            seeds = [b"escrow",payment.key().as_ref()],
             bump,
         )]
-        pub payment:Account<'info,Payment>,
+        pub escrow: Account<'info,Escrow>,
     
-        ///CHECK: User Token Account
-        #[account(mut)]
-        pub user_ata: AccountInfo<'info>,
-        ///CHECK: Escrow Token Account
-        #[account(mut)]
-        pub escrow_ata: AccountInfo<'info>,
-        ///CHECK: Buyer Token Account
-        #[account(mut)]
-        pub buyer_ata: AccountInfo<'info>,
-        ///CHECK: Seller Token Account
-        #[account(mut)]
-        pub seller_ata: AccountInfo<'info>,
+        #[account(
+            mut,
+            constraint = user_ata.mint == mint.key() @ EcomError::UserTokenMintMismatch,
+            constraint = user_ata.owner == owner.key() @ EcomError::InvalidUserTokenAccount,
+        )]
+        pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+            constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+        )]
+        pub escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+            constraint = buyer_ata.owner == escrow.buyer_pubkey @ EcomError::InvalidBuyerTokenAccount,
+        )]
+        pub buyer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = seller_ata.mint == mint.key() @ EcomError::SellerTokenMintMismatch,
+            constraint = seller_ata.owner == escrow.seller_pubkey @ EcomError::InvalidSellerTokenAccount,
+        )]
+        pub seller_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+        )]
+        pub mint: InterfaceAccount<'info, Mint>,
+
+        /// Only needed when part of the deposit is drawn from a gift card.
+        #[account(
+            mut,
+            seeds = [b"gift_card",gift_card.code_hash.as_ref()],
+            bump = gift_card.gift_card_bump,
+            constraint = gift_card.holder == Some(owner.key()) @ EcomError::GiftCardNotHolder,
+            constraint = gift_card.mint == mint.key() @ EcomError::GiftCardMintMismatch,
+        )]
+        pub gift_card:Option<Box<Account<'info,GiftCard>>>,
+        #[account(
+            mut,
+            constraint = gift_card.as_ref().is_some_and(|gift_card| gift_card_vault.owner == gift_card.key())
+                @ EcomError::GiftCardAccountsRequired,
+        )]
+        pub gift_card_vault:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
         pub system_program:Program<'info,System>,
-        pub token_program:Program<'info,Token>
+        pub token_program:Interface<'info,TokenInterface>
     }
 
     #[derive(Accounts)]
     pub struct WithdrawlEscrow<'info>{
+        /// The buyer confirming receipt, or anyone else (the seller, or a
+        /// keeper through `crank`) once the inspection window has passed.
         #[account(mut)]
-        pub owner: Signer<'info>,
+        pub authority: Signer<'info>,
+
+        /// The buyer; receives the vault's rent and the receipt token.
+        #[account(mut, address = payment.owner)]
+        pub owner: SystemAccount<'info>,
+
+        #[account(
+            mut,
+            seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+            bump = payment.payment_bump,
+        )]
+        pub payment:Account<'info,Payment>,
 
         #[account(
             mut,
-            seeds = [b"escrow",owner.key().as_ref()],
+            seeds = [b"escrow",payment.key().as_ref()],
             bump,
         )]
         pub escrow: Account<'info,Escrow>,
 
         #[account(
             mut,
-            seeds = [b"payment",owner.key().as_ref()],
+            seeds = [b"purchase_list",payment.owner.as_ref()],
+            bump = purchase_list.purchase_list_bump,
+        )]
+        pub purchase_list:Account<'info,PurchaseList>,
+
+        #[account(
+            seeds = [b"marketplace"],
+            bump = marketplace.marketplace_bump,
+        )]
+        pub marketplace:Account<'info,Marketplace>,
+
+        #[account(address = payment.product_pubkey)]
+        pub product:Account<'info,Product>,
+
+        ///CHECK: The seller's profile PDA, read for their fee tier. Always
+        /// required so a seller can't leave it out to skip a tier fee; may not
+        /// exist.
+        #[account(
+            seeds = [b"seller_profile",escrow.seller_pubkey.as_ref()],
             bump,
         )]
-        pub payment:Account<'info,Payment>,
+        pub seller_profile:UncheckedAccount<'info>,
 
-        ///CHECK: User Token Account
-        #[account(mut)]
-        pub user_ata: AccountInfo<'info>,
-        ///CHECK: Escrow Token Account
-        #[account(mut)]
-        pub escrow_ata: AccountInfo<'info>,
-        ///CHECK: Buyer Token Account
-        #[account(mut)]
-        pub buyer_ata: AccountInfo<'info>,
-        ///CHECK: Seller Token Account
-        #[account(mut)]
-        pub seller_ata: AccountInfo<'info>,
+        #[account(
+            mut,
+            constraint = user_ata.mint == mint.key() @ EcomError::UserTokenMintMismatch,
+            constraint = user_ata.owner == owner.key() @ EcomError::InvalidUserTokenAccount,
+        )]
+        pub user_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+            constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+        )]
+        pub escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+            constraint = buyer_ata.owner == escrow.buyer_pubkey @ EcomError::InvalidBuyerTokenAccount,
+        )]
+        pub buyer_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = seller_ata.mint == mint.key() @ EcomError::SellerTokenMintMismatch,
+            constraint = seller_ata.owner == escrow.seller_pubkey @ EcomError::InvalidSellerTokenAccount,
+        )]
+        pub seller_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+        #[account(
+            mut,
+            constraint = treasury_ata.mint == mint.key() @ EcomError::TreasuryTokenMintMismatch,
+            constraint = treasury_ata.owner == marketplace.treasury @ EcomError::InvalidTreasuryAccount,
+        )]
+        pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+        /// Required once the marketplace diverts part of its fee to keepers.
+        #[account(
+            mut,
+            seeds = [b"keeper_vault",mint.key().as_ref()],
+            bump,
+        )]
+        pub keeper_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+        #[account(
+            mut,
+            constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+        )]
+        pub mint: InterfaceAccount<'info, Mint>,
+
+        ///CHECK: The registration PDA of `mint`; only registered payment
+        /// mints earn loyalty points. May not exist.
+        #[account(
+            seeds = [b"accepted_mint",mint.key().as_ref()],
+            bump,
+        )]
+        pub accepted_mint:UncheckedAccount<'info>,
+        ///CHECK: The loyalty programme PDA. Always required so whoever
+        /// releases can't leave it out to skip the buyer's points; may not
+        /// exist.
+        #[account(
+            seeds = [b"loyalty"],
+            bump,
+        )]
+        pub loyalty:UncheckedAccount<'info>,
+        /// Required when this release earns points.
+        #[account(
+            mut,
+            seeds = [b"loyalty_mint"],
+            bump,
+        )]
+        pub points_mint:Option<Box<InterfaceAccount<'info,Mint>>>,
+        #[account(
+            init_if_needed,
+            payer = authority,
+            associated_token::mint = points_mint,
+            associated_token::authority = owner,
+            associated_token::token_program = points_token_program,
+        )]
+        pub buyer_points:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
+        pub points_token_program:Interface<'info,TokenInterface>,
+
+        /// Its ID is written to the receipt when the order has been placed.
+        /// Required when the seller releases after delivery.
+        #[account(
+            seeds = [b"order",payment.key().as_ref()],
+            bump = order.order_bump,
+        )]
+        pub order:Option<Box<Account<'info,Order>>>,
+        #[account(
+            init,
+            payer = authority,
+            seeds = [b"receipt",payment.key().as_ref()],
+            bump,
+            space = 8 + Receipt::INIT_SPACE,
+        )]
+        pub receipt:Box<Account<'info,Receipt>>,
+        #[account(
+            init,
+            payer = authority,
+            seeds = [b"receipt_mint",payment.key().as_ref()],
+            bump,
+            mint::decimals = 0,
+            mint::authority = receipt,
+            mint::token_program = token_program,
+        )]
+        pub receipt_mint:Box<InterfaceAccount<'info,Mint>>,
+        #[account(
+            init,
+            payer = authority,
+            associated_token::mint = receipt_mint,
+            associated_token::authority = owner,
+            associated_token::token_program = token_program,
+        )]
+        pub buyer_receipt_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+        pub associated_token_program:Program<'info,AssociatedToken>,
         pub system_program:Program<'info,System>,
-        pub token_program:Program<'info,Token>
+        pub token_program:Interface<'info,TokenInterface>
     }
 
 
@@ -133,15 +412,130 @@ pub struct CreateEscrow<'info>{
 impl<'info> CreatePayment<'info>{
     pub fn create_payment(
         &mut self,
-        payment_amount: u64,
-        product_pubkey:Pubkey,
-        tx_signature:Option<String>,
+        quantity: u64,
+        coupon_code:Option<String>,
+        redeem_points:u64,
         payment_bump:u8,
+        purchase_list_bump:u8,
+        coupon_redemption_bump:Option<u8>,
     ) -> Result<()> {
         let clock = Clock::get()?;
+        let nonce = self.purchase_list.payment_count;
+        let product = &self.product;
+
+        let quantity = match &self.cart {
+            Some(cart) => cart.quantity,
+            None => quantity,
+        };
+        require!(
+            quantity <= product.quantity as u64,
+            EcomError::InsufficientStock
+        );
+        let mut amount_in_product_currency = amount_due(
+            product.price as u64,
+            quantity,
+            product.discount_bps,
+            product.order_fee as u64,
+        )?;
+
+        // Coupons come off the amount in the product's own pricing unit,
+        // before any USD conversion.
+        let mut coupon_discount = 0;
+        if let Some(code) = coupon_code {
+            let (Some(coupon), Some(redemption), Some(redemption_bump)) =
+                (self.coupon.as_mut(), self.coupon_redemption.as_mut(), coupon_redemption_bump)
+            else {
+                return err!(EcomError::InvalidCouponCode);
+            };
+            require!(
+                hash::hash(code.as_bytes()).to_bytes() == coupon.code_hash,
+                EcomError::InvalidCouponCode
+            );
+            require!(
+                redemption.count < coupon.per_wallet_cap,
+                EcomError::CouponWalletLimit
+            );
+            coupon_discount = coupon.discount_for(
+                product,
+                amount_in_product_currency,
+                clock.unix_timestamp,
+            )?;
+            amount_in_product_currency -= coupon_discount;
+
+            coupon.redemptions += 1;
+            redemption.coupon = coupon.key();
+            redemption.buyer = self.signer.key();
+            redemption.count += 1;
+            redemption.coupon_redemption_bump = redemption_bump;
+            emit!(CouponRedeemed{
+                coupon: coupon.key(),
+                buyer: self.signer.key(),
+                payment: self.payments.key(),
+                discount: coupon_discount,
+            });
+        }
+
+        // Points are burned in whole units of discount; any remainder stays
+        // with the buyer.
+        let mut points_redeemed = 0;
+        if redeem_points > 0 {
+            let (Some(loyalty), Some(points_mint), Some(buyer_points), Some(points_token_program)) =
+                (&self.loyalty, &self.points_mint, &self.buyer_points, &self.points_token_program)
+            else {
+                return err!(EcomError::LoyaltyAccountsRequired);
+            };
+            let points_discount = (redeem_points / loyalty.points_per_unit).min(amount_in_product_currency);
+            points_redeemed = points_discount
+                .checked_mul(loyalty.points_per_unit)
+                .ok_or(EcomError::MathOverflow)?;
+            require!(points_redeemed > 0, EcomError::InsufficientPoints);
+            require!(buyer_points.amount >= points_redeemed, EcomError::InsufficientPoints);
+
+            token_interface::burn(
+                CpiContext::new(
+                    points_token_program.to_account_info(),
+                    Burn {
+                        mint: points_mint.to_account_info(),
+                        from: buyer_points.to_account_info(),
+                        authority: self.signer.to_account_info(),
+                    },
+                ),
+                points_redeemed,
+            )?;
+            amount_in_product_currency -= points_discount;
+            emit!(PointsRedeemed{
+                payment: self.payments.key(),
+                buyer: self.signer.key(),
+                points: points_redeemed,
+                discount: points_discount,
+            });
+        }
+
+        let payment_amount = match product.price_currency {
+            PriceCurrency::Token => amount_in_product_currency,
+            PriceCurrency::UsdCents => {
+                let (Some(marketplace), Some(_), Some(price_feed)) =
+                    (&self.marketplace, &self.accepted_mint, &self.price_feed)
+                else {
+                    return err!(EcomError::PriceFeedRequired);
+                };
+                let quote = load_price(
+                    &price_feed.to_account_info(),
+                    marketplace.max_price_age,
+                    marketplace.max_confidence_bps,
+                )?;
+                usd_cents_to_tokens(amount_in_product_currency, &quote, self.mint.decimals)?
+            }
+        };
+
+        require!(
+            self.purchase_list.open_purchases.len() < PurchaseList::MAX_OPEN_PURCHASES,
+            EcomError::PurchaseListFull
+        );
 
         let seed_data = [
             self.signer.key().as_ref(),
+            &nonce.to_le_bytes(),
             &clock.unix_timestamp.to_le_bytes(),
         ].concat();
         let hash = hash::hash(&seed_data);
@@ -151,16 +545,34 @@ impl<'info> CreatePayment<'info>{
 
         self.payments.set_inner(
             Payment { 
-                payment_id: payment_id, 
+                payment_id, 
+                owner: self.signer.key(),
+                nonce,
+                seller: product.seller_pubkey,
+                mint: self.mint.key(),
+                quantity,
                 payment_amount, 
-                product_pubkey, 
+                priced_amount: amount_in_product_currency,
+                coupon: self.coupon.as_ref().filter(|_| coupon_discount > 0).map(|coupon| coupon.key()),
+                coupon_discount,
+                points_redeemed,
+                refunded_amount: 0,
+                marketplace_fee: 0,
+                product_pubkey: product.key(), 
                 payment_method: PaymentMethod::SOL, 
                 payment_status: PaymentStatus::Pending, 
                 time_stamp: clock.unix_timestamp, 
-                tx_signature, 
+                funding: None,
+                payment_plan: None,
+                rent_payer: self.signer.key(),
                 payment_bump, 
             }
         );
+
+        let purchase_list = &mut self.purchase_list;
+        purchase_list.payment_count = nonce.checked_add(1).ok_or(EcomError::MathOverflow)?;
+        purchase_list.purchase_list_bump = purchase_list_bump;
+        purchase_list.open_purchases.push(self.payments.key());
         Ok(())
     }
 }
@@ -170,7 +582,7 @@ impl <'info> CreateEscrow<'info> {
         &mut self,
         buyer_pubkey:Pubkey,
         seller_pubkey:Pubkey,
-        amount:u64,
+        milestones:Vec<MilestoneTerm>,
         escrow_bump:u8,
     )->Result<()> {
         let clock = Clock::get()?;
@@ -180,15 +592,24 @@ impl <'info> CreateEscrow<'info> {
             self.payment.payment_status == PaymentStatus::Pending,
             EcomError::InvalidPayment
         );
+        require!(
+            seller_pubkey == self.payment.seller,
+            EcomError::SellerMismatch
+        );
+        let amount = self.payment.payment_amount;
 
         self.escrow.set_inner(Escrow { 
             owner: self.owner.key(), 
             buyer_pubkey, 
             seller_pubkey, 
+            mint: self.mint.key(),
             amount, 
-            release_fund: false, 
+            deposited_amount: 0,
             time_stamp: clock.unix_timestamp, 
             update_timestamp :clock.unix_timestamp, 
+            ship_by: clock.unix_timestamp.saturating_add(self.marketplace.shipping_window),
+            milestones: Escrow::milestone_schedule(&milestones)?,
+            released_amount: 0,
             escrow_status:EscrowStatus::SwapPending, 
             escrow_bump,
         });
@@ -200,76 +621,268 @@ impl <'info> CreateEscrow<'info> {
 impl <'info> DepositeEscrow<'info> {
     pub fn deposite_escrow(
         &mut self,
+        amount:u64,
+        gift_card_amount:u64,
         _escrow_bump:u8,
 
     )-> Result<()> {
         let payment = &mut self.payment;
         let escrow = &mut self.escrow;
-        let amount = payment.payment_amount;
         
         require!(
             payment.payment_status == PaymentStatus::Pending,
             EcomError::EscrowError
         );
-
-        let cpi_accounts = Transfer{
-            from:self.user_ata.to_account_info(),
-            to:self.escrow_ata.to_account_info(),
-            authority: self.owner.to_account_info(),
-        };
-        let cpi_programs = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new(
-            cpi_programs,
-            cpi_accounts,
+        require!(payment.payment_plan.is_none(), EcomError::PaymentPlanActive);
+        require!(
+            amount == escrow.amount && amount == payment.payment_amount,
+            EcomError::DepositAmountMismatch
         );
-        token::transfer(cpi_ctx, amount)?;
+        // Fails on a second deposit before any tokens move.
+        let escrow_key = escrow.key();
+        escrow.transition(escrow_key, EscrowStatus::FundsReceived)?;
+
+        // The vault has to end up holding enough to cover the fee charged again on release,
+        // so the buyer pays both hops and the seller receives the full payment amount.
+        let mint_info = self.mint.to_account_info();
+        let release_amount = amount_with_fee(&mint_info, amount)?;
+
+        // A gift card covers `gift_card_amount` of the payment, fees on its
+        // own hop included; the buyer pays whatever is left.
+        let mut gift_card_release = 0;
+        let mut gift_card_draw = 0;
+        if gift_card_amount > 0 {
+            let (Some(gift_card), Some(vault)) = (self.gift_card.as_mut(), &self.gift_card_vault) else {
+                return err!(EcomError::GiftCardAccountsRequired);
+            };
+            require!(gift_card_amount <= amount, EcomError::DepositAmountMismatch);
+            gift_card_release = amount_with_fee(&mint_info, gift_card_amount)?.min(release_amount);
+            gift_card_draw = amount_with_fee(&mint_info, gift_card_release)?;
+            require!(
+                gift_card_draw <= gift_card.balance,
+                EcomError::InsufficientGiftCardBalance
+            );
+
+            let code_hash = gift_card.code_hash;
+            let seeds: &[&[u8]] = &[
+                b"gift_card",
+                code_hash.as_ref(),
+                &[gift_card.gift_card_bump],
+            ];
+            transfer_tokens(
+                self.token_program.to_account_info(),
+                vault.to_account_info(),
+                mint_info.clone(),
+                self.escrow_ata.to_account_info(),
+                gift_card.to_account_info(),
+                gift_card_draw,
+                self.mint.decimals,
+                &[seeds],
+            )?;
+            gift_card.balance -= gift_card_draw;
+            emit!(GiftCardDrawn{
+                gift_card: gift_card.key(),
+                payment: payment.key(),
+                amount: gift_card_draw,
+                remaining: gift_card.balance,
+            });
+        }
+
+        let buyer_release = release_amount - gift_card_release;
+        let buyer_deposit = amount_with_fee(&mint_info, buyer_release)?;
+        if buyer_release > 0 {
+            transfer_tokens(
+                self.token_program.to_account_info(),
+                self.user_ata.to_account_info(),
+                mint_info.clone(),
+                self.escrow_ata.to_account_info(),
+                self.owner.to_account_info(),
+                buyer_deposit,
+                self.mint.decimals,
+                &[],
+            )?;
+        }
+        payment.record_funding(
+            self.escrow_ata.key(),
+            gift_card_draw + buyer_deposit,
+            release_amount,
+        )?;
 
         // payment.payment_status = PaymentStatus::Success;
-        escrow.escrow_status = EscrowStatus::FundsReceived;
-        escrow.release_fund = true;
+        escrow.deposited_amount = release_amount;
         Ok(())
     }
 }
 
 impl <'info> WithdrawlEscrow<'info> {
+    /// The buyer can confirm receipt and release at any time. Anyone else,
+    /// the seller or a keeper, can only release once the order is
+    /// `Delivered` and the marketplace's inspection window has passed.
+    ///
+    /// Products with a payout split table pay each recipient instead of
+    /// `seller_ata`; their token accounts are passed as remaining accounts
+    /// in table order.
     pub fn withdrawl_escrow(
         &mut self,
         escrow_bump:u8,
+        receipt_bump:u8,
+        payout_accounts:&'info [AccountInfo<'info>],
     )-> Result<()> {
         let payment = &mut self.payment;
         let escrow = &mut self.escrow;
-        let amount = payment.payment_amount;
+        // Whatever milestones haven't already paid out.
+        let amount = escrow.amount;
+        let total_paid = amount
+            .checked_add(escrow.released_amount)
+            .ok_or(EcomError::MathOverflow)?;
 
         require!(
-            payment.payment_status == PaymentStatus::Pending
+            (payment.payment_status == PaymentStatus::Pending
+                || payment.payment_status == PaymentStatus::PartiallyRefunded)
             && payment.payment_method == PaymentMethod::SOL,
             EcomError::InvalidPayment
         );
-        require!(escrow.release_fund == true,EcomError::FundsNotFound);
-        
-        let cpi_accounts = Transfer{
-            from:self.escrow_ata.to_account_info(),
-            to:self.seller_ata.to_account_info(),
-            authority: escrow.to_account_info(),
-        };
-        let cpi_programs = self.token_program.to_account_info();
-        let owner_key = self.owner.key();
+        require!(escrow.escrow_status != EscrowStatus::Disputed, EcomError::EscrowDisputed);
+        let escrow_key = escrow.key();
+        escrow.transition(escrow_key, EscrowStatus::SwapSuccess)?;
+
+        // Anyone but the buyer, the seller or a keeper, has to wait out the
+        // inspection window after delivery.
+        if self.authority.key() != self.owner.key() {
+            let delivered_at = self.order
+                .as_ref()
+                .and_then(|order| order.delivered_at)
+                .ok_or(EcomError::OrderNotDelivered)?;
+            require!(
+                Clock::get()?.unix_timestamp >= delivered_at.saturating_add(self.marketplace.inspection_period),
+                EcomError::InspectionPeriodActive
+            );
+        }
+
+        // Recipients are paid first; the treasury takes whatever is left in
+        // the vault, so transfer-fee rounding never leaves dust behind.
+        let mint_info = self.mint.to_account_info();
+        let plan = plan_release(
+            &self.marketplace,
+            &self.product,
+            &self.seller_profile.to_account_info(),
+            ReleaseAccounts {
+                seller: escrow.seller_pubkey,
+                seller_ata: self.seller_ata.to_account_info(),
+                payout_accounts,
+                keeper_vault: self.keeper_vault.as_ref().map(|vault| vault.to_account_info()),
+                treasury_ata: self.treasury_ata.to_account_info(),
+            },
+            &mint_info,
+            amount,
+            self.escrow_ata.amount,
+            true,
+        )?;
+
+        let payment_key = payment.key();
         let seeds: &[&[u8]] = &[
             b"escrow",
-            owner_key.as_ref(),
+            payment_key.as_ref(),
             &[escrow_bump],
         ];
-        let signer_seeds = &[&seeds[..]];
-        let cpi_ctx = CpiContext::new_with_signer(
-            cpi_programs,
-            cpi_accounts,
+        let signer_seeds = &[seeds];
+        plan.execute(
+            &self.token_program.to_account_info(),
+            &self.escrow_ata.to_account_info(),
+            &mint_info,
+            &escrow.to_account_info(),
+            self.mint.decimals,
             signer_seeds,
-        );
-        token::transfer(cpi_ctx, amount)?;
+            payment_key,
+        )?;
+
+        close_vault(
+            self.token_program.to_account_info(),
+            self.escrow_ata.to_account_info(),
+            self.mint.to_account_info(),
+            self.owner.to_account_info(),
+            escrow.to_account_info(),
+            signer_seeds,
+        )?;
 
         payment.payment_status = PaymentStatus::Success;
-        escrow.escrow_status = EscrowStatus::SwapSuccess;
-        escrow.release_fund = false;
+        payment.marketplace_fee += plan.fee_amount;
+        escrow.released_amount = total_paid;
+        escrow.amount = 0;
+        // The final milestone settles here rather than in `release_milestones`.
+        let settled_at = escrow.update_timestamp;
+        if let Some(last) = escrow.milestones.last_mut() {
+            last.released_amount = amount;
+            last.released_at = Some(settled_at);
+        }
+        escrow.deposited_amount = 0;
+        self.purchase_list.open_purchases.retain(|key| key != &payment_key);
+
+        // Points accrue in the product's pricing unit, so a USD price earns
+        // the same whichever registered mint paid for it.
+        if let Some(loyalty) = LoyaltyProgram::load(&self.loyalty)? {
+            let points = if AcceptedMint::is_registered(&self.accepted_mint) {
+                bps_of(
+                    payment.priced_share(total_paid)?,
+                    loyalty.rate_bps_for(&self.product.category),
+                )?
+            } else {
+                0
+            };
+            if points > 0 {
+                let (Some(points_mint), Some(buyer_points)) = (&self.points_mint, &self.buyer_points) else {
+                    return err!(EcomError::LoyaltyAccountsRequired);
+                };
+                let loyalty_seeds: &[&[u8]] = &[b"loyalty", &[loyalty.loyalty_bump]];
+                token_interface::mint_to(
+                    CpiContext::new_with_signer(
+                        self.points_token_program.to_account_info(),
+                        MintTo {
+                            mint: points_mint.to_account_info(),
+                            to: buyer_points.to_account_info(),
+                            authority: self.loyalty.to_account_info(),
+                        },
+                        &[loyalty_seeds],
+                    ),
+                    points,
+                )?;
+                emit!(PointsMinted{
+                    payment: payment_key,
+                    buyer: escrow.buyer_pubkey,
+                    points,
+                });
+            }
+        }
+
+        mint_receipt(
+            &mut self.receipt,
+            &self.receipt_mint,
+            &self.buyer_receipt_ata,
+            self.token_program.to_account_info(),
+            Receipt {
+                payment: payment_key,
+                mint: self.receipt_mint.key(),
+                buyer: escrow.buyer_pubkey,
+                seller: escrow.seller_pubkey,
+                product: self.product.key(),
+                order_id: self.order.as_ref().map(|order| order.order_id),
+                amount: total_paid,
+                settled_at: Clock::get()?.unix_timestamp,
+                receipt_bump,
+            },
+        )?;
+
+        emit!(EscrowSettled{
+            payment: payment_key,
+            escrow: escrow.key(),
+            seller: escrow.seller_pubkey,
+            amount,
+            seller_amount: plan.seller_amount,
+            fee_amount: plan.fee_amount,
+            fee_bps: plan.fee_bps,
+            treasury: self.marketplace.treasury,
+            released_by: self.authority.key(),
+        });
         Ok(())
     }
 }
\ No newline at end of file
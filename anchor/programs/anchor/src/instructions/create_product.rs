@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::states::{Category, Division, Product, ProductCreated, ProductsList, Stock};
+use anchor_spl::token_interface::Mint;
+use crate::error::EcomError;
+use crate::states::{Category, Division, PayoutSplit, PayoutSplitsUpdated, PriceCurrency, Product, ProductCreated, ProductPricingUpdated, ProductsList, Stock};
+use crate::utils::BPS_DENOMINATOR;
 use anchor_lang::solana_program::hash::{self};
 use anchor_lang::solana_program::program_error::ProgramError;
 
@@ -27,10 +30,26 @@ pub struct CreateProduct<'info> {
         space = 8 + ProductsList::INIT_SPACE
     )]
     pub product_list: Account<'info, ProductsList>,
+
+    /// Token `price` is quoted in.
+    pub mint: InterfaceAccount<'info, Mint>,
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateProductPricing<'info> {
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"product", seller.key().as_ref(), product.product_name.as_bytes()],
+        bump = product.creation_bump,
+    )]
+    pub product: Account<'info, Product>,
+}
+
 impl <'info> CreateProduct<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_product(
         &mut self,
         product_name:String,
@@ -60,11 +79,16 @@ impl <'info> CreateProduct<'info> {
             category:category.clone() , 
             division:division.clone(), 
             quantity: 100, 
-            seller_pubkey:self.seller.key().clone(), 
+            seller_pubkey:self.seller.key(), 
             seller_name, 
             product_short_description, 
             product_imgurl, 
             price, 
+            price_currency: PriceCurrency::Token,
+            mint: self.mint.key(),
+            discount_bps: 0,
+            order_fee: 0,
+            payout_splits: Vec::new(),
             rating: 0.0, 
             stock_status: Stock::InStock,
             creation_bump  
@@ -93,4 +117,66 @@ impl <'info> CreateProduct<'info> {
     }
 }
 
+impl <'info> UpdateProductPricing<'info> {
+    pub fn update_product_pricing(
+        &mut self,
+        price:u32,
+        price_currency:PriceCurrency,
+        discount_bps:u16,
+        order_fee:u32,
+    ) -> Result<()> {
+        require!(
+            (discount_bps as u64) <= BPS_DENOMINATOR,
+            EcomError::InvalidBasisPoints
+        );
+        let product = &mut self.product;
+        product.price = price;
+        product.price_currency = price_currency;
+        product.discount_bps = discount_bps;
+        product.order_fee = order_fee;
+
+        emit!(ProductPricingUpdated{
+            product_pubkey: product.key(),
+            price,
+            price_currency,
+            discount_bps,
+            order_fee,
+        });
+        Ok(())
+    }
+}
+
+impl <'info> UpdateProductPricing<'info> {
+    /// Replaces the payout split table; an empty table pays the seller alone.
+    pub fn set_payout_splits(
+        &mut self,
+        payout_splits:Vec<PayoutSplit>,
+    ) -> Result<()> {
+        if !payout_splits.is_empty() {
+            require!(
+                payout_splits.len() <= Product::MAX_PAYOUT_SPLITS,
+                EcomError::InvalidPayoutSplits
+            );
+            require!(
+                payout_splits.iter().all(|split| split.share_bps > 0),
+                EcomError::InvalidPayoutSplits
+            );
+            let total_bps: u64 = payout_splits.iter().map(|split| split.share_bps as u64).sum();
+            require!(total_bps == BPS_DENOMINATOR, EcomError::InvalidPayoutSplits);
+            for (index, split) in payout_splits.iter().enumerate() {
+                require!(
+                    payout_splits[..index].iter().all(|other| other.recipient != split.recipient),
+                    EcomError::InvalidPayoutSplits
+                );
+            }
+        }
 
+        let product = &mut self.product;
+        product.payout_splits = payout_splits.clone();
+        emit!(PayoutSplitsUpdated{
+            product_pubkey: product.key(),
+            payout_splits,
+        });
+        Ok(())
+    }
+}
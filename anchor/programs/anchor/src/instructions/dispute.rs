@@ -0,0 +1,511 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    dispute::{
+        ArbiterVote, Dispute, DisputeEvidenceAdded, DisputeOpened, DisputeResolved, DisputeRuling,
+        DisputeStatus, DisputeVoteCast,
+    },
+    escrow::{Escrow, EscrowStatus},
+    marketplace::Marketplace,
+    order::Order,
+    payment::{Payment, PaymentStatus, PurchaseList},
+    receipt::Receipt,
+    seller::SellerProfile,
+    Product,
+};
+use crate::utils::{bps_of, close_vault, mint_receipt, plan_release, transfer_tokens, ReleaseAccounts};
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info>{
+    #[account(mut)]
+    pub buyer:Signer<'info>,
+
+    #[account(
+        seeds = [b"payment",buyer.key().as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [b"dispute",escrow.key().as_ref()],
+        bump,
+        space = 8 + Dispute::INIT_SPACE,
+    )]
+    pub dispute:Account<'info,Dispute>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct AddDisputeEvidence<'info>{
+    pub submitter:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute",dispute.escrow.as_ref()],
+        bump = dispute.dispute_bump,
+    )]
+    pub dispute:Account<'info,Dispute>,
+}
+
+/// Used both by the marketplace arbiter's ruling and by panel votes, since
+/// the vote that reaches the threshold settles the escrow.
+#[derive(Accounts)]
+pub struct ResolveDispute<'info>{
+    #[account(mut)]
+    pub arbiter:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Box<Account<'info,Marketplace>>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Box<Account<'info,Payment>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Box<Account<'info,Escrow>>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute",escrow.key().as_ref()],
+        bump = dispute.dispute_bump,
+    )]
+    pub dispute:Box<Account<'info,Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_list",payment.owner.as_ref()],
+        bump = purchase_list.purchase_list_bump,
+    )]
+    pub purchase_list:Box<Account<'info,PurchaseList>>,
+
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump = order.order_bump,
+    )]
+    pub order:Option<Box<Account<'info,Order>>>,
+
+    #[account(address = payment.product_pubkey)]
+    pub product:Box<Account<'info,Product>>,
+
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        seeds = [b"seller_profile",escrow.seller_pubkey.as_ref()],
+        bump,
+        space = 8 + SellerProfile::INIT_SPACE,
+    )]
+    pub seller_profile:Box<Account<'info,SellerProfile>>,
+
+    /// Receives the vault's rent.
+    #[account(mut, address = escrow.owner)]
+    pub buyer:SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == escrow.owner @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = seller_ata.mint == mint.key() @ EcomError::SellerTokenMintMismatch,
+        constraint = seller_ata.owner == escrow.seller_pubkey @ EcomError::InvalidSellerTokenAccount,
+    )]
+    pub seller_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = treasury_ata.mint == mint.key() @ EcomError::TreasuryTokenMintMismatch,
+        constraint = treasury_ata.owner == marketplace.treasury @ EcomError::InvalidTreasuryAccount,
+    )]
+    pub treasury_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    /// Required once the marketplace diverts part of its fee to keepers.
+    #[account(
+        mut,
+        seeds = [b"keeper_vault",mint.key().as_ref()],
+        bump,
+    )]
+    pub keeper_vault:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
+    #[account(
+        mut,
+        constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+    )]
+    pub mint:Box<InterfaceAccount<'info,Mint>>,
+
+    /// The buyer's receipt, required when the ruling releases anything to
+    /// the seller.
+    #[account(
+        init,
+        payer = arbiter,
+        seeds = [b"receipt",payment.key().as_ref()],
+        bump,
+        space = 8 + Receipt::INIT_SPACE,
+    )]
+    pub receipt:Option<Box<Account<'info,Receipt>>>,
+    #[account(
+        init,
+        payer = arbiter,
+        seeds = [b"receipt_mint",payment.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = receipt,
+        mint::token_program = token_program,
+    )]
+    pub receipt_mint:Option<Box<InterfaceAccount<'info,Mint>>>,
+    #[account(
+        init,
+        payer = arbiter,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program,
+    )]
+    pub buyer_receipt_ata:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
+    pub associated_token_program:Program<'info,AssociatedToken>,
+    pub token_program:Interface<'info,TokenInterface>,
+    pub system_program:Program<'info,System>,
+}
+
+impl<'info> OpenDispute<'info> {
+    pub fn open_dispute(
+        &mut self,
+        evidence_uri:String,
+        dispute_bump:u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut self.escrow;
+
+        require!(
+            escrow.deposited_amount > 0
+                && (escrow.escrow_status == EscrowStatus::FundsReceived
+                    || escrow.escrow_status == EscrowStatus::PartiallyRefunded),
+            EcomError::DisputeNotAllowed
+        );
+        require!(
+            evidence_uri.len() <= Dispute::MAX_EVIDENCE_URI_LEN,
+            EcomError::EvidenceLimitReached
+        );
+
+        let escrow_key = escrow.key();
+        escrow.transition(escrow_key, EscrowStatus::Disputed)?;
+
+        self.dispute.set_inner(Dispute {
+            escrow: escrow.key(),
+            payment: self.payment.key(),
+            buyer: self.buyer.key(),
+            seller: escrow.seller_pubkey,
+            buyer_evidence: vec![evidence_uri.clone()],
+            seller_evidence: Vec::new(),
+            dispute_status: DisputeStatus::Open,
+            ruling: None,
+            panel_required: self.marketplace.panel_required(escrow.amount),
+            votes: Vec::new(),
+            opened_at: now,
+            resolved_at: 0,
+            dispute_bump,
+        });
+        emit!(DisputeOpened{
+            dispute: self.dispute.key(),
+            escrow: escrow.key(),
+            buyer: self.buyer.key(),
+            seller: escrow.seller_pubkey,
+        });
+        emit!(DisputeEvidenceAdded{
+            dispute: self.dispute.key(),
+            submitter: self.buyer.key(),
+            uri: evidence_uri,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> AddDisputeEvidence<'info> {
+    pub fn add_dispute_evidence(
+        &mut self,
+        uri:String,
+    ) -> Result<()> {
+        let submitter = self.submitter.key();
+        let dispute = &mut self.dispute;
+        require!(dispute.dispute_status == DisputeStatus::Open, EcomError::DisputeNotOpen);
+
+        let evidence = if submitter == dispute.buyer {
+            &mut dispute.buyer_evidence
+        } else if submitter == dispute.seller {
+            &mut dispute.seller_evidence
+        } else {
+            return err!(EcomError::UnauthorizedEvidence);
+        };
+        require!(
+            evidence.len() < Dispute::MAX_EVIDENCE && uri.len() <= Dispute::MAX_EVIDENCE_URI_LEN,
+            EcomError::EvidenceLimitReached
+        );
+        evidence.push(uri.clone());
+
+        emit!(DisputeEvidenceAdded{
+            dispute: dispute.key(),
+            submitter,
+            uri,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> ResolveDispute<'info> {
+    /// A panel only decides while it has members; if the admin empties it,
+    /// its open disputes fall back to the single arbiter.
+    fn panel_decides(&self) -> bool {
+        self.dispute.panel_required && !self.marketplace.arbiter_panel.is_empty()
+    }
+
+    /// The marketplace arbiter's ruling on a dispute outside the panel's remit.
+    ///
+    /// Products with a payout split table take their recipients' token
+    /// accounts as remaining accounts, as on a normal release.
+    pub fn resolve_dispute(
+        &mut self,
+        ruling:DisputeRuling,
+        seller_profile_bump:u8,
+        receipt_bump:Option<u8>,
+        payout_accounts:&'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(self.dispute.dispute_status == DisputeStatus::Open, EcomError::DisputeNotOpen);
+        require!(!self.panel_decides(), EcomError::PanelRulingRequired);
+        require_keys_eq!(self.arbiter.key(), self.marketplace.arbiter, EcomError::UnauthorizedArbiter);
+        self.settle(ruling, seller_profile_bump, receipt_bump, payout_accounts)
+    }
+
+    /// Records a panel member's vote. Only votes from current members count,
+    /// and the first ruling to reach the threshold is carried out at once.
+    pub fn vote_dispute(
+        &mut self,
+        ruling:DisputeRuling,
+        seller_profile_bump:u8,
+        receipt_bump:Option<u8>,
+        payout_accounts:&'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(self.dispute.dispute_status == DisputeStatus::Open, EcomError::DisputeNotOpen);
+        require!(self.panel_decides(), EcomError::PanelVoteNotAllowed);
+        ruling.buyer_bps()?;
+
+        let arbiter = self.arbiter.key();
+        let panel = &self.marketplace.arbiter_panel;
+        require!(panel.contains(&arbiter), EcomError::UnauthorizedArbiter);
+        let dispute = &mut self.dispute;
+        dispute.votes.retain(|vote| panel.contains(&vote.arbiter));
+        require!(
+            dispute.votes.iter().all(|vote| vote.arbiter != arbiter),
+            EcomError::AlreadyVoted
+        );
+        dispute.votes.push(ArbiterVote {
+            arbiter,
+            ruling,
+            voted_at: Clock::get()?.unix_timestamp,
+        });
+
+        let matching_votes = dispute.votes.iter().filter(|vote| vote.ruling == ruling).count() as u8;
+        let threshold = self.marketplace.panel_threshold;
+        emit!(DisputeVoteCast{
+            dispute: dispute.key(),
+            arbiter,
+            ruling,
+            matching_votes,
+            threshold,
+        });
+        if matching_votes >= threshold {
+            self.settle(ruling, seller_profile_bump, receipt_bump, payout_accounts)?;
+        }
+        Ok(())
+    }
+
+    /// Pays out the vault according to `ruling` and closes it. The buyer's
+    /// share is refunded as is; the seller's share is released like a normal
+    /// withdrawal, net of the marketplace fee and through any payout splits,
+    /// and earns the buyer a receipt.
+    fn settle(
+        &mut self,
+        ruling:DisputeRuling,
+        seller_profile_bump:u8,
+        receipt_bump:Option<u8>,
+        payout_accounts:&'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let dispute = &mut self.dispute;
+        let escrow = &mut self.escrow;
+
+        let buyer_bps = ruling.buyer_bps()?;
+        let held = self.escrow_ata.amount;
+        let buyer_amount = bps_of(held, buyer_bps)?;
+        let seller_amount = held - buyer_amount;
+        let buyer_share_of_order = bps_of(escrow.amount, buyer_bps)?;
+        let seller_share_of_order = escrow.amount - buyer_share_of_order;
+
+        let payment_key = self.payment.key();
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            payment_key.as_ref(),
+            &[escrow.escrow_bump],
+        ];
+        let signer_seeds = &[seeds];
+        if buyer_amount > 0 {
+            transfer_tokens(
+                self.token_program.to_account_info(),
+                self.escrow_ata.to_account_info(),
+                self.mint.to_account_info(),
+                self.buyer_ata.to_account_info(),
+                escrow.to_account_info(),
+                buyer_amount,
+                self.mint.decimals,
+                signer_seeds,
+            )?;
+        }
+        let mut fee_amount = 0;
+        if seller_amount > 0 {
+            let mint_info = self.mint.to_account_info();
+            let plan = plan_release(
+                &self.marketplace,
+                &self.product,
+                &self.seller_profile.to_account_info(),
+                ReleaseAccounts {
+                    seller: escrow.seller_pubkey,
+                    seller_ata: self.seller_ata.to_account_info(),
+                    payout_accounts,
+                    keeper_vault: self.keeper_vault.as_ref().map(|vault| vault.to_account_info()),
+                    treasury_ata: self.treasury_ata.to_account_info(),
+                },
+                &mint_info,
+                seller_share_of_order,
+                seller_amount,
+                true,
+            )?;
+            plan.execute(
+                &self.token_program.to_account_info(),
+                &self.escrow_ata.to_account_info(),
+                &mint_info,
+                &escrow.to_account_info(),
+                self.mint.decimals,
+                signer_seeds,
+                payment_key,
+            )?;
+            fee_amount = plan.fee_amount;
+        }
+        close_vault(
+            self.token_program.to_account_info(),
+            self.escrow_ata.to_account_info(),
+            self.mint.to_account_info(),
+            self.buyer.to_account_info(),
+            escrow.to_account_info(),
+            signer_seeds,
+        )?;
+
+        let payment = &mut self.payment;
+        payment.refunded_amount = payment.refunded_amount
+            .checked_add(buyer_share_of_order)
+            .ok_or(EcomError::MathOverflow)?;
+        payment.marketplace_fee += fee_amount;
+        escrow.released_amount = escrow.released_amount
+            .checked_add(seller_share_of_order)
+            .ok_or(EcomError::MathOverflow)?;
+        escrow.amount = 0;
+        escrow.deposited_amount = 0;
+        let escrow_key = escrow.key();
+
+        let seller_profile = &mut self.seller_profile;
+        seller_profile.seller = escrow.seller_pubkey;
+        seller_profile.seller_profile_bump = seller_profile_bump;
+        match ruling {
+            DisputeRuling::RefundBuyer => {
+                escrow.transition(escrow_key, EscrowStatus::Refunded)?;
+                payment.payment_status = PaymentStatus::Refunded;
+                if let Some(order) = self.order.as_mut() {
+                    order.order_status = order.refunded_status();
+                    order.updated_at = now;
+                }
+                seller_profile.disputes_lost += 1;
+            }
+            DisputeRuling::ReleaseToSeller => {
+                escrow.transition(escrow_key, EscrowStatus::SwapSuccess)?;
+                payment.payment_status = PaymentStatus::Success;
+                seller_profile.disputes_won += 1;
+            }
+            DisputeRuling::Split { .. } => {
+                escrow.transition(escrow_key, EscrowStatus::SwapSuccess)?;
+                payment.payment_status = PaymentStatus::PartiallyRefunded;
+                seller_profile.disputes_split += 1;
+            }
+        }
+        self.purchase_list.open_purchases.retain(|key| key != &payment_key);
+
+        if ruling != DisputeRuling::RefundBuyer {
+            let (Some(receipt), Some(receipt_mint), Some(buyer_receipt_ata), Some(receipt_bump)) = (
+                self.receipt.as_mut(),
+                &self.receipt_mint,
+                &self.buyer_receipt_ata,
+                receipt_bump,
+            ) else {
+                return err!(EcomError::ReceiptAccountsRequired);
+            };
+            mint_receipt(
+                receipt,
+                receipt_mint,
+                buyer_receipt_ata,
+                self.token_program.to_account_info(),
+                Receipt {
+                    payment: payment_key,
+                    mint: receipt_mint.key(),
+                    buyer: escrow.buyer_pubkey,
+                    seller: escrow.seller_pubkey,
+                    product: self.product.key(),
+                    order_id: self.order.as_ref().map(|order| order.order_id),
+                    amount: escrow.released_amount,
+                    settled_at: now,
+                    receipt_bump,
+                },
+            )?;
+        }
+
+        dispute.dispute_status = DisputeStatus::Resolved;
+        dispute.ruling = Some(ruling);
+        dispute.resolved_at = now;
+        emit!(DisputeResolved{
+            dispute: dispute.key(),
+            escrow: escrow.key(),
+            ruling,
+            buyer_amount,
+            seller_amount,
+            arbiter: self.arbiter.key(),
+        });
+        Ok(())
+    }
+}
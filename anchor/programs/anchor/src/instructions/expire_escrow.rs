@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    escrow::{Escrow, EscrowExpired, EscrowStatus},
+    order::{Order, OrderStatus, OrderTracking},
+    payment::{Payment, PaymentStatus, PurchaseList},
+};
+use crate::utils::{close_vault, transfer_tokens};
+
+#[derive(Accounts)]
+pub struct ExpireEscrow<'info>{
+    /// Anyone; collects the vault's rent as a reward for the crank.
+    #[account(mut)]
+    pub caller:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_list",payment.owner.as_ref()],
+        bump = purchase_list.purchase_list_bump,
+    )]
+    pub purchase_list:Account<'info,PurchaseList>,
+
+    ///CHECK: The payment's order PDA. Always required so a caller can't skip
+    /// the shipping check by leaving it out; read only if it has been created.
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump,
+    )]
+    pub order:UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == escrow.owner @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+    )]
+    pub mint:Box<InterfaceAccount<'info,Mint>>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+impl<'info> ExpireEscrow<'info> {
+    /// Refunds everything still escrowed once the shipping deadline has
+    /// passed without the order shipping.
+    pub fn expire_escrow(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut self.escrow;
+
+        require!(
+            matches!(escrow.escrow_status, EscrowStatus::FundsReceived | EscrowStatus::PartiallyRefunded)
+                && escrow.deposited_amount > 0,
+            EcomError::EscrowNotExpirable
+        );
+        require!(now > escrow.ship_by, EcomError::ShippingDeadlineNotReached);
+
+        let mut order = if self.order.data_is_empty() {
+            None
+        } else {
+            require_keys_eq!(*self.order.owner, crate::ID, EcomError::InvalidOrderAccount);
+            let data = self.order.try_borrow_data()?;
+            Some(Order::try_deserialize(&mut &data[..])?)
+        };
+        if let Some(order) = order.as_ref() {
+            require!(order.order_tracking < OrderTracking::InTransit, EcomError::OrderAlreadyShipped);
+        }
+
+        let payment_key = self.payment.key();
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            payment_key.as_ref(),
+            &[escrow.escrow_bump],
+        ];
+        let refunded = escrow.amount;
+        let sent = self.escrow_ata.amount;
+        transfer_tokens(
+            self.token_program.to_account_info(),
+            self.escrow_ata.to_account_info(),
+            self.mint.to_account_info(),
+            self.buyer_ata.to_account_info(),
+            escrow.to_account_info(),
+            sent,
+            self.mint.decimals,
+            &[seeds],
+        )?;
+        let reward = self.escrow_ata.to_account_info().lamports();
+        close_vault(
+            self.token_program.to_account_info(),
+            self.escrow_ata.to_account_info(),
+            self.mint.to_account_info(),
+            self.caller.to_account_info(),
+            escrow.to_account_info(),
+            &[seeds],
+        )?;
+
+        escrow.amount = 0;
+        escrow.deposited_amount = 0;
+        let escrow_key = escrow.key();
+        escrow.transition(escrow_key, EscrowStatus::Refunded)?;
+        let payment = &mut self.payment;
+        payment.refunded_amount = payment.refunded_amount
+            .checked_add(refunded)
+            .ok_or(EcomError::MathOverflow)?;
+        payment.payment_status = PaymentStatus::Refunded;
+        if let Some(order) = order.as_mut() {
+            order.order_status = OrderStatus::Failed;
+            order.updated_at = now;
+            let mut data = self.order.try_borrow_mut_data()?;
+            order.try_serialize(&mut &mut data[..])?;
+        }
+        self.purchase_list.open_purchases.retain(|key| key != &payment_key);
+
+        emit!(EscrowExpired{
+            payment: payment_key,
+            escrow: escrow.key(),
+            caller: self.caller.key(),
+            refunded,
+            reward,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::gift_card::{GiftCard, GiftCardIssued, GiftCardTransferred};
+use crate::utils::{amount_with_fee, transfer_tokens};
+
+#[derive(Accounts)]
+#[instruction(code_hash: [u8; 32])]
+pub struct CreateGiftCard<'info>{
+    #[account(mut)]
+    pub issuer:Signer<'info>,
+
+    #[account(
+        init,
+        payer = issuer,
+        seeds = [b"gift_card",code_hash.as_ref()],
+        bump,
+        space = 8 + GiftCard::INIT_SPACE,
+    )]
+    pub gift_card:Account<'info,GiftCard>,
+
+    #[account(
+        init,
+        payer = issuer,
+        seeds = [b"gift_card_vault",gift_card.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = gift_card,
+        token::token_program = token_program,
+    )]
+    pub vault:InterfaceAccount<'info,TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = issuer_ata.mint == mint.key() @ EcomError::UserTokenMintMismatch,
+    )]
+    pub issuer_ata:InterfaceAccount<'info,TokenAccount>,
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub system_program:Program<'info,System>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemGiftCard<'info>{
+    /// Becomes the holder.
+    pub signer:Signer<'info>,
+
+    #[account(
+        constraint = gift_card.claim_key == Some(claim_authority.key()) @ EcomError::InvalidGiftCardSecret,
+    )]
+    pub claim_authority:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"gift_card",gift_card.code_hash.as_ref()],
+        bump = gift_card.gift_card_bump,
+    )]
+    pub gift_card:Account<'info,GiftCard>,
+}
+
+#[derive(Accounts)]
+pub struct TransferGiftCard<'info>{
+    pub signer:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"gift_card",gift_card.code_hash.as_ref()],
+        bump = gift_card.gift_card_bump,
+    )]
+    pub gift_card:Account<'info,GiftCard>,
+}
+
+impl<'info> CreateGiftCard<'info> {
+    /// Funds the card with `amount`; the issuer covers any transfer fee so
+    /// the vault holds the full balance.
+    pub fn create_gift_card(
+        &mut self,
+        code_hash:[u8;32],
+        amount:u64,
+        holder:Option<Pubkey>,
+        claim_key:Option<Pubkey>,
+        gift_card_bump:u8,
+        vault_bump:u8,
+    ) -> Result<()> {
+        require!(amount > 0, EcomError::InvalidGiftCard);
+        require!(holder.is_some() != claim_key.is_some(), EcomError::InvalidGiftCardClaim);
+
+        let mint_info = self.mint.to_account_info();
+        transfer_tokens(
+            self.token_program.to_account_info(),
+            self.issuer_ata.to_account_info(),
+            mint_info.clone(),
+            self.vault.to_account_info(),
+            self.issuer.to_account_info(),
+            amount_with_fee(&mint_info, amount)?,
+            self.mint.decimals,
+            &[],
+        )?;
+
+        self.gift_card.set_inner(GiftCard {
+            code_hash,
+            issuer: self.issuer.key(),
+            holder,
+            claim_key,
+            mint: self.mint.key(),
+            balance: amount,
+            created_at: Clock::get()?.unix_timestamp,
+            gift_card_bump,
+            vault_bump,
+        });
+        emit!(GiftCardIssued{
+            gift_card: self.gift_card.key(),
+            issuer: self.issuer.key(),
+            holder,
+            amount,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> RedeemGiftCard<'info> {
+    /// Claims a bearer card with the signature of its claim key.
+    pub fn redeem_gift_card(&mut self) -> Result<()> {
+        let gift_card = &mut self.gift_card;
+        require!(gift_card.holder.is_none(), EcomError::GiftCardAlreadyClaimed);
+        gift_card.holder = Some(self.signer.key());
+        gift_card.claim_key = None;
+        emit!(GiftCardTransferred{
+            gift_card: gift_card.key(),
+            from: None,
+            to: self.signer.key(),
+        });
+        Ok(())
+    }
+}
+
+impl<'info> TransferGiftCard<'info> {
+    pub fn transfer_gift_card(
+        &mut self,
+        new_holder:Pubkey,
+    ) -> Result<()> {
+        let gift_card = &mut self.gift_card;
+        require!(
+            gift_card.holder == Some(self.signer.key()),
+            EcomError::GiftCardNotHolder
+        );
+        gift_card.holder = Some(new_holder);
+        emit!(GiftCardTransferred{
+            gift_card: gift_card.key(),
+            from: Some(self.signer.key()),
+            to: new_holder,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use crate::error::EcomError;
+use crate::states::loyalty::{CategoryRate, LoyaltyProgram};
+use crate::states::marketplace::Marketplace;
+use crate::states::Category;
+use crate::utils::BPS_DENOMINATOR;
+
+#[derive(Accounts)]
+pub struct InitializeLoyalty<'info>{
+    #[account(mut)]
+    pub admin:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+        has_one = admin @ EcomError::Unauthorized,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"loyalty"],
+        bump,
+        space = 8 + LoyaltyProgram::INIT_SPACE,
+    )]
+    pub loyalty:Account<'info,LoyaltyProgram>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"loyalty_mint"],
+        bump,
+        mint::decimals = 0,
+        mint::authority = loyalty,
+        mint::token_program = token_program,
+    )]
+    pub points_mint:InterfaceAccount<'info,Mint>,
+    pub system_program:Program<'info,System>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLoyalty<'info>{
+    pub admin:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+        has_one = admin @ EcomError::Unauthorized,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    #[account(
+        mut,
+        seeds = [b"loyalty"],
+        bump = loyalty.loyalty_bump,
+    )]
+    pub loyalty:Account<'info,LoyaltyProgram>,
+}
+
+impl<'info> InitializeLoyalty<'info> {
+    pub fn initialize_loyalty(
+        &mut self,
+        points_per_unit:u64,
+        loyalty_bump:u8,
+        points_mint_bump:u8,
+    ) -> Result<()> {
+        require!(points_per_unit > 0, EcomError::InvalidLoyaltyConfig);
+        self.loyalty.set_inner(LoyaltyProgram {
+            points_mint: self.points_mint.key(),
+            points_per_unit,
+            category_rates: Vec::new(),
+            loyalty_bump,
+            points_mint_bump,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> UpdateLoyalty<'info> {
+    /// Sets or, with `None`, clears the points rate for a category.
+    pub fn set_loyalty_rate(
+        &mut self,
+        category:Category,
+        rate_bps:Option<u16>,
+    ) -> Result<()> {
+        let category_rates = &mut self.loyalty.category_rates;
+        category_rates.retain(|rate| rate.category != category);
+        if let Some(rate_bps) = rate_bps {
+            require!((rate_bps as u64) <= BPS_DENOMINATOR, EcomError::InvalidBasisPoints);
+            category_rates.push(CategoryRate { category, rate_bps });
+        }
+        Ok(())
+    }
+
+    pub fn set_points_value(
+        &mut self,
+        points_per_unit:u64,
+    ) -> Result<()> {
+        require!(points_per_unit > 0, EcomError::InvalidLoyaltyConfig);
+        self.loyalty.points_per_unit = points_per_unit;
+        Ok(())
+    }
+}
@@ -0,0 +1,370 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::marketplace::{AcceptedMint, ArbiterPanelUpdated, CategoryFee, Marketplace, PaymentMintRegistered, TierFee};
+use crate::states::seller::{SellerProfile, SellerTier};
+use crate::states::Category;
+use crate::states::oracle::MockPriceFeed;
+use crate::utils::BPS_DENOMINATOR;
+
+#[derive(Accounts)]
+pub struct InitializeMarketplace<'info>{
+    #[account(mut)]
+    pub admin:Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"marketplace"],
+        bump,
+        space = 8 + Marketplace::INIT_SPACE,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPaymentMint<'info>{
+    #[account(mut)]
+    pub admin:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+        has_one = admin @ EcomError::Unauthorized,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    pub mint:InterfaceAccount<'info,Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"accepted_mint",mint.key().as_ref()],
+        bump,
+        space = 8 + AcceptedMint::INIT_SPACE,
+    )]
+    pub accepted_mint:Account<'info,AcceptedMint>,
+
+    ///CHECK: Price feed quoting `mint` in USD, validated when it is read
+    pub price_feed:UncheckedAccount<'info>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct InitKeeperVault<'info>{
+    #[account(mut)]
+    pub admin:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+        has_one = admin @ EcomError::Unauthorized,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    pub mint:InterfaceAccount<'info,Mint>,
+
+    /// Keeper fee pool for `mint`, spendable only by the marketplace PDA.
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"keeper_vault",mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = marketplace,
+        token::token_program = token_program,
+    )]
+    pub keeper_vault:InterfaceAccount<'info,TokenAccount>,
+    pub token_program:Interface<'info,TokenInterface>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketplace<'info>{
+    pub admin:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+        has_one = admin @ EcomError::Unauthorized,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+}
+
+#[derive(Accounts)]
+#[instruction(seller: Pubkey)]
+pub struct SetSellerTier<'info>{
+    #[account(mut)]
+    pub admin:Signer<'info>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+        has_one = admin @ EcomError::Unauthorized,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [b"seller_profile",seller.as_ref()],
+        bump,
+        space = 8 + SellerProfile::INIT_SPACE,
+    )]
+    pub seller_profile:Account<'info,SellerProfile>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMockPrice<'info>{
+    #[account(mut)]
+    pub authority:Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"mock_price_feed",authority.key().as_ref()],
+        bump,
+        space = 8 + MockPriceFeed::INIT_SPACE,
+    )]
+    pub mock_price_feed:Account<'info,MockPriceFeed>,
+    pub system_program:Program<'info,System>,
+}
+
+impl<'info> InitializeMarketplace<'info> {
+    pub fn initialize_marketplace(
+        &mut self,
+        max_price_age:u64,
+        max_confidence_bps:u16,
+        marketplace_bump:u8,
+    ) -> Result<()> {
+        require!(
+            (max_confidence_bps as u64) <= BPS_DENOMINATOR,
+            EcomError::InvalidBasisPoints
+        );
+        self.marketplace.set_inner(Marketplace {
+            admin: self.admin.key(),
+            max_price_age,
+            max_confidence_bps,
+            installment_release_bps: BPS_DENOMINATOR as u16,
+            installment_grace_period: Marketplace::DEFAULT_INSTALLMENT_GRACE_PERIOD,
+            installment_default_fee_bps: 0,
+            treasury: self.admin.key(),
+            fee_bps: 0,
+            category_fees: Vec::new(),
+            tier_fees: Vec::new(),
+            inspection_period: Marketplace::DEFAULT_INSPECTION_PERIOD,
+            arbiter: self.admin.key(),
+            shipping_window: Marketplace::DEFAULT_SHIPPING_WINDOW,
+            arbiter_panel: Vec::new(),
+            panel_threshold: 0,
+            panel_min_amount: 0,
+            keeper_fee_bps: 0,
+            keeper_reward: 0,
+            marketplace_bump,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> RegisterPaymentMint<'info> {
+    pub fn register_payment_mint(
+        &mut self,
+        accepted_mint_bump:u8,
+    ) -> Result<()> {
+        self.accepted_mint.set_inner(AcceptedMint {
+            mint: self.mint.key(),
+            price_feed: self.price_feed.key(),
+            accepted_mint_bump,
+        });
+        emit!(PaymentMintRegistered{
+            mint: self.mint.key(),
+            price_feed: self.price_feed.key(),
+        });
+        Ok(())
+    }
+}
+
+impl<'info> UpdateMarketplace<'info> {
+    pub fn set_price_rules(
+        &mut self,
+        max_price_age:u64,
+        max_confidence_bps:u16,
+    ) -> Result<()> {
+        require!(
+            (max_confidence_bps as u64) <= BPS_DENOMINATOR,
+            EcomError::InvalidBasisPoints
+        );
+        let marketplace = &mut self.marketplace;
+        marketplace.max_price_age = max_price_age;
+        marketplace.max_confidence_bps = max_confidence_bps;
+        Ok(())
+    }
+
+    pub fn set_installment_rules(
+        &mut self,
+        release_bps:u16,
+        grace_period:i64,
+        default_fee_bps:u16,
+    ) -> Result<()> {
+        require!(
+            (release_bps as u64) <= BPS_DENOMINATOR
+                && (default_fee_bps as u64) <= BPS_DENOMINATOR,
+            EcomError::InvalidBasisPoints
+        );
+        require!(grace_period >= 0, EcomError::InvalidPaymentPlan);
+        let marketplace = &mut self.marketplace;
+        marketplace.installment_release_bps = release_bps;
+        marketplace.installment_grace_period = grace_period;
+        marketplace.installment_default_fee_bps = default_fee_bps;
+        Ok(())
+    }
+
+    pub fn set_marketplace_fee(
+        &mut self,
+        fee_bps:u16,
+        treasury:Pubkey,
+    ) -> Result<()> {
+        require!((fee_bps as u64) <= BPS_DENOMINATOR, EcomError::InvalidBasisPoints);
+        let marketplace = &mut self.marketplace;
+        marketplace.fee_bps = fee_bps;
+        marketplace.treasury = treasury;
+        Ok(())
+    }
+
+    pub fn set_inspection_period(
+        &mut self,
+        inspection_period:i64,
+    ) -> Result<()> {
+        require!(inspection_period >= 0, EcomError::InvalidInspectionPeriod);
+        self.marketplace.inspection_period = inspection_period;
+        Ok(())
+    }
+
+    pub fn set_shipping_window(
+        &mut self,
+        shipping_window:i64,
+    ) -> Result<()> {
+        require!(shipping_window > 0, EcomError::InvalidShippingWindow);
+        self.marketplace.shipping_window = shipping_window;
+        Ok(())
+    }
+
+    pub fn set_arbiter(
+        &mut self,
+        arbiter:Pubkey,
+    ) -> Result<()> {
+        self.marketplace.arbiter = arbiter;
+        Ok(())
+    }
+
+    pub fn set_keeper_rules(
+        &mut self,
+        keeper_fee_bps:u16,
+        keeper_reward:u64,
+    ) -> Result<()> {
+        require!((keeper_fee_bps as u64) <= BPS_DENOMINATOR, EcomError::InvalidBasisPoints);
+        let marketplace = &mut self.marketplace;
+        marketplace.keeper_fee_bps = keeper_fee_bps;
+        marketplace.keeper_reward = keeper_reward;
+        Ok(())
+    }
+
+    /// Replaces the arbiter panel. Open panel disputes count only votes from
+    /// the new members; an empty list hands every dispute back to `arbiter`.
+    pub fn set_arbiter_panel(
+        &mut self,
+        arbiters:Vec<Pubkey>,
+        threshold:u8,
+        min_amount:u64,
+    ) -> Result<()> {
+        let distinct = arbiters
+            .iter()
+            .enumerate()
+            .all(|(index, arbiter)| !arbiters[..index].contains(arbiter));
+        require!(
+            arbiters.len() <= Marketplace::MAX_PANEL_ARBITERS
+                && distinct
+                && (arbiters.is_empty() || (1..=arbiters.len()).contains(&(threshold as usize))),
+            EcomError::InvalidArbiterPanel
+        );
+        let marketplace = &mut self.marketplace;
+        marketplace.arbiter_panel = arbiters.clone();
+        marketplace.panel_threshold = if arbiters.is_empty() { 0 } else { threshold };
+        marketplace.panel_min_amount = min_amount;
+        emit!(ArbiterPanelUpdated{
+            arbiters,
+            threshold: marketplace.panel_threshold,
+            min_amount,
+        });
+        Ok(())
+    }
+
+    /// Sets or, with `None`, clears the fee override for a category.
+    pub fn set_category_fee(
+        &mut self,
+        category:Category,
+        fee_bps:Option<u16>,
+    ) -> Result<()> {
+        let category_fees = &mut self.marketplace.category_fees;
+        category_fees.retain(|fee| fee.category != category);
+        if let Some(fee_bps) = fee_bps {
+            require!((fee_bps as u64) <= BPS_DENOMINATOR, EcomError::InvalidBasisPoints);
+            category_fees.push(CategoryFee { category, fee_bps });
+        }
+        Ok(())
+    }
+
+    /// Sets or, with `None`, clears the fee override for a seller tier.
+    pub fn set_tier_fee(
+        &mut self,
+        tier:SellerTier,
+        fee_bps:Option<u16>,
+    ) -> Result<()> {
+        let tier_fees = &mut self.marketplace.tier_fees;
+        tier_fees.retain(|fee| fee.tier != tier);
+        if let Some(fee_bps) = fee_bps {
+            require!((fee_bps as u64) <= BPS_DENOMINATOR, EcomError::InvalidBasisPoints);
+            tier_fees.push(TierFee { tier, fee_bps });
+        }
+        Ok(())
+    }
+}
+
+impl<'info> SetSellerTier<'info> {
+    pub fn set_seller_tier(
+        &mut self,
+        seller:Pubkey,
+        tier:SellerTier,
+        seller_profile_bump:u8,
+    ) -> Result<()> {
+        let seller_profile = &mut self.seller_profile;
+        seller_profile.seller = seller;
+        seller_profile.tier = tier;
+        seller_profile.seller_profile_bump = seller_profile_bump;
+        Ok(())
+    }
+}
+
+impl<'info> SetMockPrice<'info> {
+    pub fn set_mock_price(
+        &mut self,
+        price:i64,
+        conf:u64,
+        expo:i32,
+        mock_price_feed_bump:u8,
+    ) -> Result<()> {
+        require!(cfg!(feature = "localnet"), EcomError::MockOracleDisabled);
+        self.mock_price_feed.set_inner(MockPriceFeed {
+            authority: self.authority.key(),
+            price,
+            conf,
+            expo,
+            publish_time: Clock::get()?.unix_timestamp,
+            mock_price_feed_bump,
+        });
+        Ok(())
+    }
+}
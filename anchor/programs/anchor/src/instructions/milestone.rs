@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    escrow::{Escrow, EscrowStatus, MilestoneReleased},
+    marketplace::Marketplace,
+    order::Order,
+    payment::Payment,
+    Product,
+};
+use crate::utils::{bps_of, plan_release, ReleaseAccounts};
+
+#[derive(Accounts)]
+pub struct ReleaseMilestones<'info>{
+    /// Anyone; the schedule and the order's tracking decide what is due.
+    pub caller:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Box<Account<'info,Payment>>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Box<Account<'info,Escrow>>,
+
+    #[account(
+        seeds = [b"order",payment.key().as_ref()],
+        bump = order.order_bump,
+    )]
+    pub order:Box<Account<'info,Order>>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Box<Account<'info,Marketplace>>,
+
+    #[account(address = payment.product_pubkey)]
+    pub product:Box<Account<'info,Product>>,
+
+    ///CHECK: The seller's profile PDA, read for their fee tier. Always
+    /// required so a seller can't leave it out to skip a tier fee; may not
+    /// exist.
+    #[account(
+        seeds = [b"seller_profile",escrow.seller_pubkey.as_ref()],
+        bump,
+    )]
+    pub seller_profile:UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = seller_ata.mint == mint.key() @ EcomError::SellerTokenMintMismatch,
+        constraint = seller_ata.owner == escrow.seller_pubkey @ EcomError::InvalidSellerTokenAccount,
+    )]
+    pub seller_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        mut,
+        constraint = treasury_ata.mint == mint.key() @ EcomError::TreasuryTokenMintMismatch,
+        constraint = treasury_ata.owner == marketplace.treasury @ EcomError::InvalidTreasuryAccount,
+    )]
+    pub treasury_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    /// Required once the marketplace diverts part of its fee to keepers.
+    #[account(
+        mut,
+        seeds = [b"keeper_vault",mint.key().as_ref()],
+        bump,
+    )]
+    pub keeper_vault:Option<Box<InterfaceAccount<'info,TokenAccount>>>,
+    #[account(
+        constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+    )]
+    pub mint:Box<InterfaceAccount<'info,Mint>>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+impl<'info> ReleaseMilestones<'info> {
+    /// Pays out every milestone the order's tracking has reached, except the
+    /// final one, which settles through `withdraw_escrow`. Fees and payout
+    /// splits apply to each tranche as they would to a full release.
+    pub fn release_milestones(
+        &mut self,
+        payout_accounts:&'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let payment = &mut self.payment;
+        let escrow = &mut self.escrow;
+
+        require!(escrow.escrow_status != EscrowStatus::Disputed, EcomError::EscrowDisputed);
+        require!(
+            matches!(escrow.escrow_status, EscrowStatus::FundsReceived | EscrowStatus::PartiallyRefunded),
+            EcomError::EscrowNotFunded
+        );
+
+        let tracking = self.order.order_tracking;
+        let final_index = escrow.milestones.len().saturating_sub(1);
+        // Tranches are shares of what the escrow actually holds for the order,
+        // after any discount or partial refund, not of the list price.
+        let unreleased = escrow.amount;
+        let escrowed = unreleased
+            .checked_add(escrow.released_amount)
+            .ok_or(EcomError::MathOverflow)?;
+        let mut released = Vec::new();
+        let mut amount: u64 = 0;
+        for (index, milestone) in escrow.milestones.iter_mut().enumerate() {
+            if index == final_index || milestone.released_at.is_some() || tracking < milestone.stage {
+                continue;
+            }
+            let tranche = bps_of(escrowed, milestone.release_bps)?
+                .min(unreleased - amount);
+            milestone.released_amount = tranche;
+            milestone.released_at = Some(now);
+            amount += tranche;
+            released.push((milestone.stage, tranche));
+        }
+        require!(!released.is_empty(), EcomError::NoMilestoneDue);
+
+        // Each hop is grossed up for transfer fees like a full release, and
+        // capped by what the vault still holds.
+        let mint_info = self.mint.to_account_info();
+        let plan = plan_release(
+            &self.marketplace,
+            &self.product,
+            &self.seller_profile.to_account_info(),
+            ReleaseAccounts {
+                seller: escrow.seller_pubkey,
+                seller_ata: self.seller_ata.to_account_info(),
+                payout_accounts,
+                keeper_vault: self.keeper_vault.as_ref().map(|vault| vault.to_account_info()),
+                treasury_ata: self.treasury_ata.to_account_info(),
+            },
+            &mint_info,
+            amount,
+            self.escrow_ata.amount,
+            false,
+        )?;
+
+        let payment_key = payment.key();
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            payment_key.as_ref(),
+            &[escrow.escrow_bump],
+        ];
+        plan.execute(
+            &self.token_program.to_account_info(),
+            &self.escrow_ata.to_account_info(),
+            &mint_info,
+            &escrow.to_account_info(),
+            self.mint.decimals,
+            &[seeds],
+            payment_key,
+        )?;
+
+        escrow.amount -= amount;
+        escrow.released_amount += amount;
+        escrow.deposited_amount = escrow.deposited_amount
+            .saturating_sub(plan.sent());
+        escrow.update_timestamp = now;
+        payment.marketplace_fee += plan.fee_amount;
+
+        for (stage, tranche) in released {
+            emit!(MilestoneReleased{
+                payment: payment_key,
+                escrow: escrow.key(),
+                stage,
+                amount: tranche,
+                fee_amount: bps_of(tranche, plan.fee_bps)?,
+                remaining: escrow.amount,
+            });
+        }
+        Ok(())
+    }
+}
@@ -2,8 +2,34 @@ pub mod create_product;
 pub mod add_to_cart;
 pub mod create_payment;
 pub mod create_order;
+pub mod marketplace;
+pub mod payment_plan;
+pub mod subscription;
+pub mod refund_escrow;
+pub mod coupon;
+pub mod loyalty;
+pub mod gift_card;
+pub mod relay;
+pub mod dispute;
+pub mod expire_escrow;
+pub mod milestone;
+pub mod close_purchase;
+pub mod crank;
 
 pub use create_product::*;
 pub use add_to_cart::*;
 pub use create_payment::*;
 pub use create_order::*;
+pub use marketplace::*;
+pub use payment_plan::*;
+pub use subscription::*;
+pub use refund_escrow::*;
+pub use coupon::*;
+pub use loyalty::*;
+pub use gift_card::*;
+pub use relay::*;
+pub use dispute::*;
+pub use expire_escrow::*;
+pub use milestone::*;
+pub use close_purchase::*;
+pub use crank::*;
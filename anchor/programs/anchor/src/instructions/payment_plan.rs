@@ -0,0 +1,360 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    escrow::{Escrow, EscrowStatus, RefundReason, Refunded},
+    marketplace::Marketplace,
+    order::{Order, OrderStatus},
+    payment::{Payment, PaymentStatus, PurchaseList},
+    payment_plan::{Installment, InstallmentPaid, PaymentPlan, PaymentPlanDefaulted, PlanStatus},
+};
+use crate::utils::{amount_with_fee, bps_of, close_vault, transfer_tokens};
+
+#[derive(Accounts)]
+pub struct CreatePaymentPlan<'info>{
+    #[account(mut)]
+    pub owner:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",owner.key().as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Account<'info,Marketplace>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"payment_plan",payment.key().as_ref()],
+        bump,
+        space = 8 + PaymentPlan::INIT_SPACE,
+    )]
+    pub payment_plan:Account<'info,PaymentPlan>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct PayInstallment<'info>{
+    #[account(mut)]
+    pub owner:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",owner.key().as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_plan",payment.key().as_ref()],
+        bump = payment_plan.payment_plan_bump,
+    )]
+    pub payment_plan:Account<'info,PaymentPlan>,
+
+    #[account(
+        mut,
+        constraint = user_ata.mint == mint.key() @ EcomError::UserTokenMintMismatch,
+        constraint = user_ata.owner == owner.key() @ EcomError::InvalidUserTokenAccount,
+    )]
+    pub user_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+    )]
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPaymentPlan<'info>{
+    pub seller:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+        constraint = payment.seller == seller.key() @ EcomError::SellerMismatch,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"payment_plan",payment.key().as_ref()],
+        bump = payment_plan.payment_plan_bump,
+    )]
+    pub payment_plan:Account<'info,PaymentPlan>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_list",payment.owner.as_ref()],
+        bump = purchase_list.purchase_list_bump,
+    )]
+    pub purchase_list:Account<'info,PurchaseList>,
+
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump = order.order_bump,
+    )]
+    pub order:Option<Account<'info,Order>>,
+
+    /// Receives the vault's rent once it is emptied.
+    #[account(mut, address = escrow.owner)]
+    pub buyer:SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == payment.owner @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        constraint = seller_ata.mint == mint.key() @ EcomError::SellerTokenMintMismatch,
+        constraint = seller_ata.owner == seller.key() @ EcomError::InvalidSellerTokenAccount,
+    )]
+    pub seller_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+    )]
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+impl<'info> CreatePaymentPlan<'info> {
+    pub fn create_payment_plan(
+        &mut self,
+        due_dates:Vec<i64>,
+        payment_plan_bump:u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let payment = &mut self.payment;
+
+        require!(
+            payment.payment_status == PaymentStatus::Pending
+                && payment.payment_plan.is_none(),
+            EcomError::InvalidPayment
+        );
+        require!(
+            self.escrow.escrow_status == EscrowStatus::SwapPending
+                && self.escrow.deposited_amount == 0,
+            EcomError::EscrowError
+        );
+        require!(
+            (2..=PaymentPlan::MAX_INSTALLMENTS).contains(&due_dates.len())
+                && due_dates[0] >= now
+                && due_dates.windows(2).all(|pair| pair[0] < pair[1]),
+            EcomError::InvalidPaymentPlan
+        );
+
+        // Equal instalments, with any rounding remainder due up front.
+        let total_amount = payment.payment_amount;
+        let count = due_dates.len() as u64;
+        let base = total_amount / count;
+        let remainder = total_amount % count;
+        let installments = due_dates
+            .iter()
+            .enumerate()
+            .map(|(index, due_at)| Installment {
+                amount: if index == 0 { base + remainder } else { base },
+                due_at: *due_at,
+                paid: false,
+            })
+            .collect();
+
+        let marketplace = &self.marketplace;
+        self.payment_plan.set_inner(PaymentPlan {
+            payment: payment.key(),
+            buyer: self.owner.key(),
+            seller: payment.seller,
+            total_amount,
+            paid_amount: 0,
+            installments,
+            release_share_bps: marketplace.installment_release_bps,
+            grace_period: marketplace.installment_grace_period,
+            default_fee_bps: marketplace.installment_default_fee_bps,
+            plan_status: PlanStatus::Active,
+            created_at: now,
+            payment_plan_bump,
+        });
+        payment.payment_plan = Some(self.payment_plan.key());
+        Ok(())
+    }
+}
+
+impl<'info> PayInstallment<'info> {
+    pub fn pay_installment(&mut self) -> Result<()> {
+        let plan = &mut self.payment_plan;
+        let escrow = &mut self.escrow;
+
+        require!(plan.plan_status == PlanStatus::Active, EcomError::PaymentPlanNotActive);
+        let index = plan.next_unpaid().ok_or(EcomError::PaymentPlanNotActive)?;
+        let amount = plan.installments[index].amount;
+
+        // Same two-hop fee handling as a full deposit.
+        let mint_info = self.mint.to_account_info();
+        let release_amount = amount_with_fee(&mint_info, amount)?;
+        let deposit_amount = amount_with_fee(&mint_info, release_amount)?;
+
+        transfer_tokens(
+            self.token_program.to_account_info(),
+            self.user_ata.to_account_info(),
+            mint_info,
+            self.escrow_ata.to_account_info(),
+            self.owner.to_account_info(),
+            deposit_amount,
+            self.mint.decimals,
+            &[],
+        )?;
+        self.payment.record_funding(self.escrow_ata.key(), deposit_amount, release_amount)?;
+
+        plan.installments[index].paid = true;
+        plan.paid_amount = plan.paid_amount
+            .checked_add(amount)
+            .ok_or(EcomError::MathOverflow)?;
+        escrow.deposited_amount = escrow.deposited_amount
+            .checked_add(release_amount)
+            .ok_or(EcomError::MathOverflow)?;
+        // Releasable only once every instalment is in.
+        let escrow_key = escrow.key();
+        if plan.next_unpaid().is_none() {
+            plan.plan_status = PlanStatus::Completed;
+            escrow.transition(escrow_key, EscrowStatus::FundsReceived)?;
+        } else {
+            escrow.transition(escrow_key, EscrowStatus::PartiallyFunded)?;
+        }
+
+        emit!(InstallmentPaid{
+            payment: self.payment.key(),
+            index: index as u8,
+            amount,
+            paid_amount: plan.paid_amount,
+        });
+        Ok(())
+    }
+}
+
+impl<'info> CancelPaymentPlan<'info> {
+    pub fn cancel_payment_plan(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let plan = &mut self.payment_plan;
+        let escrow = &mut self.escrow;
+
+        require!(plan.plan_status == PlanStatus::Active, EcomError::PaymentPlanNotActive);
+        let index = plan.next_unpaid().ok_or(EcomError::PaymentPlanNotActive)?;
+        require!(
+            now > plan.installments[index].due_at.saturating_add(plan.grace_period),
+            EcomError::PaymentPlanNotInDefault
+        );
+
+        // The seller keeps the marketplace's default fee out of what was paid,
+        // the rest goes back to the buyer.
+        let held = self.escrow_ata.amount;
+        let retained = bps_of(held, plan.default_fee_bps)?;
+        let refunded = held - retained;
+
+        let payment_key = self.payment.key();
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            payment_key.as_ref(),
+            &[escrow.escrow_bump],
+        ];
+        let signer_seeds = &[seeds];
+        for (to, amount) in [
+            (self.buyer_ata.to_account_info(), refunded),
+            (self.seller_ata.to_account_info(), retained),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            transfer_tokens(
+                self.token_program.to_account_info(),
+                self.escrow_ata.to_account_info(),
+                self.mint.to_account_info(),
+                to,
+                escrow.to_account_info(),
+                amount,
+                self.mint.decimals,
+                signer_seeds,
+            )?;
+        }
+        close_vault(
+            self.token_program.to_account_info(),
+            self.escrow_ata.to_account_info(),
+            self.mint.to_account_info(),
+            self.buyer.to_account_info(),
+            escrow.to_account_info(),
+            signer_seeds,
+        )?;
+
+        plan.plan_status = PlanStatus::Defaulted;
+        escrow.amount = 0;
+        escrow.deposited_amount = 0;
+        let escrow_key = escrow.key();
+        escrow.transition(escrow_key, EscrowStatus::Refunded)?;
+        let payment = &mut self.payment;
+        payment.payment_status = PaymentStatus::Refunded;
+        payment.refunded_amount = payment.refunded_amount
+            .checked_add(refunded)
+            .ok_or(EcomError::MathOverflow)?;
+        if let Some(order) = self.order.as_mut() {
+            order.order_status = OrderStatus::Failed;
+            order.updated_at = now;
+        }
+        self.purchase_list.open_purchases.retain(|key| key != &payment_key);
+
+        emit!(PaymentPlanDefaulted{
+            payment: payment_key,
+            refunded,
+            retained,
+        });
+        emit!(Refunded{
+            payment: payment_key,
+            escrow: escrow.key(),
+            buyer: payment.owner,
+            amount: refunded,
+            remaining: 0,
+            reason: RefundReason::PaymentPlanDefaulted,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    escrow::{Escrow, EscrowStatus, RefundReason, Refunded},
+    order::Order,
+    payment::{Payment, PaymentStatus, PurchaseList},
+};
+use crate::utils::{amount_with_fee, close_vault, transfer_tokens};
+
+#[derive(Accounts)]
+pub struct RefundEscrow<'info>{
+    /// Only the seller refunds; every path that fails an order already
+    /// returns the escrow to the buyer.
+    #[account(address = escrow.seller_pubkey @ EcomError::UnauthorizedRefund)]
+    pub authority:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"payment",payment.owner.as_ref(),&payment.nonce.to_le_bytes()],
+        bump = payment.payment_bump,
+    )]
+    pub payment:Account<'info,Payment>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump = escrow.escrow_bump,
+    )]
+    pub escrow:Account<'info,Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_list",payment.owner.as_ref()],
+        bump = purchase_list.purchase_list_bump,
+    )]
+    pub purchase_list:Account<'info,PurchaseList>,
+
+    #[account(
+        mut,
+        seeds = [b"order",payment.key().as_ref()],
+        bump = order.order_bump,
+    )]
+    pub order:Option<Account<'info,Order>>,
+
+    /// Receives the vault's rent once the escrow is fully refunded.
+    #[account(mut, address = escrow.owner)]
+    pub buyer:SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = escrow_ata.mint == mint.key() @ EcomError::EscrowVaultMintMismatch,
+        constraint = escrow_ata.owner == escrow.key() @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == escrow.owner @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(
+        mut,
+        constraint = mint.key() == escrow.mint @ EcomError::InvalidMint,
+    )]
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+}
+
+impl<'info> RefundEscrow<'info> {
+    /// Refunds `amount` of the order to the buyer, or everything still
+    /// escrowed when `amount` is zero.
+    pub fn refund_escrow(
+        &mut self,
+        amount:u64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut self.escrow;
+
+        require!(escrow.escrow_status != EscrowStatus::Disputed, EcomError::EscrowDisputed);
+        require!(escrow.deposited_amount > 0, EcomError::FundsNotFound);
+        require!(amount <= escrow.amount, EcomError::RefundExceedsEscrow);
+
+        // A partly paid instalment plan holds less than the order amount, so
+        // a full refund records only what the vault can actually return.
+        let full_refund = amount == 0 || amount == escrow.amount;
+        // Later instalments are sized against the full order, so an active
+        // plan can only be refunded in full.
+        require!(
+            full_refund || escrow.escrow_status != EscrowStatus::PartiallyFunded,
+            EcomError::PaymentPlanActive
+        );
+        let (refunded, sent) = if full_refund {
+            (escrow.amount.min(self.escrow_ata.amount), self.escrow_ata.amount)
+        } else {
+            let gross = amount_with_fee(&self.mint.to_account_info(), amount)?;
+            require!(gross <= escrow.deposited_amount, EcomError::RefundExceedsEscrow);
+            (amount, gross)
+        };
+
+        let payment_key = self.payment.key();
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            payment_key.as_ref(),
+            &[escrow.escrow_bump],
+        ];
+        transfer_tokens(
+            self.token_program.to_account_info(),
+            self.escrow_ata.to_account_info(),
+            self.mint.to_account_info(),
+            self.buyer_ata.to_account_info(),
+            escrow.to_account_info(),
+            sent,
+            self.mint.decimals,
+            &[seeds],
+        )?;
+
+        if full_refund {
+            escrow.amount = 0;
+            escrow.deposited_amount = 0;
+        } else {
+            escrow.amount -= refunded;
+            escrow.deposited_amount -= sent;
+        }
+        let escrow_key = escrow.key();
+        let payment = &mut self.payment;
+        payment.refunded_amount = payment.refunded_amount
+            .checked_add(refunded)
+            .ok_or(EcomError::MathOverflow)?;
+
+        if full_refund {
+            close_vault(
+                self.token_program.to_account_info(),
+                self.escrow_ata.to_account_info(),
+                self.mint.to_account_info(),
+                self.buyer.to_account_info(),
+                escrow.to_account_info(),
+                &[seeds],
+            )?;
+            escrow.transition(escrow_key, EscrowStatus::Refunded)?;
+            payment.payment_status = PaymentStatus::Refunded;
+            if let Some(order) = self.order.as_mut() {
+                order.order_status = order.refunded_status();
+                order.updated_at = now;
+            }
+            self.purchase_list.open_purchases.retain(|key| key != &payment_key);
+        } else {
+            escrow.transition(escrow_key, EscrowStatus::PartiallyRefunded)?;
+            payment.payment_status = PaymentStatus::PartiallyRefunded;
+        }
+
+        emit!(Refunded{
+            payment: payment_key,
+            escrow: escrow.key(),
+            buyer: escrow.owner,
+            amount: refunded,
+            remaining: escrow.amount,
+            reason: RefundReason::SellerCancelled,
+        });
+        Ok(())
+    }
+}
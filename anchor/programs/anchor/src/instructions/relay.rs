@@ -0,0 +1,271 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    marketplace::Marketplace,
+    escrow::{Escrow, EscrowStatus},
+    order::{Order, OrderStatus, OrderTracking},
+    payment::{Payment, PaymentMethod, PaymentStatus, PurchaseList},
+    relay::{PaymentIntent, PaymentIntentRelayed, RelayNonce},
+    PriceCurrency, Product,
+};
+use crate::utils::{
+    amount_due, amount_with_fee, derive_id, id_to_uuid_string, transfer_tokens,
+    verify_ed25519_signature,
+};
+
+/// Gasless checkout: the relayer signs and pays fees and rent, the buyer
+/// only signs the intent, checked through the ed25519 instruction placed
+/// right before this one.
+#[derive(Accounts)]
+#[instruction(intent: PaymentIntent)]
+pub struct RelayPayment<'info>{
+    #[account(mut)]
+    pub relayer:Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        seeds = [b"relay_nonce",intent.buyer.as_ref()],
+        bump,
+        space = 8 + RelayNonce::INIT_SPACE,
+    )]
+    pub relay_nonce:Box<Account<'info,RelayNonce>>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Box<Account<'info,Marketplace>>,
+
+    #[account(address = intent.product)]
+    pub product:Box<Account<'info,Product>>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        seeds = [b"purchase_list",intent.buyer.as_ref()],
+        bump,
+        space = 8 + PurchaseList::INIT_SPACE
+    )]
+    pub purchase_list:Box<Account<'info,PurchaseList>>,
+
+    #[account(
+        init,
+        payer = relayer,
+        seeds = [
+            b"payment",
+            intent.buyer.as_ref(),
+            &purchase_list.payment_count.to_le_bytes(),
+        ],
+        bump,
+        space = 8 + Payment::INIT_SPACE
+    )]
+    pub payment:Box<Account<'info,Payment>>,
+
+    #[account(
+        init,
+        payer = relayer,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump,
+        space = 8 + Escrow::INIT_SPACE,
+    )]
+    pub escrow:Box<Account<'info,Escrow>>,
+
+    #[account(
+        init,
+        payer = relayer,
+        seeds = [b"order",payment.key().as_ref()],
+        bump,
+        space = 9 + Order::INIT_SPACE,
+    )]
+    pub order:Box<Account<'info,Order>>,
+
+    ///CHECK: PDA the buyer approved as delegate on their token account.
+    /// Subscriptions bill through the same PDA, since a token account has
+    /// only one delegate.
+    #[account(
+        seeds = [b"payment_delegate",intent.buyer.as_ref()],
+        bump,
+    )]
+    pub delegate:UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == intent.buyer @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    #[account(
+        address = intent.mint @ EcomError::InvalidMint,
+        constraint = mint.key() == product.mint @ EcomError::ProductMintMismatch,
+    )]
+    pub mint:Box<InterfaceAccount<'info,Mint>>,
+
+    ///CHECK: Instructions sysvar, read to find the ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions:UncheckedAccount<'info>,
+    pub token_program:Interface<'info,TokenInterface>,
+    pub associated_token_program:Program<'info,AssociatedToken>,
+    pub system_program:Program<'info,System>,
+}
+
+impl<'info> RelayPayment<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn relay_payment(
+        &mut self,
+        intent:PaymentIntent,
+        relay_nonce_bump:u8,
+        purchase_list_bump:u8,
+        payment_bump:u8,
+        escrow_bump:u8,
+        order_bump:u8,
+        delegate_bump:u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let buyer = intent.buyer;
+
+        verify_ed25519_signature(
+            &self.instructions.to_account_info(),
+            &buyer,
+            &intent.signed_message()?,
+        )?;
+        require!(now <= intent.expires_at, EcomError::IntentExpired);
+
+        let relay_nonce = &mut self.relay_nonce;
+        require!(intent.nonce == relay_nonce.next_nonce, EcomError::IntentNonceMismatch);
+        relay_nonce.buyer = buyer;
+        relay_nonce.next_nonce = intent.nonce.checked_add(1).ok_or(EcomError::MathOverflow)?;
+        relay_nonce.relay_nonce_bump = relay_nonce_bump;
+
+        // Nothing here can reach a price feed on the buyer's behalf, so only
+        // token-priced products can be bought through a relayer.
+        let product = &self.product;
+        require!(
+            product.price_currency == PriceCurrency::Token,
+            EcomError::UnsupportedIntent
+        );
+        require!(intent.quantity > 0, EcomError::InvalidQuantity);
+        require!(
+            intent.quantity <= product.quantity as u64,
+            EcomError::InsufficientStock
+        );
+        let amount = amount_due(
+            product.price as u64,
+            intent.quantity,
+            product.discount_bps,
+            product.order_fee as u64,
+        )?;
+
+        let mint_info = self.mint.to_account_info();
+        let release_amount = amount_with_fee(&mint_info, amount)?;
+        let deposit_amount = amount_with_fee(&mint_info, release_amount)?;
+        require!(deposit_amount <= intent.max_amount, EcomError::IntentAmountExceeded);
+        let seeds: &[&[u8]] = &[
+            b"payment_delegate",
+            buyer.as_ref(),
+            &[delegate_bump],
+        ];
+        transfer_tokens(
+            self.token_program.to_account_info(),
+            self.buyer_ata.to_account_info(),
+            mint_info,
+            self.escrow_ata.to_account_info(),
+            self.delegate.to_account_info(),
+            deposit_amount,
+            self.mint.decimals,
+            &[seeds],
+        )?;
+
+        let nonce = self.purchase_list.payment_count;
+        let payment_key = self.payment.key();
+        let payment_id = derive_id(&[
+            buyer.as_ref(),
+            &nonce.to_le_bytes(),
+            &now.to_le_bytes(),
+        ]);
+        let order_id = derive_id(&[payment_key.as_ref(), &now.to_le_bytes()]);
+
+        self.payment.set_inner(Payment {
+            payment_id,
+            owner: buyer,
+            nonce,
+            seller: product.seller_pubkey,
+            mint: self.mint.key(),
+            quantity: intent.quantity,
+            payment_amount: amount,
+            priced_amount: amount,
+            coupon: None,
+            coupon_discount: 0,
+            points_redeemed: 0,
+            refunded_amount: 0,
+            marketplace_fee: 0,
+            product_pubkey: product.key(),
+            payment_method: PaymentMethod::SOL,
+            payment_status: PaymentStatus::Pending,
+            time_stamp: now,
+            funding: None,
+            payment_plan: None,
+            rent_payer: self.relayer.key(),
+            payment_bump,
+        });
+        self.payment.record_funding(self.escrow_ata.key(), deposit_amount, release_amount)?;
+        self.escrow.set_inner(Escrow {
+            owner: buyer,
+            buyer_pubkey: buyer,
+            seller_pubkey: product.seller_pubkey,
+            mint: self.mint.key(),
+            amount,
+            deposited_amount: release_amount,
+            time_stamp: now,
+            update_timestamp: now,
+            ship_by: now.saturating_add(self.marketplace.shipping_window),
+            milestones: Vec::new(),
+            released_amount: 0,
+            escrow_status: EscrowStatus::SwapPending,
+            escrow_bump,
+        });
+        let escrow_key = self.escrow.key();
+        self.escrow.transition(escrow_key, EscrowStatus::FundsReceived)?;
+        self.order.set_inner(Order {
+            order_id,
+            payment: payment_key,
+            payment_id: id_to_uuid_string(&payment_id),
+            tracking_id: order_id,
+            order_status: OrderStatus::Placed,
+            order_tracking: OrderTracking::Booked,
+            created_at: now,
+            updated_at: now,
+            delivered_at: None,
+            order_bump,
+        });
+
+        let purchase_list = &mut self.purchase_list;
+        require!(
+            purchase_list.open_purchases.len() < PurchaseList::MAX_OPEN_PURCHASES,
+            EcomError::PurchaseListFull
+        );
+        purchase_list.payment_count = nonce.checked_add(1).ok_or(EcomError::MathOverflow)?;
+        purchase_list.purchase_list_bump = purchase_list_bump;
+        purchase_list.open_purchases.push(payment_key);
+
+        emit!(PaymentIntentRelayed{
+            buyer,
+            relayer: self.relayer.key(),
+            payment: payment_key,
+            nonce: intent.nonce,
+            amount,
+        });
+        Ok(())
+    }
+}
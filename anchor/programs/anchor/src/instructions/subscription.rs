@@ -0,0 +1,438 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken, Create};
+use anchor_spl::token_interface::{self, Approve, Mint, TokenAccount, TokenInterface};
+use crate::error::EcomError;
+use crate::states::{
+    marketplace::Marketplace,
+    escrow::{Escrow, EscrowStatus},
+    order::{Order, OrderStatus, OrderTracking},
+    payment::{Payment, PaymentMethod, PaymentStatus, PurchaseList},
+    subscription::{
+        BillingFailure, Subscription, SubscriptionBilled, SubscriptionBillingFailed,
+        SubscriptionStatus, SubscriptionStatusChanged,
+    },
+    PriceCurrency, Product,
+};
+use crate::utils::{amount_due, amount_with_fee, derive_id, id_to_uuid_string, transfer_tokens};
+
+#[derive(Accounts)]
+pub struct CreateSubscription<'info>{
+    #[account(mut)]
+    pub buyer:Signer<'info>,
+
+    pub product:Account<'info,Product>,
+
+    #[account(
+        init,
+        payer = buyer,
+        seeds = [b"subscription",buyer.key().as_ref(),product.key().as_ref()],
+        bump,
+        space = 8 + Subscription::INIT_SPACE,
+    )]
+    pub subscription:Account<'info,Subscription>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        seeds = [b"purchase_list",buyer.key().as_ref()],
+        bump,
+        space = 8 + PurchaseList::INIT_SPACE
+    )]
+    pub purchase_list:Account<'info,PurchaseList>,
+
+    ///CHECK: PDA that bills the buyer's token account, holds no data.
+    /// Shared with relayed payments, since a token account has only one
+    /// delegate.
+    #[account(
+        seeds = [b"payment_delegate",buyer.key().as_ref()],
+        bump,
+    )]
+    pub delegate:UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == buyer.key() @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:InterfaceAccount<'info,TokenAccount>,
+    #[account(constraint = mint.key() == product.mint @ EcomError::ProductMintMismatch)]
+    pub mint:InterfaceAccount<'info,Mint>,
+    pub token_program:Interface<'info,TokenInterface>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct BillSubscription<'info>{
+    #[account(mut)]
+    pub cranker:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription",subscription.buyer.as_ref(),subscription.product.as_ref()],
+        bump = subscription.subscription_bump,
+    )]
+    pub subscription:Box<Account<'info,Subscription>>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump = marketplace.marketplace_bump,
+    )]
+    pub marketplace:Box<Account<'info,Marketplace>>,
+
+    #[account(address = subscription.product)]
+    pub product:Box<Account<'info,Product>>,
+
+    #[account(
+        mut,
+        seeds = [b"purchase_list",subscription.buyer.as_ref()],
+        bump = purchase_list.purchase_list_bump,
+    )]
+    pub purchase_list:Box<Account<'info,PurchaseList>>,
+
+    #[account(
+        init,
+        payer = cranker,
+        seeds = [
+            b"payment",
+            subscription.buyer.as_ref(),
+            &purchase_list.payment_count.to_le_bytes(),
+        ],
+        bump,
+        space = 8 + Payment::INIT_SPACE
+    )]
+    pub payment:Box<Account<'info,Payment>>,
+
+    #[account(
+        init,
+        payer = cranker,
+        seeds = [b"escrow",payment.key().as_ref()],
+        bump,
+        space = 8 + Escrow::INIT_SPACE,
+    )]
+    pub escrow:Box<Account<'info,Escrow>>,
+
+    #[account(
+        init,
+        payer = cranker,
+        seeds = [b"order",payment.key().as_ref()],
+        bump,
+        space = 9 + Order::INIT_SPACE,
+    )]
+    pub order:Box<Account<'info,Order>>,
+
+    ///CHECK: PDA approved as delegate on the buyer's token account
+    #[account(
+        seeds = [b"payment_delegate",subscription.buyer.as_ref()],
+        bump,
+    )]
+    pub delegate:UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = buyer_ata.mint == mint.key() @ EcomError::BuyerTokenMintMismatch,
+        constraint = buyer_ata.owner == subscription.buyer @ EcomError::InvalidBuyerTokenAccount,
+    )]
+    pub buyer_ata:Box<InterfaceAccount<'info,TokenAccount>>,
+    ///CHECK: The escrow's vault, only created once the bill goes through so
+    /// a failed period leaves no token account behind.
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&escrow.key(), &mint.key(), &token_program.key())
+            @ EcomError::InvalidEscrowVault,
+    )]
+    pub escrow_ata:UncheckedAccount<'info>,
+    #[account(
+        address = subscription.mint @ EcomError::InvalidMint,
+        constraint = mint.key() == product.mint @ EcomError::ProductMintMismatch,
+    )]
+    pub mint:Box<InterfaceAccount<'info,Mint>>,
+    pub token_program:Interface<'info,TokenInterface>,
+    pub associated_token_program:Program<'info,AssociatedToken>,
+    pub system_program:Program<'info,System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSubscriptionStatus<'info>{
+    pub buyer:Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription",buyer.key().as_ref(),subscription.product.as_ref()],
+        bump = subscription.subscription_bump,
+    )]
+    pub subscription:Account<'info,Subscription>,
+}
+
+impl<'info> CreateSubscription<'info> {
+    pub fn create_subscription(
+        &mut self,
+        quantity:u64,
+        interval:i64,
+        allowance:u64,
+        subscription_bump:u8,
+        purchase_list_bump:u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let product = &self.product;
+
+        // Billing runs unattended, so only token-priced products qualify.
+        require!(
+            product.price_currency == PriceCurrency::Token
+                && interval >= Subscription::MIN_INTERVAL,
+            EcomError::InvalidSubscription
+        );
+        require!(quantity > 0, EcomError::InvalidQuantity);
+
+        // Relayed payments spend through the same delegate, so add to any
+        // allowance it already has rather than replacing it.
+        let existing_allowance = if self.buyer_ata.delegate == COption::Some(self.delegate.key()) {
+            self.buyer_ata.delegated_amount
+        } else {
+            0
+        };
+        let cpi_accounts = Approve {
+            to: self.buyer_ata.to_account_info(),
+            delegate: self.delegate.to_account_info(),
+            authority: self.buyer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        token_interface::approve(
+            cpi_ctx,
+            existing_allowance.checked_add(allowance).ok_or(EcomError::MathOverflow)?,
+        )?;
+
+        self.subscription.set_inner(Subscription {
+            buyer: self.buyer.key(),
+            seller: product.seller_pubkey,
+            product: product.key(),
+            mint: self.mint.key(),
+            quantity,
+            interval,
+            next_billing_at: now,
+            billed_count: 0,
+            failed_count: 0,
+            consecutive_failures: 0,
+            last_failure: None,
+            last_failure_at: 0,
+            subscription_status: SubscriptionStatus::Active,
+            created_at: now,
+            subscription_bump,
+        });
+        self.purchase_list.purchase_list_bump = purchase_list_bump;
+        Ok(())
+    }
+}
+
+impl<'info> BillSubscription<'info> {
+    pub fn bill_subscription(
+        &mut self,
+        payment_bump:u8,
+        escrow_bump:u8,
+        order_bump:u8,
+        delegate_bump:u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let subscription = &mut self.subscription;
+        let product = &self.product;
+
+        require!(
+            subscription.subscription_status == SubscriptionStatus::Active,
+            EcomError::SubscriptionNotActive
+        );
+        require!(now >= subscription.next_billing_at, EcomError::SubscriptionNotDue);
+
+        let amount = amount_due(
+            product.price as u64,
+            subscription.quantity,
+            product.discount_bps,
+            product.order_fee as u64,
+        )?;
+        let mint_info = self.mint.to_account_info();
+        let release_amount = amount_with_fee(&mint_info, amount)?;
+        let deposit_amount = amount_with_fee(&mint_info, release_amount)?;
+
+        let buyer_ata = &self.buyer_ata;
+        let failure = if (product.quantity as u64) < subscription.quantity {
+            Some(BillingFailure::OutOfStock)
+        } else if buyer_ata.delegate != COption::Some(self.delegate.key()) {
+            Some(BillingFailure::DelegateNotApproved)
+        } else if buyer_ata.delegated_amount < deposit_amount {
+            Some(BillingFailure::AllowanceExceeded)
+        } else if buyer_ata.amount < deposit_amount {
+            Some(BillingFailure::InsufficientBalance)
+        } else {
+            None
+        };
+
+        let buyer = subscription.buyer;
+        let nonce = self.purchase_list.payment_count;
+        let payment_key = self.payment.key();
+        let payment_id = derive_id(&[
+            buyer.as_ref(),
+            &nonce.to_le_bytes(),
+            &now.to_le_bytes(),
+        ]);
+        let order_id = derive_id(&[payment_key.as_ref(), &now.to_le_bytes()]);
+
+        if failure.is_none() {
+            associated_token::create_idempotent(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                Create {
+                    payer: self.cranker.to_account_info(),
+                    associated_token: self.escrow_ata.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    mint: mint_info.clone(),
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            ))?;
+            let seeds: &[&[u8]] = &[
+                b"payment_delegate",
+                buyer.as_ref(),
+                &[delegate_bump],
+            ];
+            transfer_tokens(
+                self.token_program.to_account_info(),
+                self.buyer_ata.to_account_info(),
+                mint_info,
+                self.escrow_ata.to_account_info(),
+                self.delegate.to_account_info(),
+                deposit_amount,
+                self.mint.decimals,
+                &[seeds],
+            )?;
+        }
+        let billed = failure.is_none();
+
+        // Every attempt leaves a payment, escrow and order behind so a
+        // failed period is visible on-chain instead of silently skipped.
+        self.payment.set_inner(Payment {
+            payment_id,
+            owner: buyer,
+            nonce,
+            seller: subscription.seller,
+            mint: subscription.mint,
+            quantity: subscription.quantity,
+            payment_amount: amount,
+            priced_amount: amount,
+            coupon: None,
+            coupon_discount: 0,
+            points_redeemed: 0,
+            refunded_amount: 0,
+            marketplace_fee: 0,
+            product_pubkey: product.key(),
+            payment_method: PaymentMethod::SOL,
+            payment_status: if billed { PaymentStatus::Pending } else { PaymentStatus::Failed },
+            time_stamp: now,
+            funding: None,
+            payment_plan: None,
+            rent_payer: self.cranker.key(),
+            payment_bump,
+        });
+        if billed {
+            self.payment.record_funding(self.escrow_ata.key(), deposit_amount, release_amount)?;
+        }
+        self.escrow.set_inner(Escrow {
+            owner: buyer,
+            buyer_pubkey: buyer,
+            seller_pubkey: subscription.seller,
+            mint: subscription.mint,
+            amount,
+            deposited_amount: if billed { release_amount } else { 0 },
+            time_stamp: now,
+            update_timestamp: now,
+            ship_by: now.saturating_add(self.marketplace.shipping_window),
+            milestones: Vec::new(),
+            released_amount: 0,
+            escrow_status: EscrowStatus::SwapPending,
+            escrow_bump,
+        });
+        let escrow_key = self.escrow.key();
+        self.escrow.transition(
+            escrow_key,
+            if billed { EscrowStatus::FundsReceived } else { EscrowStatus::TransferFailed },
+        )?;
+        self.order.set_inner(Order {
+            order_id,
+            payment: payment_key,
+            payment_id: id_to_uuid_string(&payment_id),
+            tracking_id: order_id,
+            order_status: if billed { OrderStatus::Placed } else { OrderStatus::Failed },
+            order_tracking: if billed { OrderTracking::Booked } else { OrderTracking::WatingForOrders },
+            created_at: now,
+            updated_at: now,
+            delivered_at: None,
+            order_bump,
+        });
+
+        let purchase_list = &mut self.purchase_list;
+        purchase_list.payment_count = nonce.checked_add(1).ok_or(EcomError::MathOverflow)?;
+
+        match failure {
+            None => {
+                require!(
+                    purchase_list.open_purchases.len() < PurchaseList::MAX_OPEN_PURCHASES,
+                    EcomError::PurchaseListFull
+                );
+                purchase_list.open_purchases.push(payment_key);
+
+                let next = subscription.next_billing_at.saturating_add(subscription.interval);
+                subscription.next_billing_at = if next > now { next } else { now.saturating_add(subscription.interval) };
+                subscription.billed_count += 1;
+                subscription.consecutive_failures = 0;
+
+                emit!(SubscriptionBilled{
+                    subscription: subscription.key(),
+                    payment: payment_key,
+                    amount,
+                    next_billing_at: subscription.next_billing_at,
+                });
+            }
+            Some(reason) => {
+                subscription.failed_count += 1;
+                subscription.consecutive_failures = subscription.consecutive_failures.saturating_add(1);
+                subscription.last_failure = Some(reason);
+                subscription.last_failure_at = now;
+                subscription.next_billing_at = now.saturating_add(Subscription::RETRY_DELAY);
+                if subscription.consecutive_failures >= Subscription::MAX_CONSECUTIVE_FAILURES {
+                    subscription.subscription_status = SubscriptionStatus::Paused;
+                }
+
+                emit!(SubscriptionBillingFailed{
+                    subscription: subscription.key(),
+                    payment: payment_key,
+                    reason,
+                    consecutive_failures: subscription.consecutive_failures,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'info> UpdateSubscriptionStatus<'info> {
+    pub fn update_subscription_status(
+        &mut self,
+        subscription_status:SubscriptionStatus,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let subscription = &mut self.subscription;
+
+        require!(
+            subscription.subscription_status != SubscriptionStatus::Cancelled,
+            EcomError::SubscriptionCancelled
+        );
+        if subscription_status == SubscriptionStatus::Active {
+            subscription.consecutive_failures = 0;
+            subscription.next_billing_at = subscription.next_billing_at.max(now);
+        }
+        subscription.subscription_status = subscription_status;
+
+        emit!(SubscriptionStatusChanged{
+            subscription: subscription.key(),
+            subscription_status,
+        });
+        Ok(())
+    }
+}
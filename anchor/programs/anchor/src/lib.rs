@@ -3,8 +3,9 @@ use anchor_lang::prelude::*;
 mod instructions;
 mod states;
 mod error;
+mod utils;
 use crate::instructions::*;
-use crate::states::{Category,Division};
+use crate::states::{Category,Division,PayoutSplit,PriceCurrency,coupon::{CouponDiscount,CouponScope},relay::PaymentIntent,dispute::DisputeRuling,marketplace::CrankTask,escrow::MilestoneTerm,order::OrderTracking,seller::SellerTier,subscription::SubscriptionStatus};
 
 declare_id!("FYo4gi69vTJZJMnNxj2mZz2Q9CbUu12rQDVtHNUFQ2o7");
 
@@ -12,6 +13,7 @@ declare_id!("FYo4gi69vTJZJMnNxj2mZz2Q9CbUu12rQDVtHNUFQ2o7");
 pub mod ecom_dapp {
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_product(
         ctx: Context<CreateProduct>,
         product_name:String,
@@ -64,17 +66,35 @@ pub mod ecom_dapp {
         Ok(())
     }
 
+    pub fn update_product_pricing(
+        ctx: Context<UpdateProductPricing>,
+        price:u32,
+        price_currency:PriceCurrency,
+        discount_bps:u16,
+        order_fee:u32,
+    ) -> Result<()> {
+        ctx.accounts.update_product_pricing(
+            price,
+            price_currency,
+            discount_bps,
+            order_fee,
+        )?;
+        Ok(())
+    }
+
     pub fn create_payment(
         ctx: Context<CreatePayment>,
-        payment_amount: u64,
-        product_pubkey:Pubkey,
-        tx_signature:Option<String>,
+        quantity: u32,
+        coupon_code:Option<String>,
+        redeem_points:u64,
     ) -> Result<()> {
        ctx.accounts.create_payment(
-        payment_amount, 
-        product_pubkey, 
-        tx_signature, 
+        quantity as u64, 
+        coupon_code,
+        redeem_points,
         ctx.bumps.payments,
+        ctx.bumps.purchase_list,
+        ctx.bumps.coupon_redemption,
     )?;
         Ok(())
     }
@@ -82,12 +102,12 @@ pub mod ecom_dapp {
         ctx: Context<CreateEscrow>,
         buyer_pubkey:Pubkey,
         seller_pubkey:Pubkey,
-        amount:u64,
+        milestones:Vec<MilestoneTerm>,
     )-> Result<()> {
         ctx.accounts.create_escrow(
             buyer_pubkey, 
             seller_pubkey, 
-            amount, 
+            milestones,
             ctx.bumps.escrow
         )?;
         Ok(())
@@ -95,20 +115,25 @@ pub mod ecom_dapp {
 
     pub fn deposit_escrow(
         ctx: Context<DepositeEscrow>,
-        _product_id: u32,
+        amount: u64,
+        gift_card_amount: u64,
     ) -> Result<()> {
         ctx.accounts.deposite_escrow(
+            amount,
+            gift_card_amount,
             ctx.bumps.escrow,
         )?;
         Ok(())
     }
 
-    pub fn withdraw_escrow(
-        ctx: Context<WithdrawlEscrow>,
+    pub fn withdraw_escrow<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawlEscrow<'info>>,
         _product_id: u32,
     ) -> Result<()> {
         ctx.accounts.withdrawl_escrow(
             ctx.bumps.escrow,
+            ctx.bumps.receipt,
+            ctx.remaining_accounts,
         )?;
         Ok(())
     }
@@ -124,4 +149,438 @@ pub mod ecom_dapp {
     }
 
 
+    pub fn initialize_marketplace(
+        ctx: Context<InitializeMarketplace>,
+        max_price_age:u64,
+        max_confidence_bps:u16,
+    ) -> Result<()> {
+        ctx.accounts.initialize_marketplace(
+            max_price_age,
+            max_confidence_bps,
+            ctx.bumps.marketplace,
+        )?;
+        Ok(())
+    }
+
+    pub fn register_payment_mint(
+        ctx: Context<RegisterPaymentMint>,
+    ) -> Result<()> {
+        ctx.accounts.register_payment_mint(
+            ctx.bumps.accepted_mint,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_keeper_vault(
+        _ctx: Context<InitKeeperVault>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn set_keeper_rules(
+        ctx: Context<UpdateMarketplace>,
+        keeper_fee_bps:u16,
+        keeper_reward:u64,
+    ) -> Result<()> {
+        ctx.accounts.set_keeper_rules(keeper_fee_bps, keeper_reward)?;
+        Ok(())
+    }
+
+    pub fn set_mock_price(
+        ctx: Context<SetMockPrice>,
+        price:i64,
+        conf:u64,
+        expo:i32,
+    ) -> Result<()> {
+        ctx.accounts.set_mock_price(
+            price,
+            conf,
+            expo,
+            ctx.bumps.mock_price_feed,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_price_rules(
+        ctx: Context<UpdateMarketplace>,
+        max_price_age:u64,
+        max_confidence_bps:u16,
+    ) -> Result<()> {
+        ctx.accounts.set_price_rules(max_price_age, max_confidence_bps)?;
+        Ok(())
+    }
+
+    pub fn set_installment_rules(
+        ctx: Context<UpdateMarketplace>,
+        release_bps:u16,
+        grace_period:i64,
+        default_fee_bps:u16,
+    ) -> Result<()> {
+        ctx.accounts.set_installment_rules(
+            release_bps,
+            grace_period,
+            default_fee_bps,
+        )?;
+        Ok(())
+    }
+
+    pub fn update_order_tracking(
+        ctx: Context<UpdateOrderTracking>,
+        order_tracking:OrderTracking,
+    ) -> Result<()> {
+        ctx.accounts.update_order_tracking(
+            order_tracking,
+        )?;
+        Ok(())
+    }
+
+    pub fn create_payment_plan(
+        ctx: Context<CreatePaymentPlan>,
+        due_dates:Vec<i64>,
+    ) -> Result<()> {
+        ctx.accounts.create_payment_plan(
+            due_dates,
+            ctx.bumps.payment_plan,
+        )?;
+        Ok(())
+    }
+
+    pub fn pay_installment(
+        ctx: Context<PayInstallment>,
+    ) -> Result<()> {
+        ctx.accounts.pay_installment()?;
+        Ok(())
+    }
+
+    pub fn cancel_payment_plan(
+        ctx: Context<CancelPaymentPlan>,
+    ) -> Result<()> {
+        ctx.accounts.cancel_payment_plan()?;
+        Ok(())
+    }
+
+    pub fn create_subscription(
+        ctx: Context<CreateSubscription>,
+        quantity:u32,
+        interval:i64,
+        allowance:u64,
+    ) -> Result<()> {
+        ctx.accounts.create_subscription(
+            quantity as u64,
+            interval,
+            allowance,
+            ctx.bumps.subscription,
+            ctx.bumps.purchase_list,
+        )?;
+        Ok(())
+    }
+
+    pub fn bill_subscription(
+        ctx: Context<BillSubscription>,
+    ) -> Result<()> {
+        ctx.accounts.bill_subscription(
+            ctx.bumps.payment,
+            ctx.bumps.escrow,
+            ctx.bumps.order,
+            ctx.bumps.delegate,
+        )?;
+        Ok(())
+    }
+
+    pub fn update_subscription_status(
+        ctx: Context<UpdateSubscriptionStatus>,
+        subscription_status:SubscriptionStatus,
+    ) -> Result<()> {
+        ctx.accounts.update_subscription_status(
+            subscription_status,
+        )?;
+        Ok(())
+    }
+
+    pub fn refund_escrow(
+        ctx: Context<RefundEscrow>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.refund_escrow(
+            amount,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_marketplace_fee(
+        ctx: Context<UpdateMarketplace>,
+        fee_bps:u16,
+        treasury:Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.set_marketplace_fee(
+            fee_bps,
+            treasury,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_category_fee(
+        ctx: Context<UpdateMarketplace>,
+        category:Category,
+        fee_bps:Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.set_category_fee(
+            category,
+            fee_bps,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_tier_fee(
+        ctx: Context<UpdateMarketplace>,
+        tier:SellerTier,
+        fee_bps:Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.set_tier_fee(
+            tier,
+            fee_bps,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_seller_tier(
+        ctx: Context<SetSellerTier>,
+        seller:Pubkey,
+        tier:SellerTier,
+    ) -> Result<()> {
+        ctx.accounts.set_seller_tier(
+            seller,
+            tier,
+            ctx.bumps.seller_profile,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_payout_splits(
+        ctx: Context<UpdateProductPricing>,
+        payout_splits:Vec<PayoutSplit>,
+    ) -> Result<()> {
+        ctx.accounts.set_payout_splits(
+            payout_splits,
+        )?;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_coupon(
+        ctx: Context<CreateCoupon>,
+        code_hash:[u8;32],
+        discount:CouponDiscount,
+        scope:CouponScope,
+        expires_at:i64,
+        max_redemptions:u32,
+        per_wallet_cap:u16,
+    ) -> Result<()> {
+        ctx.accounts.create_coupon(
+            code_hash,
+            discount,
+            scope,
+            expires_at,
+            max_redemptions,
+            per_wallet_cap,
+            ctx.bumps.coupon,
+        )?;
+        Ok(())
+    }
+
+    pub fn initialize_loyalty(
+        ctx: Context<InitializeLoyalty>,
+        points_per_unit:u64,
+    ) -> Result<()> {
+        ctx.accounts.initialize_loyalty(
+            points_per_unit,
+            ctx.bumps.loyalty,
+            ctx.bumps.points_mint,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_loyalty_rate(
+        ctx: Context<UpdateLoyalty>,
+        category:Category,
+        rate_bps:Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.set_loyalty_rate(
+            category,
+            rate_bps,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_points_value(
+        ctx: Context<UpdateLoyalty>,
+        points_per_unit:u64,
+    ) -> Result<()> {
+        ctx.accounts.set_points_value(points_per_unit)?;
+        Ok(())
+    }
+
+    pub fn create_gift_card(
+        ctx: Context<CreateGiftCard>,
+        code_hash:[u8;32],
+        amount:u64,
+        holder:Option<Pubkey>,
+        claim_key:Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.create_gift_card(
+            code_hash,
+            amount,
+            holder,
+            claim_key,
+            ctx.bumps.gift_card,
+            ctx.bumps.vault,
+        )?;
+        Ok(())
+    }
+
+    pub fn redeem_gift_card(
+        ctx: Context<RedeemGiftCard>,
+    ) -> Result<()> {
+        ctx.accounts.redeem_gift_card()?;
+        Ok(())
+    }
+
+    pub fn transfer_gift_card(
+        ctx: Context<TransferGiftCard>,
+        new_holder:Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.transfer_gift_card(new_holder)?;
+        Ok(())
+    }
+
+    pub fn relay_payment(
+        ctx: Context<RelayPayment>,
+        intent:PaymentIntent,
+    ) -> Result<()> {
+        ctx.accounts.relay_payment(
+            intent,
+            ctx.bumps.relay_nonce,
+            ctx.bumps.purchase_list,
+            ctx.bumps.payment,
+            ctx.bumps.escrow,
+            ctx.bumps.order,
+            ctx.bumps.delegate,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_inspection_period(
+        ctx: Context<UpdateMarketplace>,
+        inspection_period:i64,
+    ) -> Result<()> {
+        ctx.accounts.set_inspection_period(inspection_period)?;
+        Ok(())
+    }
+
+    pub fn set_arbiter(
+        ctx: Context<UpdateMarketplace>,
+        arbiter:Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.set_arbiter(arbiter)?;
+        Ok(())
+    }
+
+    pub fn set_arbiter_panel(
+        ctx: Context<UpdateMarketplace>,
+        arbiters:Vec<Pubkey>,
+        threshold:u8,
+        min_amount:u64,
+    ) -> Result<()> {
+        ctx.accounts.set_arbiter_panel(arbiters, threshold, min_amount)?;
+        Ok(())
+    }
+
+    pub fn set_shipping_window(
+        ctx: Context<UpdateMarketplace>,
+        shipping_window:i64,
+    ) -> Result<()> {
+        ctx.accounts.set_shipping_window(shipping_window)?;
+        Ok(())
+    }
+
+    pub fn open_dispute(
+        ctx: Context<OpenDispute>,
+        evidence_uri:String,
+    ) -> Result<()> {
+        ctx.accounts.open_dispute(
+            evidence_uri,
+            ctx.bumps.dispute,
+        )?;
+        Ok(())
+    }
+
+    pub fn add_dispute_evidence(
+        ctx: Context<AddDisputeEvidence>,
+        uri:String,
+    ) -> Result<()> {
+        ctx.accounts.add_dispute_evidence(uri)?;
+        Ok(())
+    }
+
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
+        ruling:DisputeRuling,
+    ) -> Result<()> {
+        ctx.accounts.resolve_dispute(
+            ruling,
+            ctx.bumps.seller_profile,
+            ctx.bumps.receipt,
+            ctx.remaining_accounts,
+        )?;
+        Ok(())
+    }
+
+    pub fn vote_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
+        ruling:DisputeRuling,
+    ) -> Result<()> {
+        ctx.accounts.vote_dispute(
+            ruling,
+            ctx.bumps.seller_profile,
+            ctx.bumps.receipt,
+            ctx.remaining_accounts,
+        )?;
+        Ok(())
+    }
+
+    pub fn expire_escrow(
+        ctx: Context<ExpireEscrow>,
+    ) -> Result<()> {
+        ctx.accounts.expire_escrow()?;
+        Ok(())
+    }
+
+    pub fn release_milestones<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleaseMilestones<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.release_milestones(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    pub fn close_purchase(
+        ctx: Context<ClosePurchase>,
+    ) -> Result<()> {
+        ctx.accounts.close_purchase(ctx.bumps.archive)?;
+        Ok(())
+    }
+
+    pub fn cancel_payment(
+        ctx: Context<CancelPayment>,
+    ) -> Result<()> {
+        ctx.accounts.cancel_payment(ctx.bumps.escrow)?;
+        Ok(())
+    }
+
+    pub fn crank<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Crank<'info>>,
+        tasks:Vec<CrankTask>,
+    ) -> Result<()> {
+        ctx.accounts.crank(tasks, ctx.remaining_accounts)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::states::payment::PaymentStatus;
+
+/// What's left of a purchase once its payment, escrow and order are closed.
+#[account]
+#[derive(InitSpace)]
+pub struct PurchaseArchive{
+    pub payment:Pubkey,
+    pub buyer:Pubkey,
+    pub seller:Pubkey,
+    pub product:Pubkey,
+    pub mint:Pubkey,
+    pub order_id:Option<[u8;16]>,
+    pub amount:u64,
+    pub refunded_amount:u64,
+    pub marketplace_fee:u64,
+    pub outcome:PaymentStatus,
+    pub created_at:i64,
+    pub closed_at:i64,
+    pub archive_bump:u8,
+}
+
+#[event]
+pub struct PurchaseClosed {
+    pub payment: Pubkey,
+    pub rent_payer: Pubkey,
+    pub archived: bool,
+}
+
+#[event]
+pub struct PaymentCancelled {
+    pub payment: Pubkey,
+    pub buyer: Pubkey,
+    pub rent_payer: Pubkey,
+}
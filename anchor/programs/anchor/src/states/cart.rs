@@ -31,6 +31,7 @@ pub struct CartCreated {
     pub product_name: String,
     pub amount: u64,
 }
+#[allow(clippy::enum_variant_names)]
 #[derive(AnchorDeserialize,AnchorSerialize,Clone,InitSpace, PartialEq, Eq)]
 pub enum Stock {
     OutOfStock,
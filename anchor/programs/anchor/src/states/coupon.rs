@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::error::EcomError;
+use crate::states::{Category, Product};
+use crate::utils::bps_of;
+
+/// Promo code, stored under its creator and the hash of the code, so the
+/// same code can be issued by different creators without anyone claiming
+/// it first. Coupons created by a seller only apply to that seller's
+/// products; the marketplace admin's apply to any product in `scope`.
+#[account]
+#[derive(InitSpace)]
+pub struct Coupon{
+    pub code_hash:[u8;32],
+    pub creator:Pubkey,
+    pub seller:Option<Pubkey>,
+    pub discount:CouponDiscount,
+    pub scope:CouponScope,
+    pub expires_at:i64,
+    pub max_redemptions:u32,
+    pub redemptions:u32,
+    pub per_wallet_cap:u16,
+    pub created_at:i64,
+    pub coupon_bump:u8,
+}
+
+impl Coupon {
+    /// Discount this coupon takes off `amount` for `product`, after checking
+    /// scope, expiry and the global redemption cap.
+    pub fn discount_for(&self, product: &Account<Product>, amount: u64, now: i64) -> Result<u64> {
+        require!(now < self.expires_at, EcomError::CouponExpired);
+        require!(
+            self.redemptions < self.max_redemptions,
+            EcomError::CouponRedemptionLimit
+        );
+        let in_scope = match &self.scope {
+            CouponScope::All => true,
+            CouponScope::Product { product: scoped } => scoped == &product.key(),
+            CouponScope::Category { category } => category == &product.category,
+        };
+        let seller_matches = self.seller.is_none_or(|seller| seller == product.seller_pubkey);
+        require!(in_scope && seller_matches, EcomError::CouponNotApplicable);
+
+        let discount = match self.discount {
+            CouponDiscount::Percentage { bps } => bps_of(amount, bps)?,
+            CouponDiscount::Fixed { amount: fixed } => fixed.min(amount),
+        };
+        Ok(discount)
+    }
+}
+
+/// Redemptions of one coupon by one wallet.
+#[account]
+#[derive(InitSpace)]
+pub struct CouponRedemption{
+    pub coupon:Pubkey,
+    pub buyer:Pubkey,
+    pub count:u16,
+    pub coupon_redemption_bump:u8,
+}
+
+/// `Fixed` is in the product's pricing unit (token base units or US cents).
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub enum CouponDiscount {
+    Percentage { bps: u16 },
+    Fixed { amount: u64 },
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub enum CouponScope {
+    All,
+    Product { product: Pubkey },
+    Category { category: Category },
+}
+
+#[event]
+pub struct CouponCreated {
+    pub coupon: Pubkey,
+    pub creator: Pubkey,
+    pub expires_at: i64,
+    pub max_redemptions: u32,
+}
+
+#[event]
+pub struct CouponRedeemed {
+    pub coupon: Pubkey,
+    pub buyer: Pubkey,
+    pub payment: Pubkey,
+    pub discount: u64,
+}
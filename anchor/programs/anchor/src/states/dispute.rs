@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use crate::error::EcomError;
+use crate::utils::BPS_DENOMINATOR;
+
+/// Dispute over an escrow. While it is open the escrow cannot be released
+/// or refunded; the marketplace arbiter settles it with a ruling, or the
+/// arbiter panel by vote when the escrow was large enough at opening.
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute{
+    pub escrow:Pubkey,
+    pub payment:Pubkey,
+    pub buyer:Pubkey,
+    pub seller:Pubkey,
+    #[max_len(4, 128)]
+    pub buyer_evidence:Vec<String>,
+    #[max_len(4, 128)]
+    pub seller_evidence:Vec<String>,
+    pub dispute_status:DisputeStatus,
+    pub ruling:Option<DisputeRuling>,
+    pub panel_required:bool,
+    #[max_len(7)]
+    pub votes:Vec<ArbiterVote>,
+    pub opened_at:i64,
+    pub resolved_at:i64,
+    pub dispute_bump:u8,
+}
+
+impl Dispute {
+    pub const MAX_EVIDENCE: usize = 4;
+    pub const MAX_EVIDENCE_URI_LEN: usize = 128;
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub struct ArbiterVote {
+    pub arbiter: Pubkey,
+    pub ruling: DisputeRuling,
+    pub voted_at: i64,
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
+pub enum DisputeStatus {
+    Open,
+    Resolved,
+}
+
+/// `Split` sends `buyer_bps` of the escrowed funds back to the buyer and
+/// the rest to the seller.
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
+pub enum DisputeRuling {
+    RefundBuyer,
+    ReleaseToSeller,
+    Split { buyer_bps: u16 },
+}
+
+impl DisputeRuling {
+    /// Share of the escrow that goes back to the buyer.
+    pub fn buyer_bps(&self) -> Result<u16> {
+        match *self {
+            DisputeRuling::RefundBuyer => Ok(BPS_DENOMINATOR as u16),
+            DisputeRuling::ReleaseToSeller => Ok(0),
+            DisputeRuling::Split { buyer_bps } => {
+                require!((buyer_bps as u64) <= BPS_DENOMINATOR, EcomError::InvalidBasisPoints);
+                Ok(buyer_bps)
+            }
+        }
+    }
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub dispute: Pubkey,
+    pub escrow: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct DisputeEvidenceAdded {
+    pub dispute: Pubkey,
+    pub submitter: Pubkey,
+    pub uri: String,
+}
+
+#[event]
+pub struct DisputeVoteCast {
+    pub dispute: Pubkey,
+    pub arbiter: Pubkey,
+    pub ruling: DisputeRuling,
+    pub matching_votes: u8,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub dispute: Pubkey,
+    pub escrow: Pubkey,
+    pub ruling: DisputeRuling,
+    pub buyer_amount: u64,
+    pub seller_amount: u64,
+    pub arbiter: Pubkey,
+}
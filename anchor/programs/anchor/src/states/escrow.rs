@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::error::EcomError;
+use crate::states::order::OrderTracking;
+use crate::utils::BPS_DENOMINATOR;
 
 #[account]
 #[derive(InitSpace)]
@@ -6,18 +9,186 @@ pub struct Escrow{
     pub owner:Pubkey,
     pub buyer_pubkey:Pubkey,
     pub seller_pubkey:Pubkey,
+    pub mint:Pubkey,
     pub amount:u64,
-    pub release_fund:bool,
+    pub deposited_amount:u64,
     pub time_stamp:i64,
     pub update_timestamp:i64,
+    /// After this, an order that hasn't shipped can be expired by anyone.
+    /// Counted from creation, then moved to count from funding once the
+    /// escrow is funded.
+    pub ship_by:i64,
+    /// Empty for a single release; otherwise tranches paid out as the order
+    /// progresses, fixed when the escrow is created.
+    #[max_len(4)]
+    pub milestones:Vec<Milestone>,
+    /// Paid out to the seller so far through milestones.
+    pub released_amount:u64,
     pub escrow_status:EscrowStatus,
     pub escrow_bump:u8,
 }
 
+impl Escrow {
+    pub const MAX_MILESTONES: usize = 4;
+
+    /// Moves the escrow to `to`, the only way its status changes after
+    /// creation. Stamps `update_timestamp` and emits `EscrowStateChanged`.
+    ///
+    /// The seller's shipping window runs from funding, not creation, so
+    /// reaching `FundsReceived` (which only happens once) pushes `ship_by`
+    /// back by however long the escrow waited for its funds.
+    pub fn transition(&mut self, escrow: Pubkey, to: EscrowStatus) -> Result<()> {
+        let from = self.escrow_status;
+        from.check_transition(to)?;
+        self.escrow_status = to;
+        self.update_timestamp = Clock::get()?.unix_timestamp;
+        if to == EscrowStatus::FundsReceived {
+            self.ship_by = self.ship_by
+                .saturating_add(self.update_timestamp.saturating_sub(self.time_stamp));
+        }
+        emit!(EscrowStateChanged{
+            escrow,
+            from,
+            to,
+            at: self.update_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Validates a schedule: shares add up to 10,000 bps, stages strictly
+    /// advance, and the last tranche is due on delivery so it settles
+    /// through `withdraw_escrow` like any other release.
+    pub fn milestone_schedule(terms: &[MilestoneTerm]) -> Result<Vec<Milestone>> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        require!(terms.len() <= Self::MAX_MILESTONES, EcomError::InvalidMilestoneSchedule);
+        let total: u64 = terms.iter().map(|term| term.release_bps as u64).sum();
+        require!(
+            total == BPS_DENOMINATOR
+                && terms.iter().all(|term| term.release_bps > 0)
+                && terms.windows(2).all(|pair| pair[0].stage < pair[1].stage)
+                && terms.last().is_some_and(|term| term.stage == OrderTracking::Delivered),
+            EcomError::InvalidMilestoneSchedule
+        );
+        Ok(terms
+            .iter()
+            .map(|term| Milestone {
+                stage: term.stage,
+                release_bps: term.release_bps,
+                released_amount: 0,
+                released_at: None,
+            })
+            .collect())
+    }
+}
+
+/// A tranche as requested at escrow creation.
+#[derive(Clone,AnchorDeserialize,AnchorSerialize)]
+pub struct MilestoneTerm {
+    pub stage: OrderTracking,
+    pub release_bps: u16,
+}
+
+/// A tranche of the escrow, due once order tracking reaches `stage`.
 #[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub struct Milestone {
+    pub stage: OrderTracking,
+    pub release_bps: u16,
+    pub released_amount: u64,
+    pub released_at: Option<i64>,
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
 pub enum EscrowStatus {
     SwapPending,
     FundsReceived,
     SwapSuccess,
     TransferFailed,
-}
\ No newline at end of file
+    PartiallyRefunded,
+    Refunded,
+    Disputed,
+    /// Some but not all instalments of a payment plan have been paid.
+    PartiallyFunded,
+}
+
+impl EscrowStatus {
+    /// `SwapSuccess`, `Refunded` and `TransferFailed` are final.
+    pub fn check_transition(self, to: EscrowStatus) -> Result<()> {
+        use EscrowStatus::*;
+        match (self, to) {
+            // A plan defaulting before its first instalment refunds straight from SwapPending.
+            (SwapPending, FundsReceived | PartiallyFunded | TransferFailed | Refunded)
+            | (PartiallyFunded, PartiallyFunded | FundsReceived | Refunded)
+            | (FundsReceived | PartiallyRefunded, SwapSuccess | PartiallyRefunded | Refunded | Disputed)
+            | (Disputed, SwapSuccess | Refunded) => Ok(()),
+            (SwapSuccess | Refunded | TransferFailed, _) => err!(EcomError::EscrowAlreadySettled),
+            (_, FundsReceived | PartiallyFunded) => err!(EcomError::EscrowAlreadyFunded),
+            (SwapPending | PartiallyFunded, _) => err!(EcomError::EscrowNotFunded),
+            _ => err!(EcomError::InvalidEscrowTransition),
+        }
+    }
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,PartialEq,Eq)]
+pub enum RefundReason {
+    SellerCancelled,
+    PaymentPlanDefaulted,
+}
+
+#[event]
+pub struct Refunded {
+    pub payment: Pubkey,
+    pub escrow: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+    pub reason: RefundReason,
+}
+
+#[event]
+pub struct EscrowStateChanged {
+    pub escrow: Pubkey,
+    pub from: EscrowStatus,
+    pub to: EscrowStatus,
+    pub at: i64,
+}
+
+#[event]
+pub struct EscrowExpired {
+    pub payment: Pubkey,
+    pub escrow: Pubkey,
+    pub caller: Pubkey,
+    pub refunded: u64,
+    pub reward: u64,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub payment: Pubkey,
+    pub escrow: Pubkey,
+    pub stage: OrderTracking,
+    pub amount: u64,
+    pub fee_amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct EscrowSettled {
+    pub payment: Pubkey,
+    pub escrow: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+    pub seller_amount: u64,
+    pub fee_amount: u64,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub released_by: Pubkey,
+}
+
+#[event]
+pub struct PayoutSent {
+    pub payment: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+/// Prepaid balance held in a vault owned by this account. A card issued
+/// without a holder is a bearer card: whoever holds the secret behind
+/// `code_hash` claims it.
+#[account]
+#[derive(InitSpace)]
+pub struct GiftCard{
+    pub code_hash:[u8;32],
+    pub issuer:Pubkey,
+    pub holder:Option<Pubkey>,
+    /// Bearer cards only: public key of a keypair the client derives from
+    /// the secret. Claiming takes its signature, so the secret itself never
+    /// appears in a transaction that could be copied from the mempool.
+    pub claim_key:Option<Pubkey>,
+    pub mint:Pubkey,
+    pub balance:u64,
+    pub created_at:i64,
+    pub gift_card_bump:u8,
+    pub vault_bump:u8,
+}
+
+#[event]
+pub struct GiftCardIssued {
+    pub gift_card: Pubkey,
+    pub issuer: Pubkey,
+    pub holder: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GiftCardTransferred {
+    pub gift_card: Pubkey,
+    pub from: Option<Pubkey>,
+    pub to: Pubkey,
+}
+
+#[event]
+pub struct GiftCardDrawn {
+    pub gift_card: Pubkey,
+    pub payment: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::states::Category;
+
+/// Repeat-buyer programme. The points mint is a PDA whose mint authority is
+/// this account, so points can only be minted by a settled escrow release.
+#[account]
+#[derive(InitSpace)]
+pub struct LoyaltyProgram{
+    pub points_mint:Pubkey,
+    /// Points burned for one unit of discount, in the product's pricing unit.
+    pub points_per_unit:u64,
+    /// Points minted per release, in basis points of the released amount.
+    #[max_len(5)]
+    pub category_rates:Vec<CategoryRate>,
+    pub loyalty_bump:u8,
+    pub points_mint_bump:u8,
+}
+
+impl LoyaltyProgram {
+    /// The programme at its PDA `account`, if the marketplace has set one up.
+    pub fn load(account: &AccountInfo) -> Result<Option<Self>> {
+        if account.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*account.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let data = account.try_borrow_data()?;
+        Ok(Some(Self::try_deserialize(&mut &data[..])?))
+    }
+
+    /// Categories without a configured rate earn no points.
+    pub fn rate_bps_for(&self, category: &Category) -> u16 {
+        self.category_rates
+            .iter()
+            .find(|rate| &rate.category == category)
+            .map(|rate| rate.rate_bps)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub struct CategoryRate{
+    pub category:Category,
+    pub rate_bps:u16,
+}
+
+#[event]
+pub struct PointsMinted {
+    pub payment: Pubkey,
+    pub buyer: Pubkey,
+    pub points: u64,
+}
+
+#[event]
+pub struct PointsRedeemed {
+    pub payment: Pubkey,
+    pub buyer: Pubkey,
+    pub points: u64,
+    pub discount: u64,
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use crate::states::{seller::SellerTier, Category};
+
+/// Program-wide settings owned by the marketplace admin.
+#[account]
+#[derive(InitSpace)]
+pub struct Marketplace{
+    pub admin:Pubkey,
+    pub max_price_age:u64,
+    pub max_confidence_bps:u16,
+    pub installment_release_bps:u16,
+    pub installment_grace_period:i64,
+    pub installment_default_fee_bps:u16,
+    pub treasury:Pubkey,
+    pub fee_bps:u16,
+    #[max_len(5)]
+    pub category_fees:Vec<CategoryFee>,
+    #[max_len(3)]
+    pub tier_fees:Vec<TierFee>,
+    /// Seconds after delivery before the seller may release funds themselves.
+    pub inspection_period:i64,
+    /// Rules on disputes; the admin until one is appointed.
+    pub arbiter:Pubkey,
+    /// Seconds a seller has to ship after the escrow is funded.
+    pub shipping_window:i64,
+    /// Votes on disputes over escrows worth at least `panel_min_amount`;
+    /// empty to leave every dispute to `arbiter`.
+    #[max_len(7)]
+    pub arbiter_panel:Vec<Pubkey>,
+    pub panel_threshold:u8,
+    pub panel_min_amount:u64,
+    /// Share of every release fee diverted to the keeper vault of its mint.
+    pub keeper_fee_bps:u16,
+    /// Paid per task `crank` completes, from the keeper vault of the task's
+    /// mint and in its base units, but never more than the marketplace fee
+    /// the task produced.
+    pub keeper_reward:u64,
+    pub marketplace_bump:u8,
+}
+
+impl Marketplace {
+    pub const DEFAULT_INSTALLMENT_GRACE_PERIOD: i64 = 3 * 24 * 60 * 60;
+    pub const DEFAULT_INSPECTION_PERIOD: i64 = 7 * 24 * 60 * 60;
+    pub const DEFAULT_SHIPPING_WINDOW: i64 = 14 * 24 * 60 * 60;
+    pub const MAX_PANEL_ARBITERS: usize = 7;
+    pub const MAX_CRANK_TASKS: usize = 8;
+
+    /// Whether a dispute over `amount` goes to the arbiter panel.
+    pub fn panel_required(&self, amount: u64) -> bool {
+        !self.arbiter_panel.is_empty() && amount >= self.panel_min_amount
+    }
+
+    /// Fee charged on a release: the seller's tier override wins over the
+    /// category override, which wins over the default `fee_bps`.
+    pub fn fee_bps_for(&self, category: &Category, tier: Option<SellerTier>) -> u16 {
+        tier.and_then(|tier| self.tier_fees.iter().find(|fee| fee.tier == tier))
+            .map(|fee| fee.fee_bps)
+            .or_else(|| {
+                self.category_fees
+                    .iter()
+                    .find(|fee| &fee.category == category)
+                    .map(|fee| fee.fee_bps)
+            })
+            .unwrap_or(self.fee_bps)
+    }
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub struct CategoryFee{
+    pub category:Category,
+    pub fee_bps:u16,
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub struct TierFee{
+    pub tier:SellerTier,
+    pub fee_bps:u16,
+}
+
+/// A mint buyers may pay with, and the price feed quoting it in USD.
+#[account]
+#[derive(InitSpace)]
+pub struct AcceptedMint{
+    pub mint:Pubkey,
+    pub price_feed:Pubkey,
+    pub accepted_mint_bump:u8,
+}
+
+impl AcceptedMint {
+    /// Whether the registration PDA `account` has been created, i.e. its
+    /// mint is a registered payment mint.
+    pub fn is_registered(account: &AccountInfo) -> bool {
+        !account.data_is_empty() && *account.owner == crate::ID
+    }
+}
+
+#[event]
+pub struct ArbiterPanelUpdated {
+    pub arbiters: Vec<Pubkey>,
+    pub threshold: u8,
+    pub min_amount: u64,
+}
+
+/// What `crank` does with a task's target.
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,PartialEq,Eq)]
+pub enum CrankTask {
+    /// `expire_escrow` on an escrow past its shipping deadline.
+    ExpireEscrow,
+    /// `bill_subscription` on a subscription due for billing.
+    BillSubscription,
+    /// `withdraw_escrow` on an escrow whose inspection window after delivery
+    /// has passed.
+    ReleaseEscrow,
+}
+
+#[event]
+pub struct CrankRun {
+    pub keeper: Pubkey,
+    /// Tasks that earned a reward.
+    pub tasks: u8,
+}
+
+#[event]
+pub struct KeeperRewarded {
+    pub keeper: Pubkey,
+    pub target: Pubkey,
+    pub mint: Pubkey,
+    pub reward: u64,
+}
+
+#[event]
+pub struct PaymentMintRegistered {
+    pub mint: Pubkey,
+    pub price_feed: Pubkey,
+}
@@ -3,5 +3,17 @@ pub mod cart;
 pub mod payment;
 pub mod order;
 pub mod escrow;
+pub mod marketplace;
+pub mod oracle;
+pub mod payment_plan;
+pub mod subscription;
+pub mod seller;
+pub mod coupon;
+pub mod loyalty;
+pub mod gift_card;
+pub mod receipt;
+pub mod relay;
+pub mod dispute;
+pub mod archive;
 
-pub use product::*;
\ No newline at end of file
+pub use product::*;
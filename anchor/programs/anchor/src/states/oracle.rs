@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// Locally writable price feed, only read when the program is built with
+/// the `localnet` feature.
+#[account]
+#[derive(InitSpace)]
+pub struct MockPriceFeed{
+    pub authority:Pubkey,
+    pub price:i64,
+    pub conf:u64,
+    pub expo:i32,
+    pub publish_time:i64,
+    pub mock_price_feed_bump:u8,
+}
@@ -4,6 +4,7 @@ use anchor_lang::prelude::*;
 #[derive(InitSpace)]
 pub struct Order{
     pub order_id:[u8;16],
+    pub payment:Pubkey,
     #[max_len(64)]
     pub payment_id:String,
     pub tracking_id:[u8;16],
@@ -11,17 +12,38 @@ pub struct Order{
     pub order_tracking:OrderTracking,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Set when tracking reaches `Delivered`; starts the inspection window.
+    pub delivered_at: Option<i64>,
     pub order_bump:u8,
 }
 
-#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+impl Order {
+    /// Status for an order whose payment is refunded in full: one that had
+    /// already shipped comes back as a return, anything earlier just failed.
+    pub fn refunded_status(&self) -> OrderStatus {
+        if self.order_tracking >= OrderTracking::InTransit {
+            OrderStatus::Returned
+        } else {
+            OrderStatus::Failed
+        }
+    }
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
 pub enum OrderStatus {
     Pending,
     Placed,
     Failed,
     Returned
 }
-#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+#[event]
+pub struct OrderTrackingUpdated {
+    pub order: Pubkey,
+    pub payment: Pubkey,
+    pub order_tracking: OrderTracking,
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq,PartialOrd,Ord)]
 pub enum OrderTracking {
     WatingForOrders,
     Booked,
@@ -1,19 +1,97 @@
 use anchor_lang::prelude::*;
+use crate::error::EcomError;
 
 #[account]
 #[derive(InitSpace)]
 pub struct Payment{
     pub payment_id: [u8;16],
+    pub owner: Pubkey,
+    pub nonce: u64,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub quantity: u64,
     pub payment_amount: u64,
+    /// `payment_amount` in the product's pricing unit: token base units, or
+    /// cents for USD-priced products.
+    pub priced_amount: u64,
+    pub coupon: Option<Pubkey>,
+    pub coupon_discount: u64,
+    pub points_redeemed: u64,
+    pub refunded_amount: u64,
+    pub marketplace_fee: u64,
     pub product_pubkey:Pubkey,
     pub payment_method:PaymentMethod,
     pub payment_status:PaymentStatus,
     pub time_stamp:i64,
-    #[max_len(3)]
-    pub tx_signature:Option<String>,
+    /// Set by the program when tokens land in the escrow vault.
+    pub funding:Option<FundingRecord>,
+    pub payment_plan:Option<Pubkey>,
+    /// Paid the rent for this payment, its escrow and its order, and gets
+    /// it back when they are closed.
+    pub rent_payer: Pubkey,
     pub payment_bump:u8,
 }
 
+impl Payment {
+    /// Converts `amount` of this payment's tokens into the product's pricing
+    /// unit, pro rata to what the buyer was charged.
+    pub fn priced_share(&self, amount: u64) -> Result<u64> {
+        if self.payment_amount == 0 {
+            return Ok(0);
+        }
+        let share = (self.priced_amount as u128) * (amount as u128) / (self.payment_amount as u128);
+        u64::try_from(share).map_err(|_| EcomError::MathOverflow.into())
+    }
+
+    /// Records tokens that just reached `vault`. Instalments add to the
+    /// running totals; slot and time always point at the latest deposit.
+    pub fn record_funding(&mut self, vault: Pubkey, deposited: u64, received: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let (total_deposited, total_received) = match &self.funding {
+            Some(funding) => (
+                funding.deposited.checked_add(deposited).ok_or(EcomError::MathOverflow)?,
+                funding.received.checked_add(received).ok_or(EcomError::MathOverflow)?,
+            ),
+            None => (deposited, received),
+        };
+        self.funding = Some(FundingRecord {
+            slot: clock.slot,
+            funded_at: clock.unix_timestamp,
+            vault,
+            deposited: total_deposited,
+            received: total_received,
+        });
+        Ok(())
+    }
+}
+
+/// Where and when a payment was funded. `deposited` is what left the payer's
+/// accounts, `received` what the vault was credited after transfer fees.
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct FundingRecord {
+    pub slot: u64,
+    pub funded_at: i64,
+    pub vault: Pubkey,
+    pub deposited: u64,
+    pub received: u64,
+}
+
+/// Per-buyer index of purchases. `payment_count` is the nonce of the next payment
+/// and `open_purchases` holds the payments that have not been settled yet.
+#[account]
+#[derive(InitSpace)]
+pub struct PurchaseList{
+    #[max_len(40)]
+    pub open_purchases: Vec<Pubkey>,
+    pub payment_count: u64,
+    pub purchase_list_bump: u8,
+}
+
+impl PurchaseList {
+    pub const MAX_OPEN_PURCHASES: usize = 40;
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, AnchorSerialize, AnchorDeserialize, InitSpace, PartialEq, Eq)]
 pub enum PaymentMethod {
     SOL,
@@ -28,4 +106,6 @@ pub enum PaymentStatus {
     Success,
     Pending,
     Failed,
+    PartiallyRefunded,
+    Refunded,
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+/// Instalment schedule for a payment. The escrow is topped up one
+/// instalment at a time and the order may only ship once
+/// `release_share_bps` of the total has been paid.
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentPlan{
+    pub payment:Pubkey,
+    pub buyer:Pubkey,
+    pub seller:Pubkey,
+    pub total_amount:u64,
+    pub paid_amount:u64,
+    #[max_len(12)]
+    pub installments:Vec<Installment>,
+    pub release_share_bps:u16,
+    pub grace_period:i64,
+    pub default_fee_bps:u16,
+    pub plan_status:PlanStatus,
+    pub created_at:i64,
+    pub payment_plan_bump:u8,
+}
+
+impl PaymentPlan {
+    pub const MAX_INSTALLMENTS: usize = 12;
+
+    pub fn next_unpaid(&self) -> Option<usize> {
+        self.installments.iter().position(|installment| !installment.paid)
+    }
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace)]
+pub struct Installment{
+    pub amount:u64,
+    pub due_at:i64,
+    pub paid:bool,
+}
+
+#[derive(Clone,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
+pub enum PlanStatus {
+    Active,
+    Completed,
+    Defaulted,
+}
+
+#[event]
+pub struct InstallmentPaid {
+    pub payment: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    pub paid_amount: u64,
+}
+
+#[event]
+pub struct PaymentPlanDefaulted {
+    pub payment: Pubkey,
+    pub refunded: u64,
+    pub retained: u64,
+}
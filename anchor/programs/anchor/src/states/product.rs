@@ -17,6 +17,14 @@ pub struct Product {
     #[max_len(150)]
     pub product_imgurl: String,
     pub price: u32,
+    pub price_currency: PriceCurrency,
+    /// The only mint a token-priced product can be paid in. USD-priced
+    /// products take any mint the marketplace has registered instead.
+    pub mint: Pubkey,
+    pub discount_bps: u16,
+    pub order_fee: u32,
+    #[max_len(5)]
+    pub payout_splits: Vec<PayoutSplit>,
     pub rating: f32,
     pub stock_status: Stock,
     pub creation_bump:u8,
@@ -39,7 +47,43 @@ pub struct ProductCreated {
     pub division: Division,
 }
 
+#[event]
+pub struct ProductPricingUpdated {
+    pub product_pubkey: Pubkey,
+    pub price: u32,
+    pub price_currency: PriceCurrency,
+    pub discount_bps: u16,
+    pub order_fee: u32,
+}
+
+/// Share of the seller's proceeds paid to `recipient` on release. When a
+/// product has splits, shares add up to 10,000 bps and any rounding
+/// remainder goes to the first recipient.
 #[derive(Clone, AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct PayoutSplit {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
+
+impl Product {
+    pub const MAX_PAYOUT_SPLITS: usize = 5;
+}
+
+#[event]
+pub struct PayoutSplitsUpdated {
+    pub product_pubkey: Pubkey,
+    pub payout_splits: Vec<PayoutSplit>,
+}
+
+/// Unit of `Product.price` and `Product.order_fee`: base units of the
+/// payment mint, or US cents converted at checkout through a price feed.
+#[derive(Clone, Copy, AnchorSerialize, AnchorDeserialize, InitSpace, PartialEq, Eq)]
+pub enum PriceCurrency {
+    Token,
+    UsdCents,
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize, InitSpace, PartialEq, Eq)]
 pub enum Category {
     Electronics,
     BeautyAndPersonalCare,
@@ -57,6 +101,7 @@ pub enum Division {
     ComputerPeripherals,
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Clone, AnchorSerialize, AnchorDeserialize, InitSpace)]
 pub enum Stock {
     OutOfStock,
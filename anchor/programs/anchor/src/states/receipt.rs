@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+/// Proof of purchase behind a one-of-one receipt token. The receipt mint's
+/// authority is this PDA, and the program only mints from it once, at
+/// settlement, so anyone can verify a receipt by deriving
+/// `[b"receipt", payment]` and checking it against the mint.
+#[account]
+#[derive(InitSpace)]
+pub struct Receipt{
+    pub payment:Pubkey,
+    pub mint:Pubkey,
+    pub buyer:Pubkey,
+    pub seller:Pubkey,
+    pub product:Pubkey,
+    /// Missing when the order was never placed before settlement.
+    pub order_id:Option<[u8;16]>,
+    pub amount:u64,
+    pub settled_at:i64,
+    pub receipt_bump:u8,
+}
+
+#[event]
+pub struct ReceiptMinted {
+    pub receipt: Pubkey,
+    pub mint: Pubkey,
+    pub payment: Pubkey,
+    pub buyer: Pubkey,
+}
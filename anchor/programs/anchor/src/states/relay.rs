@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+/// What a buyer signs off-chain to let a relayer check out on their behalf.
+/// The signed message is `signed_message`: a domain tag and the program ID,
+/// then the Borsh encoding of this struct.
+#[derive(Clone,AnchorDeserialize,AnchorSerialize)]
+pub struct PaymentIntent{
+    pub buyer:Pubkey,
+    pub product:Pubkey,
+    pub mint:Pubkey,
+    pub quantity:u64,
+    /// Most the buyer agrees to have pulled from their account, transfer
+    /// fees included, so a price change can't overcharge them.
+    pub max_amount:u64,
+    pub nonce:u64,
+    pub expires_at:i64,
+}
+
+impl PaymentIntent {
+    pub const DOMAIN: &'static [u8] = b"ecom_dapp:payment_intent";
+
+    /// Tagged so the signature can't be passed off as some other message the
+    /// buyer signed, or replayed against another deployment of the program.
+    pub fn signed_message(&self) -> Result<Vec<u8>> {
+        let mut message = [Self::DOMAIN, crate::ID.as_ref()].concat();
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+/// Next intent nonce for a buyer. Intents must be relayed in nonce order,
+/// and each nonce is only accepted once.
+#[account]
+#[derive(InitSpace)]
+pub struct RelayNonce{
+    pub buyer:Pubkey,
+    pub next_nonce:u64,
+    pub relay_nonce_bump:u8,
+}
+
+#[event]
+pub struct PaymentIntentRelayed {
+    pub buyer: Pubkey,
+    pub relayer: Pubkey,
+    pub payment: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+}
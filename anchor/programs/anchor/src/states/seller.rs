@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+/// Marketplace-maintained record of a seller, created by the admin or on
+/// the seller's first dispute ruling.
+#[account]
+#[derive(InitSpace)]
+pub struct SellerProfile{
+    pub seller:Pubkey,
+    pub tier:SellerTier,
+    /// Dispute rulings: released to the seller, refunded to the buyer, split.
+    pub disputes_won:u32,
+    pub disputes_lost:u32,
+    pub disputes_split:u32,
+    pub seller_profile_bump:u8,
+}
+
+impl SellerProfile {
+    /// The fee tier every release path charges for the seller whose profile
+    /// PDA is `profile`. A seller without a profile, or whose profile is only
+    /// being created by this instruction, has no tier.
+    pub fn tier_of(profile: &AccountInfo) -> Result<Option<SellerTier>> {
+        if profile.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*profile.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let data = profile.try_borrow_data()?;
+        if !data.starts_with(Self::DISCRIMINATOR) {
+            return Ok(None);
+        }
+        Ok(Some(Self::try_deserialize(&mut &data[..])?.tier))
+    }
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
+pub enum SellerTier {
+    Standard,
+    Silver,
+    Gold,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+/// Recurring order of `quantity` units of `product`, billed every `interval`
+/// seconds from the buyer's token account through the delegate PDA
+/// `[b"payment_delegate", buyer]`, which relayed payments also spend through.
+#[account]
+#[derive(InitSpace)]
+pub struct Subscription{
+    pub buyer:Pubkey,
+    pub seller:Pubkey,
+    pub product:Pubkey,
+    pub mint:Pubkey,
+    pub quantity:u64,
+    pub interval:i64,
+    pub next_billing_at:i64,
+    pub billed_count:u64,
+    pub failed_count:u64,
+    pub consecutive_failures:u8,
+    pub last_failure:Option<BillingFailure>,
+    pub last_failure_at:i64,
+    pub subscription_status:SubscriptionStatus,
+    pub created_at:i64,
+    pub subscription_bump:u8,
+}
+
+impl Subscription {
+    pub const MIN_INTERVAL: i64 = 24 * 60 * 60;
+    pub const RETRY_DELAY: i64 = 24 * 60 * 60;
+    pub const MAX_CONSECUTIVE_FAILURES: u8 = 3;
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
+pub enum SubscriptionStatus {
+    Active,
+    Paused,
+    Cancelled,
+}
+
+#[derive(Clone,Copy,AnchorDeserialize,AnchorSerialize,InitSpace,PartialEq,Eq)]
+pub enum BillingFailure {
+    DelegateNotApproved,
+    AllowanceExceeded,
+    InsufficientBalance,
+    OutOfStock,
+}
+
+#[event]
+pub struct SubscriptionBilled {
+    pub subscription: Pubkey,
+    pub payment: Pubkey,
+    pub amount: u64,
+    pub next_billing_at: i64,
+}
+
+#[event]
+pub struct SubscriptionBillingFailed {
+    pub subscription: Pubkey,
+    pub payment: Pubkey,
+    pub reason: BillingFailure,
+    pub consecutive_failures: u8,
+}
+
+#[event]
+pub struct SubscriptionStatusChanged {
+    pub subscription: Pubkey,
+    pub subscription_status: SubscriptionStatus,
+}
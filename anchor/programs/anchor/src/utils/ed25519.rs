@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    instruction::Instruction,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use crate::error::EcomError;
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+/// Checks that the instruction right before the current one is an ed25519
+/// program instruction verifying `signer`'s signature over `message`.
+/// The ed25519 program itself rejects the transaction if the signature is
+/// bad, so all that is left is making sure it checked the right key and
+/// message.
+pub fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = load_current_index_checked(instructions_sysvar)? as usize;
+    require!(current > 0, EcomError::InvalidIntentSignature);
+    let ix = load_instruction_at_checked(current - 1, instructions_sysvar)?;
+    check_ed25519_instruction(&ix, signer, message)
+}
+
+fn check_ed25519_instruction(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let data = &ix.data;
+    require!(
+        ix.program_id == ed25519_program::ID
+            && ix.accounts.is_empty()
+            && data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN
+            && data[0] == 1,
+        EcomError::InvalidIntentSignature
+    );
+
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN];
+    let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+    let signature_offset = read(0) as usize;
+    let public_key_offset = read(4) as usize;
+    let message_offset = read(8) as usize;
+    let message_size = read(10) as usize;
+
+    // Every piece must live in the ed25519 instruction itself, not be
+    // pulled from some other instruction in the transaction.
+    require!(
+        read(2) == u16::MAX && read(6) == u16::MAX && read(12) == u16::MAX,
+        EcomError::InvalidIntentSignature
+    );
+    require!(
+        data.len() >= signature_offset + SIGNATURE_LEN
+            && data.len() >= public_key_offset + PUBKEY_LEN
+            && data.len() >= message_offset + message_size,
+        EcomError::InvalidIntentSignature
+    );
+    require!(
+        &data[public_key_offset..public_key_offset + PUBKEY_LEN] == signer.as_ref()
+            && &data[message_offset..message_offset + message_size] == message,
+        EcomError::InvalidIntentSignature
+    );
+    Ok(())
+}
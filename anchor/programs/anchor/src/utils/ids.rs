@@ -0,0 +1,21 @@
+use anchor_lang::solana_program::hash;
+
+/// First 16 bytes of the hash of `parts`, used for payment and order ids.
+pub fn derive_id(parts: &[&[u8]]) -> [u8; 16] {
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&hash::hash(&parts.concat()).to_bytes()[..16]);
+    id
+}
+
+/// Formats an id the way clients display it, e.g. `1b4e28ba-2fa1-11d2-883f-0016d3cca427`.
+pub fn id_to_uuid_string(id: &[u8; 16]) -> String {
+    let hex: String = id.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..],
+    )
+}
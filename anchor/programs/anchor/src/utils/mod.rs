@@ -0,0 +1,15 @@
+pub mod token;
+pub mod pricing;
+pub mod oracle;
+pub mod ids;
+pub mod ed25519;
+pub mod payout;
+pub mod receipt;
+
+pub use token::*;
+pub use pricing::*;
+pub use oracle::*;
+pub use ids::*;
+pub use ed25519::*;
+pub use payout::*;
+pub use receipt::*;
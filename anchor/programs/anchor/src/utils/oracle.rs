@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::error::EcomError;
+use crate::states::oracle::MockPriceFeed;
+use crate::utils::BPS_DENOMINATOR;
+
+/// Pyth pull-oracle receiver; owns every `PriceUpdateV2` account.
+pub const PYTH_RECEIVER_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// A price quote normalised across feed providers: USD per whole token is
+/// `price × 10^expo`, ± `conf` in the same units.
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+pub trait PriceFeedReader {
+    fn read_price(feed: &AccountInfo) -> Result<OraclePrice>;
+}
+
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial { _num_signatures: u8 },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    _feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+}
+
+impl PriceUpdateV2 {
+    /// Anchor account discriminator, `sha256("account:PriceUpdateV2")[..8]`.
+    const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+}
+
+pub struct PythPriceFeed;
+
+impl PriceFeedReader for PythPriceFeed {
+    fn read_price(feed: &AccountInfo) -> Result<OraclePrice> {
+        let data = feed.try_borrow_data()?;
+        // The receiver owns other account types too.
+        require!(
+            data.len() > 8 && data[..8] == PriceUpdateV2::DISCRIMINATOR,
+            EcomError::InvalidPriceFeed
+        );
+        let update = PriceUpdateV2::deserialize(&mut &data[8..])
+            .map_err(|_| EcomError::InvalidPriceFeed)?;
+        require!(
+            matches!(update.verification_level, VerificationLevel::Full),
+            EcomError::InvalidPriceFeed
+        );
+        Ok(OraclePrice {
+            price: update.price,
+            conf: update.conf,
+            expo: update.exponent,
+            publish_time: update.publish_time,
+        })
+    }
+}
+
+pub struct LocalPriceFeed;
+
+impl PriceFeedReader for LocalPriceFeed {
+    fn read_price(feed: &AccountInfo) -> Result<OraclePrice> {
+        let data = feed.try_borrow_data()?;
+        let mock = MockPriceFeed::try_deserialize(&mut &data[..])?;
+        Ok(OraclePrice {
+            price: mock.price,
+            conf: mock.conf,
+            expo: mock.expo,
+            publish_time: mock.publish_time,
+        })
+    }
+}
+
+/// Reads `feed` with the reader matching its owner and rejects quotes that
+/// are stale or too uncertain.
+pub fn load_price(feed: &AccountInfo, max_age: u64, max_confidence_bps: u16) -> Result<OraclePrice> {
+    let quote = if feed.owner == &PYTH_RECEIVER_ID {
+        PythPriceFeed::read_price(feed)?
+    } else if cfg!(feature = "localnet") && feed.owner == &crate::ID {
+        LocalPriceFeed::read_price(feed)?
+    } else {
+        return err!(EcomError::UntrustedPriceFeed);
+    };
+
+    require!(quote.price > 0, EcomError::InvalidPriceFeed);
+    let age = Clock::get()?.unix_timestamp.saturating_sub(quote.publish_time);
+    require!(age >= 0 && age as u64 <= max_age, EcomError::StalePrice);
+    require!(
+        (quote.conf as u128) * (BPS_DENOMINATOR as u128)
+            <= (quote.price as u128) * (max_confidence_bps as u128),
+        EcomError::PriceConfidenceTooWide
+    );
+    Ok(quote)
+}
+
+/// Converts a USD-cent amount into base units of a mint with `decimals`,
+/// rounding up so the seller is never short-changed.
+pub fn usd_cents_to_tokens(cents: u64, quote: &OraclePrice, decimals: u8) -> Result<u64> {
+    let pow10 = |exp: u32| 10u128.checked_pow(exp).ok_or(EcomError::MathOverflow);
+
+    let mut numerator = (cents as u128)
+        .checked_mul(pow10(decimals as u32)?)
+        .ok_or(EcomError::MathOverflow)?;
+    let mut denominator = (quote.price as u128)
+        .checked_mul(100)
+        .ok_or(EcomError::MathOverflow)?;
+    if quote.expo < 0 {
+        numerator = numerator
+            .checked_mul(pow10(quote.expo.unsigned_abs())?)
+            .ok_or(EcomError::MathOverflow)?;
+    } else {
+        denominator = denominator
+            .checked_mul(pow10(quote.expo as u32)?)
+            .ok_or(EcomError::MathOverflow)?;
+    }
+
+    let amount = numerator.div_ceil(denominator);
+    u64::try_from(amount).map_err(|_| EcomError::MathOverflow.into())
+}
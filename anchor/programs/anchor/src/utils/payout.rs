@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::error::EcomError;
+use crate::states::{escrow::PayoutSent, marketplace::Marketplace, seller::SellerProfile, PayoutSplit, Product};
+use crate::utils::{amount_with_fee, bps_of, split_by_bps, transfer_tokens};
+
+/// Resolves who receives `seller_amount` of a release: the seller alone, or
+/// each recipient of the product's split table, whose token accounts are
+/// passed as remaining accounts in table order.
+pub fn seller_payouts<'info>(
+    splits: &[PayoutSplit],
+    seller: Pubkey,
+    seller_ata: AccountInfo<'info>,
+    payout_accounts: &'info [AccountInfo<'info>],
+    mint: Pubkey,
+    seller_amount: u64,
+) -> Result<Vec<(Pubkey, AccountInfo<'info>, u64)>> {
+    if splits.is_empty() {
+        return Ok(vec![(seller, seller_ata, seller_amount)]);
+    }
+    require!(
+        payout_accounts.len() == splits.len(),
+        EcomError::MissingPayoutAccounts
+    );
+    let shares: Vec<u16> = splits.iter().map(|split| split.share_bps).collect();
+    let amounts = split_by_bps(seller_amount, &shares)?;
+    let mut payouts = Vec::with_capacity(splits.len());
+    for ((split, account), share) in splits.iter().zip(payout_accounts).zip(amounts) {
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(account)?;
+        require!(token_account.mint == mint, EcomError::InvalidMint);
+        require!(
+            token_account.owner == split.recipient && account.is_writable,
+            EcomError::PayoutRecipientMismatch
+        );
+        payouts.push((split.recipient, account.clone(), share));
+    }
+    Ok(payouts)
+}
+
+/// Token accounts the seller's side of a release can be paid into.
+pub struct ReleaseAccounts<'info> {
+    pub seller: Pubkey,
+    pub seller_ata: AccountInfo<'info>,
+    /// One per payout split recipient, in table order.
+    pub payout_accounts: &'info [AccountInfo<'info>],
+    pub keeper_vault: Option<AccountInfo<'info>>,
+    pub treasury_ata: AccountInfo<'info>,
+}
+
+/// The transfers that release part of an order to the seller's side.
+pub struct ReleasePlan<'info> {
+    pub fee_bps: u16,
+    pub fee_amount: u64,
+    pub seller_amount: u64,
+    /// Payout recipient (`None` for marketplace accounts), destination and
+    /// amount sent.
+    pub transfers: Vec<(Option<Pubkey>, AccountInfo<'info>, u64)>,
+}
+
+/// Plans a release of `amount` of the order, the same way for a buyer
+/// confirmation, a milestone and a dispute ruling: the marketplace fee comes
+/// off first and part of it funds keepers, the rest goes to the seller or
+/// the product's split recipients. Each hop is grossed up for transfer fees
+/// and capped by the `available` vault balance. With `sweep_to_treasury`,
+/// the treasury takes whatever is left so the vault can be closed.
+#[allow(clippy::too_many_arguments)]
+pub fn plan_release<'info>(
+    marketplace: &Marketplace,
+    product: &Product,
+    seller_profile: &AccountInfo<'info>,
+    accounts: ReleaseAccounts<'info>,
+    mint: &AccountInfo<'info>,
+    amount: u64,
+    available: u64,
+    sweep_to_treasury: bool,
+) -> Result<ReleasePlan<'info>> {
+    let fee_bps = marketplace.fee_bps_for(&product.category, SellerProfile::tier_of(seller_profile)?);
+    let fee_amount = bps_of(amount, fee_bps)?;
+    let seller_amount = amount - fee_amount;
+    let payouts = seller_payouts(
+        &product.payout_splits,
+        accounts.seller,
+        accounts.seller_ata,
+        accounts.payout_accounts,
+        mint.key(),
+        seller_amount,
+    )?;
+
+    let mut remaining = available;
+    let mut transfers = Vec::with_capacity(payouts.len() + 2);
+    for (recipient, account, share) in payouts {
+        let release = amount_with_fee(mint, share)?.min(remaining);
+        remaining -= release;
+        transfers.push((Some(recipient), account, release));
+    }
+    let keeper_amount = bps_of(fee_amount, marketplace.keeper_fee_bps)?;
+    if keeper_amount > 0 {
+        let keeper_vault = accounts.keeper_vault.ok_or(EcomError::KeeperVaultRequired)?;
+        let release = amount_with_fee(mint, keeper_amount)?.min(remaining);
+        remaining -= release;
+        transfers.push((None, keeper_vault, release));
+    }
+    let treasury_release = if sweep_to_treasury {
+        remaining
+    } else {
+        amount_with_fee(mint, fee_amount - keeper_amount)?.min(remaining)
+    };
+    transfers.push((None, accounts.treasury_ata, treasury_release));
+
+    Ok(ReleasePlan {
+        fee_bps,
+        fee_amount,
+        seller_amount,
+        transfers,
+    })
+}
+
+impl<'info> ReleasePlan<'info> {
+    /// Total leaving the vault.
+    pub fn sent(&self) -> u64 {
+        self.transfers.iter().map(|(_, _, release)| release).sum()
+    }
+
+    /// Sends every planned transfer out of `vault` and emits `PayoutSent`
+    /// for each recipient paid.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute(
+        &self,
+        token_program: &AccountInfo<'info>,
+        vault: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        decimals: u8,
+        signer_seeds: &[&[&[u8]]],
+        payment: Pubkey,
+    ) -> Result<()> {
+        for (recipient, to, release) in &self.transfers {
+            if *release == 0 {
+                continue;
+            }
+            transfer_tokens(
+                token_program.clone(),
+                vault.clone(),
+                mint.clone(),
+                to.clone(),
+                authority.clone(),
+                *release,
+                decimals,
+                signer_seeds,
+            )?;
+            if let Some(recipient) = recipient {
+                emit!(PayoutSent{
+                    payment,
+                    recipient: *recipient,
+                    amount: *release,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::error::EcomError;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Amount a buyer owes for `quantity` units: price × quantity, less the
+/// product discount, plus the flat per-order fee.
+pub fn amount_due(
+    unit_price: u64,
+    quantity: u64,
+    discount_bps: u16,
+    order_fee: u64,
+) -> Result<u64> {
+    require!(quantity > 0, EcomError::InvalidQuantity);
+    require!(
+        (discount_bps as u64) <= BPS_DENOMINATOR,
+        EcomError::InvalidBasisPoints
+    );
+    let subtotal = unit_price
+        .checked_mul(quantity)
+        .ok_or(EcomError::MathOverflow)?;
+    let discount = bps_of(subtotal, discount_bps)?;
+    subtotal
+        .checked_sub(discount)
+        .and_then(|amount| amount.checked_add(order_fee))
+        .ok_or(EcomError::MathOverflow.into())
+}
+
+/// `amount` × `bps` / 10,000, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(EcomError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(share).map_err(|_| EcomError::MathOverflow.into())
+}
+
+/// Splits `amount` by basis-point `shares` that must add up to 10,000.
+/// Each share is rounded down and the remainder goes to the first share.
+pub fn split_by_bps(amount: u64, shares: &[u16]) -> Result<Vec<u64>> {
+    let total_bps: u64 = shares.iter().map(|bps| *bps as u64).sum();
+    require!(
+        !shares.is_empty() && total_bps == BPS_DENOMINATOR,
+        EcomError::InvalidPayoutSplits
+    );
+    let mut parts = shares
+        .iter()
+        .map(|bps| bps_of(amount, *bps))
+        .collect::<Result<Vec<u64>>>()?;
+    let remainder = amount - parts.iter().sum::<u64>();
+    parts[0] += remainder;
+    Ok(parts)
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount};
+use crate::states::receipt::{Receipt, ReceiptMinted};
+
+/// Writes `details` to the receipt PDA of a settled payment and mints its
+/// one receipt token to the buyer, for every path that pays the seller.
+pub fn mint_receipt<'info>(
+    receipt: &mut Account<'info, Receipt>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    buyer_receipt_ata: &InterfaceAccount<'info, TokenAccount>,
+    token_program: AccountInfo<'info>,
+    details: Receipt,
+) -> Result<()> {
+    let payment = details.payment;
+    let buyer = details.buyer;
+    let receipt_bump = details.receipt_bump;
+    receipt.set_inner(details);
+
+    let receipt_seeds: &[&[u8]] = &[b"receipt", payment.as_ref(), &[receipt_bump]];
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program,
+            MintTo {
+                mint: receipt_mint.to_account_info(),
+                to: buyer_receipt_ata.to_account_info(),
+                authority: receipt.to_account_info(),
+            },
+            &[receipt_seeds],
+        ),
+        1,
+    )?;
+    emit!(ReceiptMinted{
+        receipt: receipt.key(),
+        mint: receipt_mint.key(),
+        payment,
+        buyer,
+    });
+    Ok(())
+}
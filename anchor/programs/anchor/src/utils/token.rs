@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+};
+use anchor_spl::token_interface::{self, CloseAccount, TransferChecked};
+use crate::error::EcomError;
+
+/// Amount that has to be sent so the receiver ends up with exactly `net`.
+/// Legacy SPL mints and Token-2022 mints without a transfer fee return `net` unchanged.
+pub fn amount_with_fee(mint: &AccountInfo, net: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let gross = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => {
+            let fee = config
+                .calculate_inverse_epoch_fee(Clock::get()?.epoch, net)
+                .ok_or(EcomError::MathOverflow)?;
+            net.checked_add(fee).ok_or(EcomError::MathOverflow)?
+        }
+        Err(_) => net,
+    };
+    Ok(gross)
+}
+
+/// `transfer_checked` through whichever token program owns the mint.
+/// Pass `signer_seeds` when the authority is a program PDA.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_tokens<'info>(
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from,
+        mint,
+        to,
+        authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program,
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, decimals)
+}
+
+/// Transfer fees a Token-2022 account is holding back; always zero for
+/// legacy SPL accounts.
+fn withheld_fees(account: &AccountInfo) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map(|fees| u64::from(fees.withheld_amount))
+        .unwrap_or(0))
+}
+
+/// Closes an emptied escrow vault and returns its rent to `destination`.
+/// A vault still holding withheld transfer fees can't be closed, so those
+/// are harvested to `mint` first; `mint` must be writable for that.
+pub fn close_vault<'info>(
+    token_program: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if withheld_fees(&vault)? > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.clone(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.clone(),
+                    mint,
+                },
+            ),
+            vec![vault.clone()],
+        )?;
+    }
+
+    let cpi_accounts = CloseAccount {
+        account: vault,
+        destination,
+        authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program,
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::close_account(cpi_ctx)
+}
@@ -1,15 +1,25 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { EcomDapp } from "../target/types/ecom_dapp";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { Ed25519Program, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_INSTRUCTIONS_PUBKEY, Transaction } from "@solana/web3.js";
 import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { BN } from "@coral-xyz/anchor";
 import { 
   createMint, 
   mintTo, 
   TOKEN_PROGRAM_ID,           
-  getOrCreateAssociatedTokenAccount
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  approve,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync
 } from "@solana/spl-token";
 
 describe("anchor", () => {
@@ -23,6 +33,7 @@ describe("anchor", () => {
   let seller = Keypair.generate();
   let consumer = Keypair.generate();
   let buyer = Keypair.generate();
+  let treasury = Keypair.generate();
   
 
   let product_id: string[] = [];
@@ -54,6 +65,74 @@ describe("anchor", () => {
       hex.slice(20),
     ].join("-");
   }
+  function productPdaFor(name: string): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("product"), seller.publicKey.toBuffer(), Buffer.from(name)],
+      program.programId
+    )[0];
+  }
+  function pointsMintPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("loyalty_mint")],
+      program.programId
+    )[0];
+  }
+
+  function marketplacePda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("marketplace")],
+      program.programId
+    )[0];
+  }
+  function mockPriceFeedPda(): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("mock_price_feed"), owner.publicKey.toBuffer()],
+      program.programId
+    )[0];
+  }
+  function acceptedMintPda(mint: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("accepted_mint"), mint.toBuffer()],
+      program.programId
+    )[0];
+  }
+  function sellerProfilePdaFor(seller: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_profile"), seller.toBuffer()],
+      program.programId
+    )[0];
+  }
+  function purchaseListPdaFor(buyer: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("purchase_list"), buyer.toBuffer()],
+      program.programId
+    )[0];
+  }
+  function paymentPdaFor(buyer: PublicKey, nonce: number): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("payment"),
+        buyer.toBuffer(),
+        new BN(nonce).toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+  }
+  function escrowPdaFor(payment: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), payment.toBuffer()],
+      program.programId
+    )[0];
+  }
+  // What a buyer signs for a relayed checkout: the domain tag and program ID,
+  // then the Borsh-encoded intent.
+  function intentMessage(intent: any): Buffer {
+    return Buffer.concat([
+      Buffer.from("ecom_dapp:payment_intent"),
+      program.programId.toBuffer(),
+      program.coder.types.encode("paymentIntent", intent),
+    ]);
+  }
   async function getSolPrice(): Promise<number> {
     const res = await fetch("https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd");
     const data = (await res.json()) as { solana: { usd: number } };
@@ -73,6 +152,14 @@ describe("anchor", () => {
     );
     await new Promise((resolve) => setTimeout(resolve, 1000));
 
+    mint = await createMint(
+      provider.connection,
+      owner.payer,
+      owner.publicKey,
+      null,
+      6
+    );
+
     product_name[0] = "iPhone 17 Pro";
     product_short_description[0] = "Premium flagship smartphone";
     price[0] = 799;
@@ -108,6 +195,7 @@ describe("anchor", () => {
         seller: seller.publicKey,
         product: productPda,
         productList: productListPda,
+        mint,
         systemProgram: SYSTEM_PROGRAM_ID,
       } as any)
       .signers([seller])
@@ -162,6 +250,7 @@ describe("anchor", () => {
         seller: seller.publicKey,
         product: productPda,
         productList: productListPda,
+        mint,
         systemProgram: SYSTEM_PROGRAM_ID,
       } as any)
       .signers([seller])
@@ -216,6 +305,7 @@ describe("anchor", () => {
         seller: seller.publicKey,
         product: productPda,
         productList: productListPda,
+        mint,
         systemProgram: SYSTEM_PROGRAM_ID,
       } as any)
       .signers([seller])
@@ -363,26 +453,229 @@ describe("anchor", () => {
     // expect(cartList.cartList.length).to.be.greaterThan(0);
   });
 
-  it("should intialize and create payment", async () => {
+  it("should initialize marketplace and register payment mint", async () => {
+    const [marketplacePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("marketplace")],
+      program.programId
+    );
+    const tx = await program.methods.initializeMarketplace(
+      new BN(60),
+      200,
+    ).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    console.log("Transaction Signature: ",tx);
+
+    const marketplace = await program.account.marketplace.fetch(marketplacePda);
+    expect(marketplace.admin.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(Number(marketplace.maxPriceAge)).to.equal(60);
+
+    // $150.00 per token, ±$0.50; needs the program built with `localnet`.
+    await program.methods.setMockPrice(
+      new BN(15_000_000_000),
+      new BN(50_000_000),
+      -8,
+    ).accounts({
+      authority: owner.publicKey,
+      mockPriceFeed: mockPriceFeedPda(),
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    await program.methods.registerPaymentMint().accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda,
+      mint,
+      acceptedMint: acceptedMintPda(mint),
+      priceFeed: mockPriceFeedPda(),
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    const accepted = await program.account.acceptedMint.fetch(acceptedMintPda(mint));
+    expect(accepted.mint.toBase58()).to.equal(mint.toBase58());
+    expect(accepted.priceFeed.toBase58()).to.equal(mockPriceFeedPda().toBase58());
+  });
+
+  it("should price a USD product in tokens and reject a stale quote", async () => {
     await provider.connection.requestAirdrop(
-      owner.publicKey,
+      consumer.publicKey,
       2 * anchor.web3.LAMPORTS_PER_SOL
     );
     await new Promise((resolve) => setTimeout(resolve, 1000));
 
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), owner.publicKey.toBuffer()],
+    const name = "USB-C Cable";
+    await program.methods
+      .createProduct(
+        name,
+        "Braided charging cable",
+        1,
+        { electronics: {} },
+        { computerPeripherals: {} },
+        seller_name[0],
+        product_imgurl[0]
+      )
+      .accounts({
+        seller: seller.publicKey,
+        product: productPdaFor(name),
+        productList: PublicKey.findProgramAddressSync(
+          [Buffer.from("product_list"), seller.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mint,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any)
+      .signers([seller])
+      .rpc();
+    // $15.00, quoted in cents.
+    await program.methods.updateProductPricing(1500, { usdCents: {} }, 0, 0).accounts({
+      seller: seller.publicKey,
+      product: productPdaFor(name),
+    } as any).signers([seller]).rpc();
+
+    const usdPaymentAccounts = (nonce: number) => ({
+      signer: consumer.publicKey,
+      purchaseList: purchaseListPdaFor(consumer.publicKey),
+      payments: paymentPdaFor(consumer.publicKey, nonce),
+      product: productPdaFor(name),
+      cart: null,
+      mint,
+      marketplace: marketplacePda(),
+      acceptedMint: acceptedMintPda(mint),
+      priceFeed: mockPriceFeedPda(),
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    });
+
+    await program.methods.createPayment(1, null, new BN(0))
+      .accounts(usdPaymentAccounts(0) as any)
+      .signers([consumer])
+      .rpc();
+    // 1500 cents / $150 per token = 0.1 token = 100_000 base units at 6 decimals.
+    const payment = await program.account.payment.fetch(paymentPdaFor(consumer.publicKey, 0));
+    expect(Number(payment.paymentAmount)).to.equal(100_000);
+    expect(Number(payment.pricedAmount)).to.equal(1500);
+
+    await program.methods.setPriceRules(new BN(1), 200).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    try {
+      await program.methods.createPayment(1, null, new BN(0))
+        .accounts(usdPaymentAccounts(1) as any)
+        .signers([consumer])
+        .rpc();
+      expect.fail("a stale quote should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("StalePrice");
+    } finally {
+      await program.methods.setPriceRules(new BN(60), 200).accounts({
+        admin: owner.publicKey,
+        marketplace: marketplacePda(),
+      } as any).rpc();
+    }
+  });
+
+  it("should set up the loyalty points programme", async () => {
+    const [loyaltyPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("loyalty")],
       program.programId
     );
-    const totalPaymentAmount = new BN(total_amount || 1459);
-    
+    await program.methods.initializeLoyalty(new BN(100)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+      loyalty: loyaltyPda,
+      pointsMint: pointsMintPda(),
+      systemProgram: SYSTEM_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    await program.methods.setLoyaltyRate({ electronics: {} }, 100).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+      loyalty: loyaltyPda,
+    } as any).rpc();
+
+    const loyalty = await program.account.loyaltyProgram.fetch(loyaltyPda);
+    expect(loyalty.pointsMint.toBase58()).to.equal(pointsMintPda().toBase58());
+    expect(loyalty.categoryRates[0].rateBps).to.equal(100);
+  });
+
+  it("should reject paying a token-priced product in another mint", async () => {
+    await provider.connection.requestAirdrop(
+      owner.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    const product = await program.account.product.fetch(productPdaFor(product_name[2]));
+    expect(product.mint.toBase58()).to.equal(mint.toBase58());
+
+    const otherMint = await createMint(
+      provider.connection,
+      owner.payer,
+      owner.publicKey,
+      null,
+      6
+    );
+    try {
+      await program.methods.createPayment(
+        2,
+        null,
+        new BN(0),
+      ).accounts({
+        signer: owner.publicKey,
+        purchaseList: purchaseListPdaFor(owner.publicKey),
+        payments: paymentPdaFor(owner.publicKey, 0),
+        product: productPdaFor(product_name[2]),
+        cart: null,
+        mint: otherMint,
+        marketplace: null,
+        acceptedMint: null,
+        priceFeed: null,
+        coupon: null,
+        couponRedemption: null,
+        loyalty: null,
+        pointsMint: null,
+        buyerPoints: null,
+        pointsTokenProgram: null,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any).rpc();
+      expect.fail("payment in a foreign mint should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("ProductMintMismatch");
+    }
+  });
+
+  it("should intialize and create payment", async () => {
+
+    const paymentPda = paymentPdaFor(owner.publicKey, 0);
     const tx = await program.methods.createPayment(
-      totalPaymentAmount,
-      paymentPda,
+      2,
       null,
+      new BN(0),
     ).accounts({
       signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
       payments: paymentPda,
+      product: productPdaFor(product_name[2]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
       systemProgram: SYSTEM_PROGRAM_ID,
     } as any).rpc();
     console.log("Transaction Signature: ",tx);
@@ -390,45 +683,59 @@ describe("anchor", () => {
     const payment = await program.account.payment.fetch(paymentPda);
     console.log("Payment Details: ",payment);
     console.log("Payment Id: ",bytesToUuid(payment.paymentId));
+
+    total_amount = Number(payment.paymentAmount);
+    expect(total_amount).to.equal(price[2] * 2);
   });
 
-  it("should create escrow and mint token", async () => {
-    mint = await createMint(
-      provider.connection,
-      owner.payer,
-      owner.publicKey,
+  it("should open a second purchase while the first is pending", async () => {
+    const paymentPda = paymentPdaFor(owner.publicKey, 1);
+
+    const tx = await program.methods.createPayment(
+      1,
       null,
-      6
-    );
-        
-    const [escrowPda] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("escrow"),
-        owner.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), owner.publicKey.toBuffer()],
-      program.programId
+      new BN(0),
+    ).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[1]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    console.log("Transaction Signature: ",tx);
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
     );
+    console.log("Open Purchases: ",purchaseList.openPurchases.map((p) => p.toBase58()));
+
+    expect(Number(purchaseList.paymentCount)).to.equal(2);
+    expect(purchaseList.openPurchases.length).to.equal(2);
+  });
+
+  it("should create escrow and mint token", async () => {
+    const escrowPda = escrowPdaFor(paymentPdaFor(owner.publicKey, 0));
+    const paymentPda = paymentPdaFor(owner.publicKey, 0);
     
-    escrowAta = (
-      await getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        owner.payer,
-        mint,
-        escrowPda,
-        true
-      )
-    ).address;
+    escrowAta = getAssociatedTokenAddressSync(mint, escrowPda, true);
 
     buyerAta = (
       await getOrCreateAssociatedTokenAccount(
         provider.connection,
         owner.payer,
         mint,
-        buyer.publicKey
+        owner.publicKey
       )
     ).address;
 
@@ -459,10 +766,63 @@ describe("anchor", () => {
       100_000_000_000
     )
     
+    // The escrow's buyer has to be whoever opened the payment.
+    try {
+      await program.methods.createEscrow(
+        buyer.publicKey,
+        seller.publicKey,
+        [],
+      ).accounts({
+        owner: owner.publicKey,
+        escrow: escrowPda,
+        payment: paymentPda,
+        userAta: userAta,
+        escrowAta: escrowAta,
+        buyerAta: buyerAta,
+        sellerAta: sellerAta,
+        mint: mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        marketplace: marketplacePda(),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any).rpc();
+      expect.fail("an escrow for someone else's payment should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("BuyerMismatch");
+    }
+
+    // A schedule has to end on delivery, so this one is rejected.
+    try {
+      await program.methods.createEscrow(
+        owner.publicKey,
+        seller.publicKey,
+        [
+          { stage: { booked: {} }, releaseBps: 3000 },
+          { stage: { shipped: {} }, releaseBps: 7000 },
+        ],
+      ).accounts({
+        owner: owner.publicKey,
+        escrow: escrowPda,
+        payment: paymentPda,
+        userAta: userAta,
+        escrowAta: escrowAta,
+        buyerAta: buyerAta,
+        sellerAta: sellerAta,
+        mint: mint,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        marketplace: marketplacePda(),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      } as any).rpc();
+      expect.fail("a schedule ending before delivery should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("InvalidMilestoneSchedule");
+    }
+
     const tx = await program.methods.createEscrow(
-      buyer.publicKey,
+      owner.publicKey,
       seller.publicKey,
-      new anchor.BN(total_amount),
+      [],
     ).accounts({
       owner: owner.publicKey,
       escrow: escrowPda,
@@ -471,8 +831,11 @@ describe("anchor", () => {
       escrowAta: escrowAta,
       buyerAta: buyerAta,
       sellerAta: sellerAta,
+      mint: mint,
       systemProgram: SystemProgram.programId,
-      tokenProgram: TOKEN_PROGRAM_ID
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     } as any).rpc()
     console.log("Transaction Signature: ",tx);
     
@@ -491,23 +854,42 @@ describe("anchor", () => {
   });
 
   it("should buyer deposite funds to escrow",async()=>{
-    const [escrowPda] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("escrow"),
-        owner.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
+    const escrowPda = escrowPdaFor(paymentPdaFor(owner.publicKey, 0));
     const lamports = (await convertUsdToLamports(total_amount));
+    try {
+      await program.methods.depositEscrow(new BN(total_amount), new BN(0)).accounts({
+        escrow: escrowPda,
+        payment: paymentPdaFor(owner.publicKey, 0),
+        owner: owner.publicKey,
+        userAta: userAta,
+        escrowAta: escrowAta,
+        buyerAta: buyerAta,
+        sellerAta: userAta,
+        mint: mint,
+        giftCard: null,
+        giftCardVault: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any).rpc();
+      expect.fail("a seller account not owned by the seller should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("InvalidSellerTokenAccount");
+    }
+
     const deposite_tx = await program.methods.depositEscrow(
-      1,
+      new BN(total_amount),
+      new BN(0),
     ).accounts({
       escrow: escrowPda,
+      payment: paymentPdaFor(owner.publicKey, 0),
       owner: owner.publicKey,
       userAta: userAta,
       escrowAta: escrowAta,
       buyerAta: buyerAta,
       sellerAta: sellerAta, 
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
       systemProgram: SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
     } as any).rpc();
@@ -530,76 +912,373 @@ describe("anchor", () => {
 
     const escrowDetails = await program.account.escrow.fetch(escrowPda);
     
-    expect(escrowDetails.releaseFund).to.be.true;
     expect(escrowDetails.escrowStatus).to.have.property("fundsReceived");
 
+    // The escrow is funded now, so a repeat deposit can't charge the buyer twice.
+    try {
+      await program.methods.depositEscrow(
+        new BN(total_amount),
+        new BN(0),
+      ).accounts({
+        escrow: escrowPda,
+        payment: paymentPdaFor(owner.publicKey, 0),
+        owner: owner.publicKey,
+        userAta: userAta,
+        escrowAta: escrowAta,
+        buyerAta: buyerAta,
+        sellerAta: sellerAta,
+        mint: mint,
+        giftCard: null,
+        giftCardVault: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any).rpc();
+      expect.fail("a second deposit should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("EscrowAlreadyFunded");
+    }
+
     });
 
   it("Escrow withdraws and sent to seller",async()=>{
-    const [escrowPda] = PublicKey.findProgramAddressSync(
-      [
-        Buffer.from("escrow"),
-        owner.publicKey.toBuffer(),
-      ],
+    const escrowPda = escrowPdaFor(paymentPdaFor(owner.publicKey, 0));
+    const paymentPda = paymentPdaFor(owner.publicKey, 0);
+    const escrowBefore = await program.account.escrow.fetch(escrowPda);
+    expect(escrowBefore.escrowStatus).to.have.property("fundsReceived");
+
+    const treasuryAta = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        owner.payer,
+        mint,
+        treasury.publicKey
+      )
+    ).address;
+    await program.methods.setMarketplaceFee(
+      250,
+      treasury.publicKey,
+    ).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const usd = 1499;
+    const lamports = (await convertUsdToLamports(usd));
+    const buyerPoints = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner.payer,
+      pointsMintPda(),
+      owner.publicKey,
+    );
+
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), paymentPda.toBuffer()],
       program.programId
     );
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), owner.publicKey.toBuffer()],
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), paymentPda.toBuffer()],
       program.programId
     );
-    const escrowBefore = await program.account.escrow.fetch(escrowPda);
-    expect(escrowBefore.releaseFund).to.be.true;
+    const buyerReceiptAta = getAssociatedTokenAddressSync(receiptMint, owner.publicKey);
+
+    try {
+      await program.methods.withdrawEscrow(
+        1,
+      ).accounts({
+        escrow: escrowPda,
+        payment:paymentPda,
+        purchaseList: purchaseListPdaFor(owner.publicKey),
+        authority: seller.publicKey,
+        owner: owner.publicKey,
+        userAta: userAta,
+        escrowAta: escrowAta,
+        buyerAta: buyerAta,
+        sellerAta: sellerAta, 
+        marketplace: marketplacePda(),
+        product: productPdaFor(product_name[2]),
+        sellerProfile: sellerProfilePdaFor(seller.publicKey),
+        treasuryAta: treasuryAta,
+        keeperVault: null,
+        mint: mint,
+        acceptedMint: acceptedMintPda(mint),
+        loyalty: PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0],
+        pointsMint: pointsMintPda(),
+        buyerPoints: buyerPoints.address,
+        pointsTokenProgram: TOKEN_PROGRAM_ID,
+        order: null,
+        receipt: receiptPda,
+        receiptMint: receiptMint,
+        buyerReceiptAta: buyerReceiptAta,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any).signers([seller]).rpc();
+      expect.fail("the seller should not release before delivery");
+    } catch (err) {
+      expect(String(err)).to.include("OrderNotDelivered");
+    }
 
-    const usd = 1499;
-    const lamports = (await convertUsdToLamports(usd));
     const withdraw_tx = await program.methods.withdrawEscrow(
       1,
     ).accounts({
       escrow: escrowPda,
       payment:paymentPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      authority: owner.publicKey,
       owner: owner.publicKey,
       userAta: userAta,
       escrowAta: escrowAta,
       buyerAta: buyerAta,
       sellerAta: sellerAta, 
+      marketplace: marketplacePda(),
+      product: productPdaFor(product_name[2]),
+      sellerProfile: sellerProfilePdaFor(seller.publicKey),
+      treasuryAta: treasuryAta,
+      keeperVault: null,
+      mint: mint,
+      acceptedMint: acceptedMintPda(mint),
+      loyalty: PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0],
+      pointsMint: pointsMintPda(),
+      buyerPoints: buyerPoints.address,
+      pointsTokenProgram: TOKEN_PROGRAM_ID,
+      order: null,
+      receipt: receiptPda,
+      receiptMint: receiptMint,
+      buyerReceiptAta: buyerReceiptAta,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
     } as any).rpc();
     console.log("Transaction Signature: ",withdraw_tx);
 
+    const receipt = await program.account.receipt.fetch(receiptPda);
+    const receiptBalance = await provider.connection.getTokenAccountBalance(buyerReceiptAta);
+    expect(receipt.mint.toBase58()).to.equal(receiptMint.toBase58());
+    expect(Number(receipt.amount)).to.equal(total_amount);
+    expect(receiptBalance.value.amount).to.equal("1");
+
+    const points = await provider.connection.getTokenAccountBalance(buyerPoints.address);
+    expect(Number(points.value.amount)).to.equal(Math.floor((total_amount * 100) / 10_000));
+
+    const settledPayment = await program.account.payment.fetch(paymentPda);
+    const wTreasury = await provider.connection.getTokenAccountBalance(treasuryAta);
+    const expectedFee = Math.floor((total_amount * 250) / 10_000);
+    expect(Number(settledPayment.marketplaceFee)).to.equal(expectedFee);
+    expect(Number(wTreasury.value.amount)).to.equal(expectedFee);
+
     console.log("AFTER WITHDRAWL...");
 
     console.log("Grand Total (solana): ",(lamports /LAMPORTS_PER_SOL).toFixed(2) + "SOL");
     console.log("Grand Total($): ",usd + "$");
     
     const wUser = await provider.connection.getTokenAccountBalance(userAta);
-    const wEscrow = await provider.connection.getTokenAccountBalance(escrowAta);
     const wBuyer = await provider.connection.getTokenAccountBalance(buyerAta);
     const wSeller = await provider.connection.getTokenAccountBalance(sellerAta)
 
     console.log("User Token Balance:", wUser.value.uiAmountString);
-    console.log("Escrow Token Balance:", wEscrow.value.uiAmountString);
     console.log("Buyer Token Balance:", wBuyer.value.uiAmountString);
     console.log("Seller Token Balance:", wSeller.value.uiAmountString)
+    expect(await provider.connection.getAccountInfo(escrowAta)).to.be.null;
     const escrowAfter = await program.account.escrow.fetch(escrowPda);
     
     expect(escrowAfter.escrowStatus).to.have.property("swapSuccess");
-    expect(escrowAfter.releaseFund).to.be.false;
     });
     
-  it("should check & confirm payment status", async () => {
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), owner.publicKey.toBuffer()],
+  it("should deposit and release a Token-2022 transfer-fee mint", async () => {
+    // 1% transfer fee on every hop, withheld in the receiving account.
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: owner.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          owner.publicKey,
+          owner.publicKey,
+          100,
+          BigInt(1_000_000),
+          TOKEN_2022_PROGRAM_ID,
+        ),
+        createInitializeMintInstruction(feeMint.publicKey, 6, owner.publicKey, null, TOKEN_2022_PROGRAM_ID),
+      ),
+      [feeMint],
+    );
+
+    const feeBuyer = Keypair.generate();
+    await provider.connection.requestAirdrop(feeBuyer.publicKey, 2 * LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    const ataFor = async (holder: PublicKey) =>
+      (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          owner.payer,
+          feeMint.publicKey,
+          holder,
+          false,
+          undefined,
+          undefined,
+          TOKEN_2022_PROGRAM_ID,
+        )
+      ).address;
+    const feeBuyerAta = await ataFor(feeBuyer.publicKey);
+    const feeSellerAta = await ataFor(seller.publicKey);
+    const feeTreasuryAta = await ataFor(treasury.publicKey);
+    await mintTo(
+      provider.connection,
+      owner.payer,
+      feeMint.publicKey,
+      feeBuyerAta,
+      owner.publicKey,
+      100_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID,
+    );
+
+    const name = "Charging Dock";
+    await program.methods
+      .createProduct(
+        name,
+        "Priced in a transfer-fee token",
+        10_000,
+        { electronics: {} },
+        { computerPeripherals: {} },
+        seller_name[0],
+        product_imgurl[0]
+      )
+      .accounts({
+        seller: seller.publicKey,
+        product: productPdaFor(name),
+        productList: PublicKey.findProgramAddressSync(
+          [Buffer.from("product_list"), seller.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mint: feeMint.publicKey,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any)
+      .signers([seller])
+      .rpc();
+
+    const paymentPda = paymentPdaFor(feeBuyer.publicKey, 0);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const feeEscrowAta = getAssociatedTokenAddressSync(feeMint.publicKey, escrowPda, true, TOKEN_2022_PROGRAM_ID);
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: feeBuyer.publicKey,
+      purchaseList: purchaseListPdaFor(feeBuyer.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(name),
+      cart: null,
+      mint: feeMint.publicKey,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([feeBuyer]).rpc();
+
+    await program.methods.createEscrow(feeBuyer.publicKey, seller.publicKey, []).accounts({
+      owner: feeBuyer.publicKey,
+      escrow: escrowPda,
+      payment: paymentPda,
+      userAta: feeBuyerAta,
+      escrowAta: feeEscrowAta,
+      buyerAta: feeBuyerAta,
+      sellerAta: feeSellerAta,
+      mint: feeMint.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).signers([feeBuyer]).rpc();
+
+    await program.methods.depositEscrow(new BN(10_000), new BN(0)).accounts({
+      owner: feeBuyer.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: feeBuyerAta,
+      escrowAta: feeEscrowAta,
+      buyerAta: feeBuyerAta,
+      sellerAta: feeSellerAta,
+      mint: feeMint.publicKey,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    } as any).signers([feeBuyer]).rpc();
+
+    // The buyer covered both hops, so the vault holds enough for the release.
+    const funded = await program.account.payment.fetch(paymentPda);
+    const vault = await getAccount(provider.connection, feeEscrowAta, undefined, TOKEN_2022_PROGRAM_ID);
+    expect(Number(funded.funding.received)).to.be.greaterThan(10_000);
+    expect(Number(vault.amount)).to.equal(Number(funded.funding.received));
+
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), paymentPda.toBuffer()],
       program.programId
     );
+    await program.methods.withdrawEscrow(1).accounts({
+      escrow: escrowPda,
+      payment: paymentPda,
+      purchaseList: purchaseListPdaFor(feeBuyer.publicKey),
+      authority: feeBuyer.publicKey,
+      owner: feeBuyer.publicKey,
+      userAta: feeBuyerAta,
+      escrowAta: feeEscrowAta,
+      buyerAta: feeBuyerAta,
+      sellerAta: feeSellerAta,
+      marketplace: marketplacePda(),
+      product: productPdaFor(name),
+      sellerProfile: sellerProfilePdaFor(seller.publicKey),
+      treasuryAta: feeTreasuryAta,
+      keeperVault: null,
+      mint: feeMint.publicKey,
+      acceptedMint: acceptedMintPda(feeMint.publicKey),
+      loyalty: PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0],
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: TOKEN_PROGRAM_ID,
+      order: null,
+      receipt: receiptPda,
+      receiptMint: receiptMint,
+      buyerReceiptAta: getAssociatedTokenAddressSync(receiptMint, feeBuyer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    } as any).signers([feeBuyer]).rpc();
+
+    // 2.5% marketplace fee; the seller nets the rest after the transfer fee.
+    const sellerAfter = await getAccount(provider.connection, feeSellerAta, undefined, TOKEN_2022_PROGRAM_ID);
+    const treasuryAfter = await getAccount(provider.connection, feeTreasuryAta, undefined, TOKEN_2022_PROGRAM_ID);
+    expect(Number(sellerAfter.amount)).to.equal(10_000 - 250);
+    expect(Number(treasuryAfter.amount)).to.be.greaterThan(0);
+    expect(await provider.connection.getAccountInfo(feeEscrowAta)).to.be.null;
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    expect(escrow.escrowStatus).to.have.property("swapSuccess");
+  });
+
+  it("should check & confirm payment status", async () => {
+    const paymentPda = paymentPdaFor(owner.publicKey, 0);
     
     const payment = await program.account.payment.fetch(paymentPda);
     
-    if (expect(payment.paymentStatus).to.have.property("success")) {
-      payment.txSignature = payment_tx;
-    }else{
-      console.log("Payment Not Intialized!");
-    }
+    expect(payment.funding).to.not.be.null;
+    expect(Number(payment.funding.received)).to.equal(total_amount);
+    expect(Number(payment.funding.slot)).to.be.greaterThan(0);
 
     console.log("Payment Details: ",payment);
     console.log("Payment Amount: ",Number(payment.paymentAmount));
@@ -608,15 +1287,11 @@ describe("anchor", () => {
   });
   it("should palce order and show details",async()=>{
     await provider.connection.requestAirdrop(signer.publicKey, 2 * LAMPORTS_PER_SOL);
+    const paymentPda = paymentPdaFor(signer.publicKey, 0);
     const [orderPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("order"), signer.publicKey.toBuffer()],
+      [Buffer.from("order"), paymentPda.toBuffer()],
       program.programId
     );console.log("Order PDA:", orderPda.toBase58());
-
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), signer.publicKey.toBuffer()],
-      program.programId
-    );
     const payment_id = (await program.account.payment.fetch(paymentPda)).paymentId;
     const order_tx = await program.methods.createOrder(
       String(bytesToUuid(payment_id)),
@@ -634,4 +1309,2277 @@ describe("anchor", () => {
     
     expect(order.orderStatus).to.have.property("placed");
     });
-});
\ No newline at end of file
+
+  it("should pay for a purchase in instalments", async () => {
+    const paymentPda = paymentPdaFor(owner.publicKey, 2);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const [marketplacePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("marketplace")],
+      program.programId
+    );
+    const [paymentPlanPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_plan"), paymentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    const planEscrowAta = getAssociatedTokenAddressSync(mint, escrowPda, true);
+
+    await program.methods.createEscrow(
+      owner.publicKey,
+      seller.publicKey,
+      [],
+    ).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: planEscrowAta,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    const now = Math.floor(Date.now() / 1000);
+    const plan_tx = await program.methods.createPaymentPlan(
+      [new BN(now + 3600), new BN(now + 7200)],
+    ).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      marketplace: marketplacePda,
+      paymentPlan: paymentPlanPda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    console.log("Transaction Signature: ",plan_tx);
+
+    const installment_tx = await program.methods.payInstallment().accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      paymentPlan: paymentPlanPda,
+      userAta: userAta,
+      escrowAta: planEscrowAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    console.log("Transaction Signature: ",installment_tx);
+
+    const plan = await program.account.paymentPlan.fetch(paymentPlanPda);
+    console.log("Payment Plan: ",plan);
+
+    expect(plan.installments[0].paid).to.be.true;
+    expect(plan.installments[1].paid).to.be.false;
+    expect(Number(plan.paidAmount)).to.equal(Number(plan.installments[0].amount));
+    expect(plan.planStatus).to.have.property("active");
+    const planEscrow = await program.account.escrow.fetch(escrowPda);
+    expect(planEscrow.escrowStatus).to.have.property("partiallyFunded");
+
+    // A partial refund would leave the remaining instalments unpayable.
+    try {
+      await program.methods.refundEscrow(new BN(1)).accounts({
+        authority: seller.publicKey,
+        payment: paymentPda,
+        escrow: escrowPda,
+        purchaseList: purchaseListPdaFor(owner.publicKey),
+        order: null,
+        buyer: owner.publicKey,
+        escrowAta: planEscrowAta,
+        buyerAta: userAta,
+        mint: mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any).signers([seller]).rpc();
+      expect.fail("an active plan should not be partially refunded");
+    } catch (err) {
+      expect(String(err)).to.include("PaymentPlanActive");
+    }
+  });
+
+  it("should cancel a defaulted plan with no instalments paid", async () => {
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const [paymentPlanPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_plan"), paymentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.setInstallmentRules(10_000, new BN(0), 0).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    const clusterNow = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    await program.methods.createPaymentPlan(
+      [new BN(clusterNow + 2), new BN(clusterNow + 3600)],
+    ).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      marketplace: marketplacePda(),
+      paymentPlan: paymentPlanPda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    // The first instalment is missed with no grace period.
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+    await program.methods.cancelPaymentPlan().accounts({
+      seller: seller.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      paymentPlan: paymentPlanPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      order: null,
+      buyer: owner.publicKey,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).signers([seller]).rpc();
+    await program.methods.setInstallmentRules(10_000, new BN(3 * 24 * 60 * 60), 0).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    const payment = await program.account.payment.fetch(paymentPda);
+    const plan = await program.account.paymentPlan.fetch(paymentPlanPda);
+    expect(escrow.escrowStatus).to.have.property("refunded");
+    expect(payment.paymentStatus).to.have.property("refunded");
+    expect(plan.planStatus).to.have.property("defaulted");
+    expect(Number(plan.paidAmount)).to.equal(0);
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+  });
+
+  it("should bill a subscription through the delegate crank", async () => {
+    const productPda = productPdaFor(product_name[2]);
+    const [subscriptionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), owner.publicKey.toBuffer(), productPda.toBuffer()],
+      program.programId
+    );
+    const [delegatePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_delegate"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const sub_tx = await program.methods.createSubscription(
+      1,
+      new BN(24 * 60 * 60),
+      new BN(10_000_000),
+    ).accounts({
+      buyer: owner.publicKey,
+      product: productPda,
+      subscription: subscriptionPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      delegate: delegatePda,
+      buyerAta: userAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    console.log("Transaction Signature: ",sub_tx);
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const subscriptionEscrowAta = getAssociatedTokenAddressSync(mint, escrowPda, true);
+
+    const cranker = Keypair.generate();
+    await provider.connection.requestAirdrop(cranker.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    const bill_tx = await program.methods.billSubscription().accounts({
+      cranker: cranker.publicKey,
+      subscription: subscriptionPda,
+      product: productPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payment: paymentPda,
+      escrow: escrowPda,
+      order: orderPda,
+      delegate: delegatePda,
+      buyerAta: userAta,
+      escrowAta: subscriptionEscrowAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([cranker]).rpc();
+    console.log("Transaction Signature: ",bill_tx);
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    const escrowDetails = await program.account.escrow.fetch(escrowPda);
+    console.log("Subscription Details: ",subscription);
+
+    expect(Number(subscription.billedCount)).to.equal(1);
+    expect(escrowDetails.escrowStatus).to.have.property("fundsReceived");
+  });
+
+  it("should refund the subscription order back to the buyer", async () => {
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount) - 1);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const before = await provider.connection.getTokenAccountBalance(userAta);
+
+    const refund_tx = await program.methods.refundEscrow(
+      new BN(0),
+    ).accounts({
+      authority: seller.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      order: orderPda,
+      buyer: owner.publicKey,
+      escrowAta: vault,
+      buyerAta: userAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).signers([seller]).rpc();
+    console.log("Transaction Signature: ",refund_tx);
+
+    const after = await provider.connection.getTokenAccountBalance(userAta);
+    const escrowDetails = await program.account.escrow.fetch(escrowPda);
+    const payment = await program.account.payment.fetch(paymentPda);
+
+    expect(Number(after.value.amount)).to.be.greaterThan(Number(before.value.amount));
+    expect(escrowDetails.escrowStatus).to.have.property("refunded");
+    expect(payment.paymentStatus).to.have.property("refunded");
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+  });
+
+  it("should close the refunded subscription purchase and archive it", async () => {
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount) - 1);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [archivePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("archive"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const payment = await program.account.payment.fetch(paymentPda);
+    const before = await provider.connection.getBalance(payment.rentPayer);
+
+    await program.methods.closePurchase().accounts({
+      authority: owner.publicKey,
+      rentPayer: payment.rentPayer,
+      payment: paymentPda,
+      escrow: escrowPda,
+      order: orderPda,
+      archive: archivePda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    const archive = await program.account.purchaseArchive.fetch(archivePda);
+    expect(archive.outcome).to.have.property("refunded");
+    expect(Number(archive.refundedAmount)).to.equal(Number(payment.paymentAmount));
+    expect(await provider.connection.getAccountInfo(paymentPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(escrowPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(orderPda)).to.be.null;
+    expect(await provider.connection.getBalance(payment.rentPayer)).to.be.greaterThan(before);
+  });
+
+  it("should record a failed bill without opening a vault and close it", async () => {
+    const subscriber = Keypair.generate();
+    await provider.connection.requestAirdrop(subscriber.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    // An empty token account, so the bill can't be covered.
+    const subscriberAta = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, owner.payer, mint, subscriber.publicKey)
+    ).address;
+    const productPda = productPdaFor(product_name[2]);
+    const [subscriptionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), subscriber.publicKey.toBuffer(), productPda.toBuffer()],
+      program.programId
+    );
+    const [delegatePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_delegate"), subscriber.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods.createSubscription(1, new BN(24 * 60 * 60), new BN(10_000)).accounts({
+      buyer: subscriber.publicKey,
+      product: productPda,
+      subscription: subscriptionPda,
+      purchaseList: purchaseListPdaFor(subscriber.publicKey),
+      delegate: delegatePda,
+      buyerAta: subscriberAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([subscriber]).rpc();
+
+    const paymentPda = paymentPdaFor(subscriber.publicKey, 0);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    await program.methods.billSubscription().accounts({
+      cranker: owner.publicKey,
+      subscription: subscriptionPda,
+      product: productPda,
+      purchaseList: purchaseListPdaFor(subscriber.publicKey),
+      payment: paymentPda,
+      escrow: escrowPda,
+      order: orderPda,
+      delegate: delegatePda,
+      buyerAta: subscriberAta,
+      escrowAta: vault,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    expect(subscription.lastFailure).to.have.property("insufficientBalance");
+    expect(escrow.escrowStatus).to.have.property("transferFailed");
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+
+    await program.methods.closePurchase().accounts({
+      authority: owner.publicKey,
+      rentPayer: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      order: orderPda,
+      archive: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    expect(await provider.connection.getAccountInfo(paymentPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(escrowPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(orderPda)).to.be.null;
+  });
+
+  it("should let a buyer cancel an unfunded checkout", async () => {
+    const planPaymentPda = paymentPdaFor(owner.publicKey, 2);
+    try {
+      await program.methods.cancelPayment().accounts({
+        owner: owner.publicKey,
+        rentPayer: owner.publicKey,
+        payment: planPaymentPda,
+        purchaseList: purchaseListPdaFor(owner.publicKey),
+        escrow: escrowPdaFor(planPaymentPda),
+        order: PublicKey.findProgramAddressSync(
+          [Buffer.from("order"), planPaymentPda.toBuffer()],
+          program.programId
+        )[0],
+        escrowAta: null,
+        mint: null,
+        tokenProgram: null,
+      } as any).rpc();
+      expect.fail("a payment on an instalment plan should not be cancellable");
+    } catch (err) {
+      expect(String(err)).to.include("PaymentNotCancellable");
+    }
+
+    // The consumer's USD checkout was never funded.
+    const paymentPda = paymentPdaFor(consumer.publicKey, 0);
+    const before = await program.account.purchaseList.fetch(purchaseListPdaFor(consumer.publicKey));
+    expect(before.openPurchases.map((key) => key.toBase58())).to.include(paymentPda.toBase58());
+
+    await program.methods.cancelPayment().accounts({
+      owner: consumer.publicKey,
+      rentPayer: consumer.publicKey,
+      payment: paymentPda,
+      purchaseList: purchaseListPdaFor(consumer.publicKey),
+      escrow: escrowPdaFor(paymentPda),
+      order: PublicKey.findProgramAddressSync(
+        [Buffer.from("order"), paymentPda.toBuffer()],
+        program.programId
+      )[0],
+      escrowAta: null,
+      mint: null,
+      tokenProgram: null,
+    } as any).signers([consumer]).rpc();
+
+    const after = await program.account.purchaseList.fetch(purchaseListPdaFor(consumer.publicKey));
+    expect(after.openPurchases.map((key) => key.toBase58())).to.not.include(paymentPda.toBase58());
+    expect(await provider.connection.getAccountInfo(paymentPda)).to.be.null;
+
+    // An escrow opened but never funded goes too, with its vault.
+    const purchaseList = await program.account.purchaseList.fetch(purchaseListPdaFor(owner.publicKey));
+    const openPaymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const openEscrowPda = escrowPdaFor(openPaymentPda);
+    const openVault = getAssociatedTokenAddressSync(mint, openEscrowPda, true);
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: openPaymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: openPaymentPda,
+      escrow: openEscrowPda,
+      userAta: userAta,
+      escrowAta: openVault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    const cancelOpen = {
+      owner: owner.publicKey,
+      rentPayer: owner.publicKey,
+      payment: openPaymentPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      escrow: openEscrowPda,
+      order: PublicKey.findProgramAddressSync(
+        [Buffer.from("order"), openPaymentPda.toBuffer()],
+        program.programId
+      )[0],
+      escrowAta: null,
+      mint: null,
+      tokenProgram: null,
+    };
+    try {
+      await program.methods.cancelPayment().accounts(cancelOpen as any).rpc();
+      expect.fail("cancelling with an escrow needs its vault");
+    } catch (err) {
+      expect(String(err)).to.include("EscrowAccountsRequired");
+    }
+    await program.methods.cancelPayment().accounts({
+      ...cancelOpen,
+      escrowAta: openVault,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    expect(await provider.connection.getAccountInfo(openPaymentPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(openEscrowPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(openVault)).to.be.null;
+  });
+
+  it("should validate and store a payout split table", async () => {
+    const productPda = productPdaFor(product_name[1]);
+    const consignor = Keypair.generate().publicKey;
+
+    try {
+      await program.methods.setPayoutSplits([
+        { recipient: seller.publicKey, shareBps: 6000 },
+        { recipient: consignor, shareBps: 3000 },
+      ]).accounts({
+        seller: seller.publicKey,
+        product: productPda,
+      } as any).signers([seller]).rpc();
+      expect.fail("splits that do not add up to 10,000 bps should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("InvalidPayoutSplits");
+    }
+
+    await program.methods.setPayoutSplits([
+      { recipient: seller.publicKey, shareBps: 7000 },
+      { recipient: consignor, shareBps: 3000 },
+    ]).accounts({
+      seller: seller.publicKey,
+      product: productPda,
+    } as any).signers([seller]).rpc();
+
+    const product = await program.account.product.fetch(productPda);
+    expect(product.payoutSplits.length).to.equal(2);
+    expect(product.payoutSplits[1].recipient.toBase58()).to.equal(consignor.toBase58());
+  });
+
+  it("should release a split product to every recipient in the table", async () => {
+    const productPda = productPdaFor(product_name[1]);
+    const product = await program.account.product.fetch(productPda);
+    const consignor = product.payoutSplits[1].recipient;
+    const consignorAta = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, owner.payer, mint, consignor)
+    ).address;
+    const treasuryAta = getAssociatedTokenAddressSync(mint, treasury.publicKey);
+    const buyerPoints = getAssociatedTokenAddressSync(pointsMintPda(), owner.publicKey);
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPda,
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.depositEscrow(new BN(price[1]), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const withdraw = (points = true) => program.methods.withdrawEscrow(1).accounts({
+      escrow: escrowPda,
+      payment: paymentPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      authority: owner.publicKey,
+      owner: owner.publicKey,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      marketplace: marketplacePda(),
+      product: productPda,
+      sellerProfile: sellerProfilePdaFor(seller.publicKey),
+      treasuryAta: treasuryAta,
+      keeperVault: null,
+      mint: mint,
+      acceptedMint: acceptedMintPda(mint),
+      loyalty: PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0],
+      pointsMint: points ? pointsMintPda() : null,
+      buyerPoints: points ? buyerPoints : null,
+      pointsTokenProgram: TOKEN_PROGRAM_ID,
+      order: null,
+      receipt: receiptPda,
+      receiptMint: receiptMint,
+      buyerReceiptAta: getAssociatedTokenAddressSync(receiptMint, owner.publicKey),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any);
+
+    try {
+      await withdraw().remainingAccounts([
+        { pubkey: sellerAta, isWritable: true, isSigner: false },
+      ]).rpc();
+      expect.fail("every split recipient needs a token account");
+    } catch (err) {
+      expect(String(err)).to.include("MissingPayoutAccounts");
+    }
+    try {
+      await withdraw(false).remainingAccounts([
+        { pubkey: sellerAta, isWritable: true, isSigner: false },
+        { pubkey: consignorAta, isWritable: true, isSigner: false },
+      ]).rpc();
+      expect.fail("a release that earns points can't leave out the buyer's points account");
+    } catch (err) {
+      expect(String(err)).to.include("LoyaltyAccountsRequired");
+    }
+
+    const pointsBefore = await provider.connection.getTokenAccountBalance(buyerPoints);
+    const sellerBefore = await provider.connection.getTokenAccountBalance(sellerAta);
+    const consignorBefore = await provider.connection.getTokenAccountBalance(consignorAta);
+    const treasuryBefore = await provider.connection.getTokenAccountBalance(treasuryAta);
+    await withdraw().remainingAccounts([
+      { pubkey: sellerAta, isWritable: true, isSigner: false },
+      { pubkey: consignorAta, isWritable: true, isSigner: false },
+    ]).rpc();
+
+    const payment = await program.account.payment.fetch(paymentPda);
+    const fee = Number(payment.marketplaceFee);
+    const consignorShare = Math.floor(((price[1] - fee) * 3000) / 10_000);
+    const sellerAfter = await provider.connection.getTokenAccountBalance(sellerAta);
+    const consignorAfter = await provider.connection.getTokenAccountBalance(consignorAta);
+    const treasuryAfter = await provider.connection.getTokenAccountBalance(treasuryAta);
+    expect(fee).to.be.greaterThan(0);
+    expect(payment.paymentStatus).to.have.property("success");
+    expect(Number(consignorAfter.value.amount) - Number(consignorBefore.value.amount))
+      .to.equal(consignorShare);
+    expect(Number(sellerAfter.value.amount) - Number(sellerBefore.value.amount))
+      .to.equal(price[1] - fee - consignorShare);
+    expect(Number(treasuryAfter.value.amount) - Number(treasuryBefore.value.amount)).to.equal(fee);
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    const pointsAfter = await provider.connection.getTokenAccountBalance(buyerPoints);
+    expect(Number(pointsAfter.value.amount) - Number(pointsBefore.value.amount))
+      .to.equal(Math.floor((price[1] * 100) / 10_000));
+  });
+
+  it("should burn loyalty points for a discount at checkout", async () => {
+    const loyaltyPda = PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0];
+    const buyerPoints = getAssociatedTokenAddressSync(pointsMintPda(), owner.publicKey);
+    await program.methods.setPointsValue(new BN(10)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+      loyalty: loyaltyPda,
+    } as any).rpc();
+
+    const balance = Number((await provider.connection.getTokenAccountBalance(buyerPoints)).value.amount);
+    expect(balance).to.be.at.least(10);
+    const checkout = async (redeem: number) => {
+      const purchaseList = await program.account.purchaseList.fetch(
+        purchaseListPdaFor(owner.publicKey)
+      );
+      const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+      await program.methods.createPayment(1, null, new BN(redeem)).accounts({
+        signer: owner.publicKey,
+        purchaseList: purchaseListPdaFor(owner.publicKey),
+        payments: paymentPda,
+        product: productPdaFor(product_name[0]),
+        cart: null,
+        mint: mint,
+        marketplace: null,
+        acceptedMint: null,
+        priceFeed: null,
+        coupon: null,
+        couponRedemption: null,
+        loyalty: loyaltyPda,
+        pointsMint: pointsMintPda(),
+        buyerPoints: buyerPoints,
+        pointsTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any).rpc();
+      return paymentPda;
+    };
+
+    try {
+      await checkout(balance + 10);
+      expect.fail("the buyer can't burn more points than they hold");
+    } catch (err) {
+      expect(String(err)).to.include("InsufficientPoints");
+    }
+
+    const paymentPda = await checkout(balance);
+    const discount = Math.floor(balance / 10);
+    const payment = await program.account.payment.fetch(paymentPda);
+    const remaining = Number((await provider.connection.getTokenAccountBalance(buyerPoints)).value.amount);
+    expect(Number(payment.pointsRedeemed)).to.equal(discount * 10);
+    expect(Number(payment.paymentAmount)).to.equal(price[0] - discount);
+    expect(remaining).to.equal(balance - discount * 10);
+
+    await program.methods.cancelPayment().accounts({
+      owner: owner.publicKey,
+      rentPayer: owner.publicKey,
+      payment: paymentPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      escrow: escrowPdaFor(paymentPda),
+      order: PublicKey.findProgramAddressSync(
+        [Buffer.from("order"), paymentPda.toBuffer()],
+        program.programId
+      )[0],
+      escrowAta: null,
+      mint: null,
+      tokenProgram: null,
+    } as any).rpc();
+    await program.methods.setPointsValue(new BN(100)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+      loyalty: loyaltyPda,
+    } as any).rpc();
+  });
+
+  it("should let a seller create a scoped coupon", async () => {
+    const codeHash = createHash("sha256").update("SPRING10").digest();
+    const [couponPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("coupon"), seller.publicKey.toBuffer(), codeHash],
+      program.programId
+    );
+    const expiresAt = new BN(Math.floor(Date.now() / 1000) + 86_400);
+
+    await program.methods.createCoupon(
+      [...codeHash],
+      { percentage: { bps: 1000 } },
+      { product: { product: productPdaFor(product_name[0]) } },
+      expiresAt,
+      100,
+      1,
+    ).accounts({
+      creator: seller.publicKey,
+      marketplace: marketplacePda(),
+      coupon: couponPda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([seller]).rpc();
+
+    const coupon = await program.account.coupon.fetch(couponPda);
+    expect(coupon.seller.toBase58()).to.equal(seller.publicKey.toBase58());
+    expect(coupon.redemptions).to.equal(0);
+    expect(coupon.perWalletCap).to.equal(1);
+  });
+
+  it("should redeem a coupon within its per-wallet and total caps", async () => {
+    const couponPdaFor = (creator: PublicKey, code: string) => PublicKey.findProgramAddressSync(
+      [Buffer.from("coupon"), creator.toBuffer(), createHash("sha256").update(code).digest()],
+      program.programId
+    )[0];
+    const redemptionPdaFor = (coupon: PublicKey, buyer: PublicKey) => PublicKey.findProgramAddressSync(
+      [Buffer.from("coupon_redemption"), coupon.toBuffer(), buyer.toBuffer()],
+      program.programId
+    )[0];
+    const checkout = async (buyer: Keypair, code: string, coupon: PublicKey) => {
+      const purchaseList = await program.account.purchaseList.fetchNullable(
+        purchaseListPdaFor(buyer.publicKey)
+      );
+      const paymentPda = paymentPdaFor(buyer.publicKey, Number(purchaseList?.paymentCount ?? 0));
+      await program.methods.createPayment(1, code, new BN(0)).accounts({
+        signer: buyer.publicKey,
+        purchaseList: purchaseListPdaFor(buyer.publicKey),
+        payments: paymentPda,
+        product: productPdaFor(product_name[0]),
+        cart: null,
+        mint: mint,
+        marketplace: null,
+        acceptedMint: null,
+        priceFeed: null,
+        coupon: coupon,
+        couponRedemption: redemptionPdaFor(coupon, buyer.publicKey),
+        loyalty: null,
+        pointsMint: null,
+        buyerPoints: null,
+        pointsTokenProgram: null,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any).signers([buyer]).rpc();
+      return paymentPda;
+    };
+    const cancel = async (buyer: Keypair, paymentPda: PublicKey) => {
+      await program.methods.cancelPayment().accounts({
+        owner: buyer.publicKey,
+        rentPayer: buyer.publicKey,
+        payment: paymentPda,
+        purchaseList: purchaseListPdaFor(buyer.publicKey),
+        escrow: escrowPdaFor(paymentPda),
+        order: PublicKey.findProgramAddressSync(
+          [Buffer.from("order"), paymentPda.toBuffer()],
+          program.programId
+        )[0],
+        escrowAta: null,
+        mint: null,
+        tokenProgram: null,
+      } as any).signers([buyer]).rpc();
+    };
+
+    // Anyone can issue the same code under their own key without taking
+    // the seller's coupon.
+    const squatted = couponPdaFor(consumer.publicKey, "SPRING10");
+    await program.methods.createCoupon(
+      [...createHash("sha256").update("SPRING10").digest()],
+      { fixed: { amount: new BN(1) } },
+      { all: {} },
+      new BN(Math.floor(Date.now() / 1000) + 86_400),
+      1,
+      1,
+    ).accounts({
+      creator: consumer.publicKey,
+      marketplace: marketplacePda(),
+      coupon: squatted,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([consumer]).rpc();
+
+    const spring = couponPdaFor(seller.publicKey, "SPRING10");
+    const first = await checkout(owner.payer, "SPRING10", spring);
+    const payment = await program.account.payment.fetch(first);
+    const discount = Math.floor((price[0] * 1000) / 10_000);
+    expect(Number(payment.couponDiscount)).to.equal(discount);
+    expect(Number(payment.paymentAmount)).to.equal(price[0] - discount);
+    expect(payment.coupon?.toBase58()).to.equal(spring.toBase58());
+    try {
+      await checkout(owner.payer, "SPRING10", spring);
+      expect.fail("the coupon is limited to one use per wallet");
+    } catch (err) {
+      expect(String(err)).to.include("CouponWalletLimit");
+    }
+    expect((await program.account.coupon.fetch(spring)).redemptions).to.equal(1);
+    const redemption = await program.account.couponRedemption.fetch(
+      redemptionPdaFor(spring, owner.publicKey)
+    );
+    expect(redemption.count).to.equal(1);
+
+    // A single-use coupon stops at its total cap whoever redeems it.
+    const oneOff = couponPdaFor(seller.publicKey, "ONEOFF");
+    await program.methods.createCoupon(
+      [...createHash("sha256").update("ONEOFF").digest()],
+      { fixed: { amount: new BN(50) } },
+      { all: {} },
+      new BN(Math.floor(Date.now() / 1000) + 86_400),
+      1,
+      5,
+    ).accounts({
+      creator: seller.publicKey,
+      marketplace: marketplacePda(),
+      coupon: oneOff,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([seller]).rpc();
+    const second = await checkout(owner.payer, "ONEOFF", oneOff);
+    expect(Number((await program.account.payment.fetch(second)).couponDiscount)).to.equal(50);
+    try {
+      await checkout(consumer, "ONEOFF", oneOff);
+      expect.fail("the coupon has no redemptions left");
+    } catch (err) {
+      expect(String(err)).to.include("CouponRedemptionLimit");
+    }
+    expect((await program.account.coupon.fetch(oneOff)).redemptions).to.equal(1);
+
+    await cancel(owner.payer, first);
+    await cancel(owner.payer, second);
+  });
+
+  it("should issue a bearer gift card, claim it and pass it on", async () => {
+    const secret = "gift-card-secret";
+    const codeHash = createHash("sha256").update(secret).digest();
+    // Derived from the secret but not from `codeHash`, which is public.
+    const claimKeyFor = (code: string) =>
+      Keypair.fromSeed(createHash("sha256").update(`gift_card_claim:${code}`).digest());
+    const claimKey = claimKeyFor(secret);
+    const [giftCardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("gift_card"), codeHash],
+      program.programId
+    );
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("gift_card_vault"), giftCardPda.toBuffer()],
+      program.programId
+    );
+    const issuerAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner.payer,
+      mint,
+      owner.publicKey,
+    );
+    await mintTo(provider.connection, owner.payer, mint, issuerAta.address, owner.publicKey, 5_000);
+
+    await program.methods.createGiftCard([...codeHash], new BN(5_000), null, claimKey.publicKey).accounts({
+      issuer: owner.publicKey,
+      giftCard: giftCardPda,
+      vault: vaultPda,
+      issuerAta: issuerAta.address,
+      mint: mint,
+      systemProgram: SYSTEM_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    try {
+      const wrongKey = claimKeyFor("wrong-secret");
+      await program.methods.redeemGiftCard().accounts({
+        signer: owner.publicKey,
+        claimAuthority: wrongKey.publicKey,
+        giftCard: giftCardPda,
+      } as any).signers([wrongKey]).rpc();
+      expect.fail("a wrong secret should not claim the card");
+    } catch (err) {
+      expect(String(err)).to.include("InvalidGiftCardSecret");
+    }
+
+    await program.methods.redeemGiftCard().accounts({
+      signer: owner.publicKey,
+      claimAuthority: claimKey.publicKey,
+      giftCard: giftCardPda,
+    } as any).signers([claimKey]).rpc();
+    await program.methods.transferGiftCard(consumer.publicKey).accounts({
+      signer: owner.publicKey,
+      giftCard: giftCardPda,
+    } as any).rpc();
+
+    const giftCard = await program.account.giftCard.fetch(giftCardPda);
+    const vault = await provider.connection.getTokenAccountBalance(vaultPda);
+    expect(giftCard.holder.toBase58()).to.equal(consumer.publicKey.toBase58());
+    expect(giftCard.claimKey).to.be.null;
+    expect(Number(giftCard.balance)).to.equal(5_000);
+    expect(Number(vault.value.amount)).to.equal(5_000);
+  });
+
+  it("should draw part of a deposit from the holder's gift card", async () => {
+    const [giftCardPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("gift_card"), createHash("sha256").update("gift-card-secret").digest()],
+      program.programId
+    );
+    const [giftCardVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("gift_card_vault"), giftCardPda.toBuffer()],
+      program.programId
+    );
+    const consumerAta = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, owner.payer, mint, consumer.publicKey)
+    ).address;
+    await mintTo(provider.connection, owner.payer, mint, consumerAta, owner.publicKey, 1_000);
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(consumer.publicKey)
+    );
+    const paymentPda = paymentPdaFor(consumer.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: consumer.publicKey,
+      purchaseList: purchaseListPdaFor(consumer.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([consumer]).rpc();
+    await program.methods.createEscrow(consumer.publicKey, seller.publicKey, []).accounts({
+      owner: consumer.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: consumerAta,
+      escrowAta: vault,
+      buyerAta: consumerAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).signers([consumer]).rpc();
+
+    const deposit = (giftCardAmount: number) => program.methods.depositEscrow(
+      new BN(price[0]),
+      new BN(giftCardAmount),
+    ).accounts({
+      owner: consumer.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: consumerAta,
+      escrowAta: vault,
+      buyerAta: consumerAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: giftCardPda,
+      giftCardVault: giftCardVault,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).signers([consumer]).rpc();
+    try {
+      await deposit(price[0] + 1);
+      expect.fail("a gift card can't cover more than the payment");
+    } catch (err) {
+      expect(String(err)).to.include("DepositAmountMismatch");
+    }
+
+    const cardBefore = await program.account.giftCard.fetch(giftCardPda);
+    const buyerBefore = await provider.connection.getTokenAccountBalance(consumerAta);
+    await deposit(500);
+
+    const cardAfter = await program.account.giftCard.fetch(giftCardPda);
+    const cardVault = await provider.connection.getTokenAccountBalance(giftCardVault);
+    const buyerAfter = await provider.connection.getTokenAccountBalance(consumerAta);
+    const escrowVault = await provider.connection.getTokenAccountBalance(vault);
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    expect(Number(cardBefore.balance) - Number(cardAfter.balance)).to.equal(500);
+    expect(Number(cardVault.value.amount)).to.equal(Number(cardAfter.balance));
+    expect(Number(buyerBefore.value.amount) - Number(buyerAfter.value.amount)).to.equal(price[0] - 500);
+    expect(Number(escrowVault.value.amount)).to.equal(price[0]);
+    expect(escrow.escrowStatus).to.have.property("fundsReceived");
+  });
+
+  it("should relay a buyer-signed payment intent with the relayer paying fees", async () => {
+    const relayBuyer = Keypair.generate();
+    const buyerAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner.payer,
+      mint,
+      relayBuyer.publicKey,
+    );
+    await mintTo(provider.connection, owner.payer, mint, buyerAta.address, owner.publicKey, 10_000);
+
+    const [delegatePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_delegate"), relayBuyer.publicKey.toBuffer()],
+      program.programId
+    );
+    // The buyer signs the approval, the relayer pays for it.
+    await approve(
+      provider.connection,
+      owner.payer,
+      buyerAta.address,
+      delegatePda,
+      relayBuyer,
+      10_000,
+    );
+
+    const paymentPda = paymentPdaFor(relayBuyer.publicKey, 0);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const escrowAta = getAssociatedTokenAddressSync(mint, escrowPda, true);
+
+    const intent = {
+      buyer: relayBuyer.publicKey,
+      product: productPdaFor(product_name[0]),
+      mint: mint,
+      quantity: new BN(1),
+      maxAmount: new BN(price[0]),
+      nonce: new BN(0),
+      expiresAt: new BN(Math.floor(Date.now() / 1000) + 600),
+    };
+    const relayAccounts = {
+      relayer: owner.publicKey,
+      product: productPdaFor(product_name[0]),
+      purchaseList: purchaseListPdaFor(relayBuyer.publicKey),
+      payment: paymentPda,
+      escrow: escrowPda,
+      delegate: delegatePda,
+      buyerAta: buyerAta.address,
+      escrowAta: escrowAta,
+      mint: mint,
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    };
+
+    // A signature over the bare intent isn't bound to this program.
+    try {
+      const untaggedIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: relayBuyer.secretKey,
+        message: program.coder.types.encode("paymentIntent", intent),
+      });
+      await program.methods.relayPayment(intent).accounts(relayAccounts as any)
+        .preInstructions([untaggedIx]).rpc();
+      expect.fail("an intent signed without the domain tag should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("InvalidIntentSignature");
+    }
+    // The cap covers everything pulled from the buyer.
+    try {
+      const lowIntent = { ...intent, maxAmount: new BN(price[0] - 1) };
+      const lowIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: relayBuyer.secretKey,
+        message: intentMessage(lowIntent),
+      });
+      await program.methods.relayPayment(lowIntent).accounts(relayAccounts as any)
+        .preInstructions([lowIx]).rpc();
+      expect.fail("an intent capped below the deposit should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("IntentAmountExceeded");
+    }
+
+    const verifyIx = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: relayBuyer.secretKey,
+      message: intentMessage(intent),
+    });
+    await program.methods.relayPayment(intent).accounts(relayAccounts as any)
+      .preInstructions([verifyIx]).rpc();
+
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    const vault = await provider.connection.getTokenAccountBalance(escrowAta);
+    expect(escrow.escrowStatus).to.have.property("fundsReceived");
+    expect(Number(vault.value.amount)).to.equal(price[0]);
+
+    const replayEscrowPda = escrowPdaFor(paymentPdaFor(relayBuyer.publicKey, 1));
+    const replayEscrowAta = getAssociatedTokenAddressSync(mint, replayEscrowPda, true);
+    try {
+      await program.methods.relayPayment(intent).accounts({
+        relayer: owner.publicKey,
+        product: productPdaFor(product_name[0]),
+        purchaseList: purchaseListPdaFor(relayBuyer.publicKey),
+        payment: paymentPdaFor(relayBuyer.publicKey, 1),
+        escrow: replayEscrowPda,
+        delegate: delegatePda,
+        buyerAta: buyerAta.address,
+        escrowAta: replayEscrowAta,
+        mint: mint,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        marketplace: marketplacePda(),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any).preInstructions([verifyIx]).rpc();
+      expect.fail("a replayed intent should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("IntentNonceMismatch");
+    }
+  });
+
+  it("should count the shipping window from funding and expire the escrow once it lapses", async () => {
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const expireAccounts = {
+      caller: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      order: orderPda,
+      escrowAta: vault,
+      buyerAta: userAta,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    await program.methods.setShippingWindow(new BN(3)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    // Funded after the window would have run out had it counted from creation.
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+    await program.methods.depositEscrow(new BN(price[0]), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    const funded = await program.account.escrow.fetch(escrowPda);
+    expect(Number(funded.shipBy)).to.equal(Number(funded.updateTimestamp) + 3);
+    try {
+      await program.methods.expireEscrow().accounts(expireAccounts as any).rpc();
+      expect.fail("an escrow inside its shipping window should not expire");
+    } catch (err) {
+      expect(String(err)).to.include("ShippingDeadlineNotReached");
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+    const buyerBefore = await provider.connection.getTokenAccountBalance(userAta);
+    await program.methods.expireEscrow().accounts(expireAccounts as any).rpc();
+    await program.methods.setShippingWindow(new BN(14 * 24 * 60 * 60)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const expired = await program.account.escrow.fetch(escrowPda);
+    const payment = await program.account.payment.fetch(paymentPda);
+    const buyerAfter = await provider.connection.getTokenAccountBalance(userAta);
+    expect(expired.escrowStatus).to.have.property("refunded");
+    expect(payment.paymentStatus).to.have.property("refunded");
+    expect(Number(buyerAfter.value.amount) - Number(buyerBefore.value.amount)).to.equal(price[0]);
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+  });
+
+  it("should release milestone tranches from the escrowed amount", async () => {
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const treasuryAta = getAssociatedTokenAddressSync(mint, treasury.publicKey);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, [
+      { stage: { inTransit: {} }, releaseBps: 4000 },
+      { stage: { delivered: {} }, releaseBps: 6000 },
+    ]).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.depositEscrow(new BN(price[0]), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    const payment = await program.account.payment.fetch(paymentPda);
+    await program.methods.createOrder(String(bytesToUuid(payment.paymentId))).accounts({
+      signer: owner.publicKey,
+      order: orderPda,
+      payment: paymentPda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.updateOrderTracking({ inTransit: {} }).accounts({
+      seller: seller.publicKey,
+      payment: paymentPda,
+      order: orderPda,
+      paymentPlan: null,
+    } as any).signers([seller]).rpc();
+
+    const releaseAccounts = {
+      caller: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      order: orderPda,
+      marketplace: marketplacePda(),
+      product: productPdaFor(product_name[0]),
+      sellerProfile: sellerProfilePdaFor(seller.publicKey),
+      escrowAta: vault,
+      sellerAta: sellerAta,
+      treasuryAta: treasuryAta,
+      keeperVault: null,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const sellerBefore = await provider.connection.getTokenAccountBalance(sellerAta);
+    await program.methods.releaseMilestones().accounts(releaseAccounts as any).rpc();
+
+    const tranche = Math.floor((price[0] * 4000) / 10_000);
+    const fee = Math.floor((tranche * 250) / 10_000);
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    const sellerAfter = await provider.connection.getTokenAccountBalance(sellerAta);
+    expect(Number(sellerAfter.value.amount) - Number(sellerBefore.value.amount)).to.equal(tranche - fee);
+    expect(Number(escrow.milestones[0].releasedAmount)).to.equal(tranche);
+    expect(escrow.milestones[0].releasedAt).to.not.be.null;
+    expect(Number(escrow.amount)).to.equal(price[0] - tranche);
+    expect(Number(escrow.releasedAmount)).to.equal(tranche);
+
+    try {
+      await program.methods.releaseMilestones().accounts(releaseAccounts as any).rpc();
+      expect.fail("a released milestone should not pay out twice");
+    } catch (err) {
+      expect(String(err)).to.include("NoMilestoneDue");
+    }
+
+    // The final tranche settles through a normal release.
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), paymentPda.toBuffer()],
+      program.programId
+    );
+    await program.methods.withdrawEscrow(1).accounts({
+      escrow: escrowPda,
+      payment: paymentPda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      authority: owner.publicKey,
+      owner: owner.publicKey,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      marketplace: marketplacePda(),
+      product: productPdaFor(product_name[0]),
+      sellerProfile: sellerProfilePdaFor(seller.publicKey),
+      treasuryAta: treasuryAta,
+      keeperVault: null,
+      mint: mint,
+      acceptedMint: acceptedMintPda(mint),
+      loyalty: PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0],
+      pointsMint: pointsMintPda(),
+      buyerPoints: getAssociatedTokenAddressSync(pointsMintPda(), owner.publicKey),
+      pointsTokenProgram: TOKEN_PROGRAM_ID,
+      order: orderPda,
+      receipt: receiptPda,
+      receiptMint: receiptMint,
+      buyerReceiptAta: getAssociatedTokenAddressSync(receiptMint, owner.publicKey),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    const settled = await program.account.escrow.fetch(escrowPda);
+    expect(Number(settled.milestones[1].releasedAmount)).to.equal(price[0] - tranche);
+    expect(settled.milestones[1].releasedAt).to.not.be.null;
+    expect(Number(settled.releasedAmount)).to.equal(price[0]);
+
+    // Released by the buyer while still in transit, and closable all the same.
+    await program.methods.closePurchase().accounts({
+      authority: owner.publicKey,
+      rentPayer: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      order: orderPda,
+      archive: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    expect(await provider.connection.getAccountInfo(orderPda)).to.be.null;
+  });
+
+  it("should share one delegate between a subscription and a relayed payment", async () => {
+    const sharedBuyer = Keypair.generate();
+    await provider.connection.requestAirdrop(sharedBuyer.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    const buyerAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner.payer,
+      mint,
+      sharedBuyer.publicKey,
+    );
+    await mintTo(provider.connection, owner.payer, mint, buyerAta.address, owner.publicKey, 10_000);
+
+    const [delegatePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("payment_delegate"), sharedBuyer.publicKey.toBuffer()],
+      program.programId
+    );
+    // Budget for the relayed payment, approved first.
+    await approve(
+      provider.connection,
+      owner.payer,
+      buyerAta.address,
+      delegatePda,
+      sharedBuyer,
+      price[0],
+    );
+
+    // The subscription adds its allowance on top of the relay budget.
+    const subscriptionProduct = productPdaFor(product_name[2]);
+    const [subscriptionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("subscription"), sharedBuyer.publicKey.toBuffer(), subscriptionProduct.toBuffer()],
+      program.programId
+    );
+    await program.methods.createSubscription(
+      1,
+      new BN(24 * 60 * 60),
+      new BN(price[2]),
+    ).accounts({
+      buyer: sharedBuyer.publicKey,
+      product: subscriptionProduct,
+      subscription: subscriptionPda,
+      purchaseList: purchaseListPdaFor(sharedBuyer.publicKey),
+      delegate: delegatePda,
+      buyerAta: buyerAta.address,
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).signers([sharedBuyer]).rpc();
+    const approved = await getAccount(provider.connection, buyerAta.address);
+    expect(approved.delegate.toBase58()).to.equal(delegatePda.toBase58());
+    expect(Number(approved.delegatedAmount)).to.equal(price[0] + price[2]);
+
+    const relayPayment = paymentPdaFor(sharedBuyer.publicKey, 0);
+    const relayEscrow = escrowPdaFor(relayPayment);
+    const intent = {
+      buyer: sharedBuyer.publicKey,
+      product: productPdaFor(product_name[0]),
+      mint: mint,
+      quantity: new BN(1),
+      maxAmount: new BN(price[0]),
+      nonce: new BN(0),
+      expiresAt: new BN(Math.floor(Date.now() / 1000) + 600),
+    };
+    const verifyIx = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: sharedBuyer.secretKey,
+      message: intentMessage(intent),
+    });
+    await program.methods.relayPayment(intent).accounts({
+      relayer: owner.publicKey,
+      product: productPdaFor(product_name[0]),
+      purchaseList: purchaseListPdaFor(sharedBuyer.publicKey),
+      payment: relayPayment,
+      escrow: relayEscrow,
+      delegate: delegatePda,
+      buyerAta: buyerAta.address,
+      escrowAta: getAssociatedTokenAddressSync(mint, relayEscrow, true),
+      mint: mint,
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).preInstructions([verifyIx]).rpc();
+
+    const billPayment = paymentPdaFor(sharedBuyer.publicKey, 1);
+    const billEscrow = escrowPdaFor(billPayment);
+    await program.methods.billSubscription().accounts({
+      cranker: owner.publicKey,
+      subscription: subscriptionPda,
+      product: subscriptionProduct,
+      purchaseList: purchaseListPdaFor(sharedBuyer.publicKey),
+      payment: billPayment,
+      escrow: billEscrow,
+      order: PublicKey.findProgramAddressSync(
+        [Buffer.from("order"), billPayment.toBuffer()],
+        program.programId
+      )[0],
+      delegate: delegatePda,
+      buyerAta: buyerAta.address,
+      escrowAta: getAssociatedTokenAddressSync(mint, billEscrow, true),
+      mint: mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    const subscription = await program.account.subscription.fetch(subscriptionPda);
+    const relayed = await program.account.escrow.fetch(relayEscrow);
+    const billed = await program.account.escrow.fetch(billEscrow);
+    const spent = await getAccount(provider.connection, buyerAta.address);
+    expect(Number(subscription.billedCount)).to.equal(1);
+    expect(relayed.escrowStatus).to.have.property("fundsReceived");
+    expect(billed.escrowStatus).to.have.property("fundsReceived");
+    expect(Number(spent.delegatedAmount)).to.equal(0);
+    expect(Number(spent.amount)).to.equal(10_000 - price[0] - price[2]);
+  });
+
+  it("should freeze a disputed escrow and settle it on the arbiter's split ruling", async () => {
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const [disputePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dispute"), escrowPda.toBuffer()],
+      program.programId
+    );
+    const [sellerProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("seller_profile"), seller.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.depositEscrow(new BN(price[0]), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+
+    await program.methods.openDispute("ipfs://item-never-arrived").accounts({
+      buyer: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      marketplace: marketplacePda(),
+      dispute: disputePda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.addDisputeEvidence("ipfs://courier-receipt").accounts({
+      submitter: seller.publicKey,
+      dispute: disputePda,
+    } as any).signers([seller]).rpc();
+
+    const treasuryAta = getAssociatedTokenAddressSync(mint, treasury.publicKey);
+    const sellerBefore = await provider.connection.getTokenAccountBalance(sellerAta);
+    const treasuryBefore = await provider.connection.getTokenAccountBalance(treasuryAta);
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const buyerReceiptAta = getAssociatedTokenAddressSync(receiptMint, owner.publicKey);
+    const settleAccounts = {
+      arbiter: owner.publicKey,
+      marketplace: marketplacePda(),
+      payment: paymentPda,
+      escrow: escrowPda,
+      dispute: disputePda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      order: null,
+      product: productPdaFor(product_name[0]),
+      sellerProfile: sellerProfilePda,
+      buyer: owner.publicKey,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      treasuryAta: treasuryAta,
+      keeperVault: null,
+      mint: mint,
+      receipt: null,
+      receiptMint: null,
+      buyerReceiptAta: null,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    };
+    try {
+      await program.methods.resolveDispute({ split: { buyerBps: 5000 } })
+        .accounts(settleAccounts as any).rpc();
+      expect.fail("a ruling that pays the seller needs the buyer's receipt accounts");
+    } catch (err) {
+      expect(String(err)).to.include("ReceiptAccountsRequired");
+    }
+    await program.methods.resolveDispute({ split: { buyerBps: 5000 } }).accounts({
+      ...settleAccounts,
+      receipt: receiptPda,
+      receiptMint: receiptMint,
+      buyerReceiptAta: buyerReceiptAta,
+    } as any).rpc();
+
+    const dispute = await program.account.dispute.fetch(disputePda);
+    const profile = await program.account.sellerProfile.fetch(sellerProfilePda);
+    const settled = await program.account.payment.fetch(paymentPda);
+    const sellerAfter = await provider.connection.getTokenAccountBalance(sellerAta);
+    const treasuryAfter = await provider.connection.getTokenAccountBalance(treasuryAta);
+    expect(dispute.disputeStatus).to.have.property("resolved");
+    expect(dispute.sellerEvidence).to.deep.equal(["ipfs://courier-receipt"]);
+    expect(profile.disputesSplit).to.equal(1);
+    // The seller's half is released like a normal withdrawal, net of the
+    // marketplace fee.
+    const sellerShare = price[0] - Math.floor(price[0] / 2);
+    const fee = Math.floor((sellerShare * 250) / 10_000);
+    expect(Number(sellerAfter.value.amount) - Number(sellerBefore.value.amount))
+      .to.equal(sellerShare - fee);
+    expect(Number(treasuryAfter.value.amount) - Number(treasuryBefore.value.amount)).to.equal(fee);
+    expect(Number(settled.marketplaceFee)).to.equal(fee);
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    expect(Number(escrow.amount)).to.equal(0);
+    expect(Number(escrow.releasedAmount)).to.equal(sellerShare);
+    const receipt = await program.account.receipt.fetch(receiptPda);
+    const receiptBalance = await provider.connection.getTokenAccountBalance(buyerReceiptAta);
+    expect(receipt.mint.toBase58()).to.equal(receiptMint.toBase58());
+    expect(Number(receipt.amount)).to.equal(sellerShare);
+    expect(receiptBalance.value.amount).to.equal("1");
+  });
+
+  it("should let the admin set and rotate a 2-of-3 arbiter panel", async () => {
+    const [first, second, third, replacement] = [0, 1, 2, 3].map(() => Keypair.generate().publicKey);
+    // Panels only apply to escrows above this, so earlier purchases are unaffected.
+    const minAmount = new BN(1_000_000_000);
+
+    try {
+      await program.methods.setArbiterPanel([first, second], 3, minAmount).accounts({
+        admin: owner.publicKey,
+        marketplace: marketplacePda(),
+      } as any).rpc();
+      expect.fail("a threshold above the panel size should be rejected");
+    } catch (err) {
+      expect(String(err)).to.include("InvalidArbiterPanel");
+    }
+
+    await program.methods.setArbiterPanel([first, second, third], 2, minAmount).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    await program.methods.setArbiterPanel([first, second, replacement], 2, minAmount).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const marketplace = await program.account.marketplace.fetch(marketplacePda());
+    expect(marketplace.arbiterPanel.map((key) => key.toBase58())).to.deep.equal(
+      [first, second, replacement].map((key) => key.toBase58())
+    );
+    expect(marketplace.panelThreshold).to.equal(2);
+    expect(Number(marketplace.panelMinAmount)).to.equal(minAmount.toNumber());
+  });
+
+  it("should settle a panel dispute once enough arbiters agree", async () => {
+    const arbiters = [0, 1, 2].map(() => Keypair.generate());
+    for (const arbiter of arbiters) {
+      await provider.connection.requestAirdrop(arbiter.publicKey, LAMPORTS_PER_SOL);
+    }
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.methods.setArbiterPanel(
+      arbiters.map((arbiter) => arbiter.publicKey),
+      2,
+      new BN(price[0]),
+    ).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const [disputePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("dispute"), escrowPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.depositEscrow(new BN(price[0]), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.openDispute("ipfs://wrong-colour").accounts({
+      buyer: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      marketplace: marketplacePda(),
+      dispute: disputePda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+
+    const accountsFor = (arbiter: PublicKey) => ({
+      arbiter,
+      marketplace: marketplacePda(),
+      payment: paymentPda,
+      escrow: escrowPda,
+      dispute: disputePda,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      order: null,
+      product: productPdaFor(product_name[0]),
+      sellerProfile: PublicKey.findProgramAddressSync(
+        [Buffer.from("seller_profile"), seller.publicKey.toBuffer()],
+        program.programId
+      )[0],
+      buyer: owner.publicKey,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      treasuryAta: getAssociatedTokenAddressSync(mint, treasury.publicKey),
+      keeperVault: null,
+      mint: mint,
+      receipt: null,
+      receiptMint: null,
+      buyerReceiptAta: null,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    });
+    const [first, second, third] = arbiters;
+
+    expect((await program.account.dispute.fetch(disputePda)).panelRequired).to.be.true;
+    try {
+      await program.methods.resolveDispute({ refundBuyer: {} })
+        .accounts(accountsFor(owner.publicKey) as any).rpc();
+      expect.fail("the marketplace arbiter should not rule on a panel dispute");
+    } catch (err) {
+      expect(String(err)).to.include("PanelRulingRequired");
+    }
+    try {
+      await program.methods.voteDispute({ refundBuyer: {} })
+        .accounts(accountsFor(owner.publicKey) as any).rpc();
+      expect.fail("only panel members can vote");
+    } catch (err) {
+      expect(String(err)).to.include("UnauthorizedArbiter");
+    }
+
+    await program.methods.voteDispute({ refundBuyer: {} })
+      .accounts(accountsFor(first.publicKey) as any).signers([first]).rpc();
+    try {
+      await program.methods.voteDispute({ refundBuyer: {} })
+        .accounts(accountsFor(first.publicKey) as any).signers([first]).rpc();
+      expect.fail("an arbiter can only vote once");
+    } catch (err) {
+      expect(String(err)).to.include("AlreadyVoted");
+    }
+
+    // A dissenting vote leaves both rulings short of the threshold.
+    await program.methods.voteDispute({ releaseToSeller: {} })
+      .accounts(accountsFor(second.publicKey) as any).signers([second]).rpc();
+    const pending = await program.account.dispute.fetch(disputePda);
+    expect(pending.disputeStatus).to.have.property("open");
+    expect(pending.votes.length).to.equal(2);
+
+    const buyerBefore = await provider.connection.getTokenAccountBalance(userAta);
+    await program.methods.voteDispute({ refundBuyer: {} })
+      .accounts(accountsFor(third.publicKey) as any).signers([third]).rpc();
+    await program.methods.setArbiterPanel([], 0, new BN(0)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const dispute = await program.account.dispute.fetch(disputePda);
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    const buyerAfter = await provider.connection.getTokenAccountBalance(userAta);
+    expect(dispute.disputeStatus).to.have.property("resolved");
+    expect(dispute.ruling).to.have.property("refundBuyer");
+    expect(dispute.votes.length).to.equal(3);
+    expect(escrow.escrowStatus).to.have.property("refunded");
+    expect(Number(escrow.amount)).to.equal(0);
+    expect(Number(escrow.releasedAmount)).to.equal(0);
+    expect(Number(buyerAfter.value.amount) - Number(buyerBefore.value.amount)).to.equal(price[0]);
+  });
+
+  it("should open a keeper pool and reject crank tasks it can't handle", async () => {
+    const [keeperVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("keeper_vault"), mint.toBuffer()],
+      program.programId
+    );
+    await program.methods.initKeeperVault().accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+      mint: mint,
+      keeperVault: keeperVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.setKeeperRules(1000, new BN(100)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    const crankAccounts = {
+      keeper: owner.publicKey,
+      marketplace: marketplacePda(),
+    };
+    try {
+      await program.methods.crank([{ expireEscrow: {} }]).accounts(crankAccounts as any).remainingAccounts([
+        { pubkey: marketplacePda(), isSigner: false, isWritable: false },
+      ]).rpc();
+      expect.fail("a marketplace account is not a crank target");
+    } catch (err) {
+      expect(String(err)).to.include("UnknownCrankTarget");
+    }
+
+    // With nothing to do the crank pays nothing.
+    await program.methods.crank([]).accounts(crankAccounts as any).rpc();
+    const marketplace = await program.account.marketplace.fetch(marketplacePda());
+    const vault = await provider.connection.getTokenAccountBalance(keeperVault);
+    expect(marketplace.keeperFeeBps).to.equal(1000);
+    expect(Number(marketplace.keeperReward)).to.equal(100);
+    expect(Number(vault.value.amount)).to.equal(0);
+  });
+
+  it("should pay no keeper reward for expiring an escrow", async () => {
+    const [keeperVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("keeper_vault"), mint.toBuffer()],
+      program.programId
+    );
+    await mintTo(provider.connection, owner.payer, mint, keeperVault, owner.publicKey, 1_000);
+
+    // A 1-unit escrow the keeper set up against themselves; expiring it
+    // produces no marketplace fee, so it can't be farmed for rewards.
+    const name = "Sticker";
+    await program.methods
+      .createProduct(
+        name,
+        "A single sticker",
+        1,
+        category,
+        division,
+        seller_name[0],
+        product_imgurl[0]
+      )
+      .accounts({
+        seller: seller.publicKey,
+        product: productPdaFor(name),
+        productList: PublicKey.findProgramAddressSync(
+          [Buffer.from("product_list"), seller.publicKey.toBuffer()],
+          program.programId
+        )[0],
+        mint,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any)
+      .signers([seller])
+      .rpc();
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.setShippingWindow(new BN(1)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(name),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.depositEscrow(new BN(1), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.setShippingWindow(new BN(14 * 24 * 60 * 60)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+
+    // The task target, `expire_escrow`'s accounts, then the pool and the
+    // keeper's account for the escrow's mint.
+    const writable = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: true });
+    const keeperBefore = await provider.connection.getTokenAccountBalance(userAta);
+    const poolBefore = await provider.connection.getTokenAccountBalance(keeperVault);
+    await program.methods.crank([{ expireEscrow: {} }]).accounts({
+      keeper: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).remainingAccounts([
+      writable(escrowPda),
+      { pubkey: owner.publicKey, isSigner: true, isWritable: true },
+      writable(paymentPda),
+      writable(escrowPda),
+      writable(purchaseListPdaFor(owner.publicKey)),
+      writable(orderPda),
+      writable(vault),
+      writable(userAta),
+      writable(mint),
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      writable(keeperVault),
+      writable(userAta),
+    ]).rpc();
+
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    const pool = await provider.connection.getTokenAccountBalance(keeperVault);
+    const keeperAfter = await provider.connection.getTokenAccountBalance(userAta);
+    expect(escrow.escrowStatus).to.have.property("refunded");
+    expect(Number(pool.value.amount)).to.equal(Number(poolBefore.value.amount));
+    // Only the buyer's refund lands in the keeper's account.
+    expect(Number(keeperAfter.value.amount) - Number(keeperBefore.value.amount)).to.equal(1);
+  });
+
+  it("should release a delivered escrow through the crank once inspection ends", async () => {
+    const [keeperVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("keeper_vault"), mint.toBuffer()],
+      program.programId
+    );
+    const keeper = Keypair.generate();
+    await provider.connection.requestAirdrop(keeper.publicKey, LAMPORTS_PER_SOL);
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    const keeperAta = (await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner.payer,
+      mint,
+      keeper.publicKey
+    )).address;
+
+    const purchaseList = await program.account.purchaseList.fetch(
+      purchaseListPdaFor(owner.publicKey)
+    );
+    const paymentPda = paymentPdaFor(owner.publicKey, Number(purchaseList.paymentCount));
+    const escrowPda = escrowPdaFor(paymentPda);
+    const vault = getAssociatedTokenAddressSync(mint, escrowPda, true);
+    const treasuryAta = getAssociatedTokenAddressSync(mint, treasury.publicKey);
+    const [orderPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("order"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [receiptPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), paymentPda.toBuffer()],
+      program.programId
+    );
+    const [receiptMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), paymentPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods.createPayment(1, null, new BN(0)).accounts({
+      signer: owner.publicKey,
+      purchaseList: purchaseListPdaFor(owner.publicKey),
+      payments: paymentPda,
+      product: productPdaFor(product_name[0]),
+      cart: null,
+      mint: mint,
+      marketplace: null,
+      acceptedMint: null,
+      priceFeed: null,
+      coupon: null,
+      couponRedemption: null,
+      loyalty: null,
+      pointsMint: null,
+      buyerPoints: null,
+      pointsTokenProgram: null,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.createEscrow(owner.publicKey, seller.publicKey, []).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      marketplace: marketplacePda(),
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.depositEscrow(new BN(price[0]), new BN(0)).accounts({
+      owner: owner.publicKey,
+      payment: paymentPda,
+      escrow: escrowPda,
+      userAta: userAta,
+      escrowAta: vault,
+      buyerAta: userAta,
+      sellerAta: sellerAta,
+      mint: mint,
+      giftCard: null,
+      giftCardVault: null,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    } as any).rpc();
+    const payment = await program.account.payment.fetch(paymentPda);
+    await program.methods.createOrder(String(bytesToUuid(payment.paymentId))).accounts({
+      signer: owner.publicKey,
+      order: orderPda,
+      payment: paymentPda,
+      systemProgram: SYSTEM_PROGRAM_ID,
+    } as any).rpc();
+    await program.methods.updateOrderTracking({ delivered: {} }).accounts({
+      seller: seller.publicKey,
+      payment: paymentPda,
+      order: orderPda,
+      paymentPlan: null,
+    } as any).signers([seller]).rpc();
+
+    // The task target, `withdraw_escrow`'s accounts with the keeper as the
+    // releasing authority, then the pool and the keeper's account.
+    const writable = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: true });
+    const readonly = (pubkey: PublicKey) => ({ pubkey, isSigner: false, isWritable: false });
+    const release = () => program.methods.crank([{ releaseEscrow: {} }]).accounts({
+      keeper: keeper.publicKey,
+      marketplace: marketplacePda(),
+    } as any).remainingAccounts([
+      writable(escrowPda),
+      { pubkey: keeper.publicKey, isSigner: true, isWritable: true },
+      writable(owner.publicKey),
+      writable(paymentPda),
+      writable(escrowPda),
+      writable(purchaseListPdaFor(owner.publicKey)),
+      readonly(marketplacePda()),
+      readonly(productPdaFor(product_name[0])),
+      readonly(sellerProfilePdaFor(seller.publicKey)),
+      writable(userAta),
+      writable(vault),
+      writable(userAta),
+      writable(sellerAta),
+      writable(treasuryAta),
+      writable(keeperVault),
+      writable(mint),
+      readonly(acceptedMintPda(mint)),
+      readonly(PublicKey.findProgramAddressSync([Buffer.from("loyalty")], program.programId)[0]),
+      writable(pointsMintPda()),
+      writable(getAssociatedTokenAddressSync(pointsMintPda(), owner.publicKey)),
+      readonly(TOKEN_PROGRAM_ID),
+      readonly(orderPda),
+      writable(receiptPda),
+      writable(receiptMint),
+      writable(getAssociatedTokenAddressSync(receiptMint, owner.publicKey)),
+      readonly(ASSOCIATED_TOKEN_PROGRAM_ID),
+      readonly(SystemProgram.programId),
+      readonly(TOKEN_PROGRAM_ID),
+      writable(keeperVault),
+      writable(keeperAta),
+    ]).signers([keeper]).rpc();
+
+    try {
+      await release();
+      expect.fail("a keeper should not release during the inspection window");
+    } catch (err) {
+      expect(String(err)).to.include("InspectionPeriodActive");
+    }
+
+    await program.methods.setInspectionPeriod(new BN(0)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+    const poolBefore = await provider.connection.getTokenAccountBalance(keeperVault);
+    await release();
+    await program.methods.setInspectionPeriod(new BN(7 * 24 * 60 * 60)).accounts({
+      admin: owner.publicKey,
+      marketplace: marketplacePda(),
+    } as any).rpc();
+
+    // The pool takes its share of the fee, then pays the keeper no more
+    // than the fee the release produced.
+    const settled = await program.account.payment.fetch(paymentPda);
+    const escrow = await program.account.escrow.fetch(escrowPda);
+    const pool = await provider.connection.getTokenAccountBalance(keeperVault);
+    const keeperAfter = await provider.connection.getTokenAccountBalance(keeperAta);
+    const fee = Number(settled.marketplaceFee);
+    const reward = Math.min(100, fee);
+    expect(escrow.escrowStatus).to.have.property("swapSuccess");
+    expect(fee).to.be.greaterThan(0);
+    expect(Number(keeperAfter.value.amount)).to.equal(reward);
+    expect(Number(pool.value.amount) - Number(poolBefore.value.amount))
+      .to.equal(Math.floor((fee * 1000) / 10_000) - reward);
+  });
+});
@@ -1,5 +1,5 @@
 "use client";
-import { fetchConfirmPayment, initCreateEscrow, initCreatePayment, initDepositeEscrow, initWithdrawEscrow, fetchAccountBalances } from "@/sdk/program";
+import { fetchCartList, fetchConfirmPayment, initCreateEscrow, initCreatePayment, initDepositeEscrow, initWithdrawEscrow, fetchAccountBalances } from "@/sdk/program";
 import { useWallet } from "@solana/wallet-adapter-react";
 import { LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { useEffect, useState } from "react";
@@ -86,51 +86,62 @@ export default function(){
             return;
         }
         try {
-            const result = await initCreatePayment(walletAdapter,totalAmount);
-            if (result.success) {
-                const escrowInit = await initCreateEscrow(wallet,sellerPubkey.toString(),totalAmount);
-                console.log("Escrow Details: ",escrowInit);
-                if (escrowInit && escrowInit.success) {
-                    toast.success("Escrow created successfully!");
+            const cartList = await fetchCartList(wallet);
+            const cartPdas = cartList.success ? (cartList.cart?.cartList ?? []) : [];
+            if (cartPdas.length === 0) {
+                toast.error("Your cart is empty");
+                return;
+            }
+            // Each cart entry is its own purchase with its own payment and escrow.
+            for (const cartPda of cartPdas) {
+                const result = await initCreatePayment(wallet, cartPda.toString());
+                if (result.success && result.payment) {
+                    const escrowInit = await initCreateEscrow(wallet, result.payment);
+                    console.log("Escrow Details: ",escrowInit);
+                    if (escrowInit && escrowInit.success) {
+                        toast.success("Escrow created successfully!");
                     
-                    if (escrowInit.data) {
-                        setEscrowPda(escrowInit.data.toString());
-                        console.log("Escrow PDA stored:", escrowInit.data.toString());
-                        setTimeout(() => {
-                            fetchBalances();
-                        }, 1000);
-                    }
-                    // const depositeEscrow = await initDepositeEscrow(walletAdapter, sellerPubkey.toString(), totalAmount, escrowInit.mint);
-                    // console.log("Escrow Details: ",depositeEscrow);
+                        if (escrowInit.data) {
+                            setEscrowPda(escrowInit.data.toString());
+                            console.log("Escrow PDA stored:", escrowInit.data.toString());
+                            setTimeout(() => {
+                                fetchBalances();
+                            }, 1000);
+                        }
+                        // const depositeEscrow = await initDepositeEscrow(wallet, result.payment);
+                        // console.log("Escrow Details: ",depositeEscrow);
                     
-                    // if (depositeEscrow.success) { 
-                    //     toast.success("Deposit escrow successful!"); 
-                    // } else {
-                    //     toast.error(`Deposit escrow failed: ${depositeEscrow.error}`);
-                    // }
+                        // if (depositeEscrow.success) { 
+                        //     toast.success("Deposit escrow successful!"); 
+                        // } else {
+                        //     toast.error(`Deposit escrow failed: ${depositeEscrow.error}`);
+                        // }
                     
-                    // const withdrawEscrow = await initWithdrawEscrow(walletAdapter, sellerPubkey.toString(), totalAmount, escrowInit.mint);
-                    // console.log("Escrow Details: ",withdrawEscrow);
+                        // const withdrawEscrow = await initWithdrawEscrow(wallet, result.payment);
+                        // console.log("Escrow Details: ",withdrawEscrow);
                     
-                    // if (withdrawEscrow.success) {
-                    //     toast.success("Withdraw escrow successful!"); 
-                    // } else {
-                    //     toast.error(`Withdraw escrow failed: ${withdrawEscrow.error}`);
-                    // }
+                        // if (withdrawEscrow.success) {
+                        //     toast.success("Withdraw escrow successful!"); 
+                        // } else {
+                        //     toast.error(`Withdraw escrow failed: ${withdrawEscrow.error}`);
+                        // }
                     
-                    const fetchPaymentConfirmation = await fetchConfirmPayment(walletAdapter);
-                    console.log("Payment Confirmation: ",fetchPaymentConfirmation);
+                        const fetchPaymentConfirmation = await fetchConfirmPayment(wallet, result.payment);
+                        console.log("Payment Confirmation: ",fetchPaymentConfirmation);
                     
-                    if (fetchPaymentConfirmation.success) {
-                        toast.success("Payment confirmed successfully!"); 
+                        if (fetchPaymentConfirmation.success) {
+                            toast.success("Payment confirmed successfully!"); 
+                        } else {
+                            toast.error(`Payment confirmation failed: ${fetchPaymentConfirmation.error}`);
+                        }
                     } else {
-                        toast.error(`Payment confirmation failed: ${fetchPaymentConfirmation.error}`);
+                        toast.error(`Escrow creation failed: ${escrowInit?.error || 'Unknown error'}`);
+                        return;
                     }
                 } else {
-                    toast.error(`Escrow creation failed: ${escrowInit?.error || 'Unknown error'}`);
+                    toast.error(`Payment creation failed: ${result.error}`);
+                    return;
                 }
-            } else {
-                toast.error(`Payment creation failed: ${result.error}`);
             }
         } catch (error) {
             console.error(error);
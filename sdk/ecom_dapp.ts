@@ -13,6 +13,56 @@ export type EcomDapp = {
     "description": "Created with Anchor"
   },
   "instructions": [
+    {
+      "name": "addDisputeEvidence",
+      "discriminator": [
+        154,
+        54,
+        114,
+        31,
+        211,
+        6,
+        111,
+        187
+      ],
+      "accounts": [
+        {
+          "name": "submitter",
+          "signer": true
+        },
+        {
+          "name": "dispute",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  100,
+                  105,
+                  115,
+                  112,
+                  117,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "dispute.escrow",
+                "account": "dispute"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "uri",
+          "type": "string"
+        }
+      ]
+    },
     {
       "name": "addToCart",
       "discriminator": [
@@ -115,182 +165,117 @@ export type EcomDapp = {
       ]
     },
     {
-      "name": "createEscrow",
+      "name": "billSubscription",
       "discriminator": [
-        253,
-        215,
-        165,
-        116,
-        36,
-        108,
-        68,
-        80
+        115,
+        136,
+        38,
+        148,
+        179,
+        99,
+        229,
+        121
       ],
       "accounts": [
         {
-          "name": "owner",
+          "name": "cranker",
           "writable": true,
           "signer": true
         },
         {
-          "name": "escrow",
+          "name": "subscription",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  101,
+                  115,
+                  117,
+                  98,
                   115,
                   99,
                   114,
+                  105,
+                  112,
+                  116,
+                  105,
                   111,
-                  119
+                  110
                 ]
               },
               {
                 "kind": "account",
-                "path": "owner"
+                "path": "subscription.buyer",
+                "account": "subscription"
+              },
+              {
+                "kind": "account",
+                "path": "subscription.product",
+                "account": "subscription"
               }
             ]
           }
         },
         {
-          "name": "payment",
-          "writable": true,
+          "name": "marketplace",
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  112,
-                  97,
-                  121,
                   109,
+                  97,
+                  114,
+                  107,
                   101,
-                  110,
-                  116
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
                 ]
-              },
-              {
-                "kind": "account",
-                "path": "owner"
               }
             ]
           }
         },
         {
-          "name": "userAta",
-          "writable": true
-        },
-        {
-          "name": "escrowAta",
-          "writable": true
-        },
-        {
-          "name": "buyerAta",
-          "writable": true
-        },
-        {
-          "name": "sellerAta",
-          "writable": true
-        },
-        {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        },
-        {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "buyerPubkey",
-          "type": "pubkey"
-        },
-        {
-          "name": "sellerPubkey",
-          "type": "pubkey"
-        },
-        {
-          "name": "amount",
-          "type": "u64"
-        }
-      ]
-    },
-    {
-      "name": "createOrder",
-      "discriminator": [
-        141,
-        54,
-        37,
-        207,
-        237,
-        210,
-        250,
-        215
-      ],
-      "accounts": [
-        {
-          "name": "signer",
-          "writable": true,
-          "signer": true
+          "name": "product"
         },
         {
-          "name": "order",
+          "name": "purchaseList",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  111,
+                  112,
+                  117,
                   114,
-                  100,
+                  99,
+                  104,
+                  97,
+                  115,
                   101,
-                  114
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
                 ]
               },
               {
                 "kind": "account",
-                "path": "signer"
+                "path": "subscription.buyer",
+                "account": "subscription"
               }
             ]
           }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "paymentId",
-          "type": "string"
-        }
-      ]
-    },
-    {
-      "name": "createPayment",
-      "discriminator": [
-        28,
-        81,
-        85,
-        253,
-        7,
-        223,
-        154,
-        42
-      ],
-      "accounts": [
-        {
-          "name": "signer",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "payments",
+          "name": "payment",
           "writable": true,
           "pda": {
             "seeds": [
@@ -308,195 +293,179 @@ export type EcomDapp = {
               },
               {
                 "kind": "account",
-                "path": "signer"
+                "path": "subscription.buyer",
+                "account": "subscription"
+              },
+              {
+                "kind": "account",
+                "path": "purchase_list.payment_count",
+                "account": "purchaseList"
               }
             ]
           }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "paymentAmount",
-          "type": "u64"
-        },
-        {
-          "name": "productPubkey",
-          "type": "pubkey"
-        },
-        {
-          "name": "txSignature",
-          "type": {
-            "option": "string"
-          }
-        }
-      ]
-    },
-    {
-      "name": "createProduct",
-      "discriminator": [
-        183,
-        155,
-        202,
-        119,
-        43,
-        114,
-        174,
-        225
-      ],
-      "accounts": [
-        {
-          "name": "seller",
-          "writable": true,
-          "signer": true
-        },
-        {
-          "name": "product",
+          "name": "escrow",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  112,
+                  101,
+                  115,
+                  99,
                   114,
                   111,
-                  100,
-                  117,
-                  99,
-                  116
+                  119
                 ]
               },
               {
                 "kind": "account",
-                "path": "seller"
-              },
-              {
-                "kind": "arg",
-                "path": "productName"
+                "path": "payment"
               }
             ]
           }
         },
         {
-          "name": "productList",
+          "name": "order",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
-                  112,
-                  114,
                   111,
+                  114,
                   100,
-                  117,
-                  99,
-                  116,
-                  95,
-                  108,
-                  105,
-                  115,
-                  116
+                  101,
+                  114
                 ]
               },
               {
                 "kind": "account",
-                "path": "seller"
+                "path": "payment"
               }
             ]
           }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
-        }
-      ],
-      "args": [
-        {
-          "name": "productName",
-          "type": "string"
+          "name": "delegate",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "subscription.buyer",
+                "account": "subscription"
+              }
+            ]
+          }
         },
         {
-          "name": "productShortDescription",
-          "type": "string"
+          "name": "buyerAta",
+          "writable": true
         },
         {
-          "name": "price",
-          "type": "u32"
+          "name": "escrowAta",
+          "docs": [
+            "a failed period leaves no token account behind."
+          ],
+          "writable": true
         },
         {
-          "name": "category",
-          "type": {
-            "defined": {
-              "name": "category"
-            }
-          }
+          "name": "mint"
         },
         {
-          "name": "division",
-          "type": {
-            "defined": {
-              "name": "division"
-            }
-          }
+          "name": "tokenProgram"
         },
         {
-          "name": "sellerName",
-          "type": "string"
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
         },
         {
-          "name": "productImgurl",
-          "type": "string"
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
         }
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "depositEscrow",
+      "name": "cancelPayment",
       "discriminator": [
-        226,
-        112,
-        158,
-        176,
-        178,
-        118,
-        153,
-        128
+        217,
+        129,
+        71,
+        37,
+        216,
+        193,
+        38,
+        33
       ],
       "accounts": [
         {
           "name": "owner",
+          "docs": [
+            "Receives the rent of an unfunded escrow and its vault."
+          ],
           "writable": true,
           "signer": true
         },
         {
-          "name": "escrow",
+          "name": "rentPayer",
+          "writable": true
+        },
+        {
+          "name": "payment",
           "writable": true,
           "pda": {
             "seeds": [
               {
                 "kind": "const",
                 "value": [
+                  112,
+                  97,
+                  121,
+                  109,
                   101,
-                  115,
-                  99,
-                  114,
-                  111,
-                  119
+                  110,
+                  116
                 ]
               },
               {
                 "kind": "account",
                 "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
               }
             ]
           }
         },
         {
-          "name": "payment",
+          "name": "purchaseList",
           "writable": true,
           "pda": {
             "seeds": [
@@ -504,11 +473,17 @@ export type EcomDapp = {
                 "kind": "const",
                 "value": [
                   112,
+                  117,
+                  114,
+                  99,
+                  104,
                   97,
-                  121,
-                  109,
+                  115,
                   101,
-                  110,
+                  95,
+                  108,
+                  105,
+                  115,
                   116
                 ]
               },
@@ -520,55 +495,124 @@ export type EcomDapp = {
           }
         },
         {
-          "name": "userAta",
-          "writable": true
-        },
-        {
-          "name": "escrowAta",
-          "writable": true
+          "name": "escrow",
+          "docs": [
+            "has been created but never funded."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
         },
         {
-          "name": "buyerAta",
-          "writable": true
+          "name": "order",
+          "docs": [
+            "has been created."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
         },
         {
-          "name": "sellerAta",
-          "writable": true
+          "name": "escrowAta",
+          "docs": [
+            "Required together with `mint` and `token_program` once the escrow",
+            "has been created."
+          ],
+          "writable": true,
+          "optional": true
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "mint",
+          "writable": true,
+          "optional": true
         },
         {
           "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+          "optional": true
         }
       ],
-      "args": [
-        {
-          "name": "productId",
-          "type": "u32"
-        }
-      ]
+      "args": []
     },
     {
-      "name": "withdrawEscrow",
+      "name": "cancelPaymentPlan",
       "discriminator": [
-        81,
-        84,
-        226,
-        128,
-        245,
-        47,
-        96,
-        104
+        106,
+        55,
+        147,
+        190,
+        173,
+        252,
+        137,
+        174
       ],
       "accounts": [
         {
-          "name": "owner",
-          "writable": true,
+          "name": "seller",
           "signer": true
         },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
         {
           "name": "escrow",
           "writable": true,
@@ -587,13 +631,13 @@ export type EcomDapp = {
               },
               {
                 "kind": "account",
-                "path": "owner"
+                "path": "payment"
               }
             ]
           }
         },
         {
-          "name": "payment",
+          "name": "paymentPlan",
           "writable": true,
           "pda": {
             "seeds": [
@@ -606,305 +650,9224 @@ export type EcomDapp = {
                   109,
                   101,
                   110,
-                  116
+                  116,
+                  95,
+                  112,
+                  108,
+                  97,
+                  110
                 ]
               },
               {
                 "kind": "account",
-                "path": "owner"
+                "path": "payment"
               }
             ]
           }
         },
         {
-          "name": "userAta",
-          "writable": true
-        },
-        {
-          "name": "escrowAta",
-          "writable": true
-        },
-        {
-          "name": "buyerAta",
-          "writable": true
-        },
-        {
-          "name": "sellerAta",
-          "writable": true
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              }
+            ]
+          }
         },
         {
-          "name": "systemProgram",
-          "address": "11111111111111111111111111111111"
+          "name": "order",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
         },
         {
-          "name": "tokenProgram",
-          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
-        }
-      ],
-      "args": [
+          "name": "buyer",
+          "docs": [
+            "Receives the vault's rent once it is emptied."
+          ],
+          "writable": true
+        },
         {
-          "name": "productId",
-          "type": "u32"
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "mint",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram"
         }
-      ]
-    }
-  ],
-  "accounts": [
-    {
-      "name": "cart",
-      "discriminator": [
-        110,
-        9,
-        100,
-        44,
-        36,
-        143,
-        131,
-        88
-      ]
-    },
-    {
-      "name": "cartList",
-      "discriminator": [
-        25,
-        206,
-        228,
-        30,
-        190,
-        207,
-        217,
-        135
-      ]
+      ],
+      "args": []
     },
     {
-      "name": "escrow",
+      "name": "closePurchase",
       "discriminator": [
-        31,
-        213,
-        123,
-        187,
-        186,
-        22,
-        218,
-        155
-      ]
+        139,
+        230,
+        239,
+        68,
+        76,
+        85,
+        196,
+        138
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "Anyone; pays for the archive record when one is written."
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "rentPayer",
+          "writable": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "docs": [
+            "behind; closed along with the rest if it has been created."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "archive",
+          "docs": [
+            "Pass to keep a compact record of the purchase."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  114,
+                  99,
+                  104,
+                  105,
+                  118,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
     },
     {
-      "name": "order",
+      "name": "crank",
       "discriminator": [
-        134,
-        173,
-        223,
-        185,
-        77,
-        86,
-        28,
-        51
+        0,
+        232,
+        3,
+        195,
+        124,
+        117,
+        105,
+        53
+      ],
+      "accounts": [
+        {
+          "name": "keeper",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "tasks",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "crankTask"
+              }
+            }
+          }
+        }
       ]
     },
     {
-      "name": "payment",
+      "name": "createCoupon",
       "discriminator": [
-        227,
-        231,
-        51,
-        26,
-        244,
+        29,
+        170,
+        159,
         88,
-        4,
-        148
-      ]
-    },
-    {
-      "name": "product",
-      "discriminator": [
-        102,
-        76,
-        55,
-        251,
-        38,
-        73,
-        224,
-        229
+        211,
+        20,
+        13,
+        56
+      ],
+      "accounts": [
+        {
+          "name": "creator",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "coupon",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  99,
+                  111,
+                  117,
+                  112,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "creator"
+              },
+              {
+                "kind": "arg",
+                "path": "codeHash"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "codeHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        },
+        {
+          "name": "discount",
+          "type": {
+            "defined": {
+              "name": "couponDiscount"
+            }
+          }
+        },
+        {
+          "name": "scope",
+          "type": {
+            "defined": {
+              "name": "couponScope"
+            }
+          }
+        },
+        {
+          "name": "expiresAt",
+          "type": "i64"
+        },
+        {
+          "name": "maxRedemptions",
+          "type": "u32"
+        },
+        {
+          "name": "perWalletCap",
+          "type": "u16"
+        }
       ]
     },
     {
-      "name": "productsList",
+      "name": "createEscrow",
       "discriminator": [
-        149,
-        195,
-        105,
-        191,
-        84,
-        99,
-        84,
-        70
+        253,
+        215,
+        165,
+        116,
+        36,
+        108,
+        68,
+        80
+      ],
+      "accounts": [
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "userAta",
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "docs": [
+            "Vault created here, with the escrow PDA as its authority. Anyone can",
+            "open an ATA, so one opened ahead of time is reused rather than",
+            "blocking the escrow."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "buyerPubkey",
+          "type": "pubkey"
+        },
+        {
+          "name": "sellerPubkey",
+          "type": "pubkey"
+        },
+        {
+          "name": "milestones",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "milestoneTerm"
+              }
+            }
+          }
+        }
       ]
-    }
-  ],
-  "events": [
+    },
+    {
+      "name": "createGiftCard",
+      "discriminator": [
+        202,
+        141,
+        181,
+        51,
+        5,
+        224,
+        200,
+        17
+      ],
+      "accounts": [
+        {
+          "name": "issuer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "giftCard",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  105,
+                  102,
+                  116,
+                  95,
+                  99,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "codeHash"
+              }
+            ]
+          }
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  105,
+                  102,
+                  116,
+                  95,
+                  99,
+                  97,
+                  114,
+                  100,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "giftCard"
+              }
+            ]
+          }
+        },
+        {
+          "name": "issuerAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "codeHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        },
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "holder",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "claimKey",
+          "type": {
+            "option": "pubkey"
+          }
+        }
+      ]
+    },
+    {
+      "name": "createOrder",
+      "discriminator": [
+        141,
+        54,
+        37,
+        207,
+        237,
+        210,
+        250,
+        215
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "paymentId",
+          "type": "string"
+        }
+      ]
+    },
+    {
+      "name": "createPayment",
+      "discriminator": [
+        28,
+        81,
+        85,
+        253,
+        7,
+        223,
+        154,
+        42
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payments",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              },
+              {
+                "kind": "account",
+                "path": "purchase_list.payment_count",
+                "account": "purchaseList"
+              }
+            ]
+          }
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "cart",
+          "docs": [
+            "Optional cart entry for `product`; when given, its quantity is charged."
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  99,
+                  97,
+                  114,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              },
+              {
+                "kind": "account",
+                "path": "product.product_name",
+                "account": "product"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "marketplace",
+          "docs": [
+            "Required for USD-priced products."
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "acceptedMint",
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  99,
+                  99,
+                  101,
+                  112,
+                  116,
+                  101,
+                  100,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "priceFeed",
+          "optional": true
+        },
+        {
+          "name": "coupon",
+          "docs": [
+            "Required together with `coupon_redemption` when a coupon code is given."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  99,
+                  111,
+                  117,
+                  112,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "coupon.creator",
+                "account": "coupon"
+              },
+              {
+                "kind": "account",
+                "path": "coupon.code_hash",
+                "account": "coupon"
+              }
+            ]
+          }
+        },
+        {
+          "name": "couponRedemption",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  99,
+                  111,
+                  117,
+                  112,
+                  111,
+                  110,
+                  95,
+                  114,
+                  101,
+                  100,
+                  101,
+                  109,
+                  112,
+                  116,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "coupon"
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "loyalty",
+          "docs": [
+            "Required when loyalty points are burned for a discount."
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "pointsMint",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "buyerPoints",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "pointsTokenProgram",
+          "optional": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "couponCode",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "redeemPoints",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createPaymentPlan",
+      "discriminator": [
+        224,
+        8,
+        120,
+        24,
+        154,
+        243,
+        155,
+        245
+      ],
+      "accounts": [
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "paymentPlan",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116,
+                  95,
+                  112,
+                  108,
+                  97,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "dueDates",
+          "type": {
+            "vec": "i64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "createProduct",
+      "discriminator": [
+        183,
+        155,
+        202,
+        119,
+        43,
+        114,
+        174,
+        225
+      ],
+      "accounts": [
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "product",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  114,
+                  111,
+                  100,
+                  117,
+                  99,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "seller"
+              },
+              {
+                "kind": "arg",
+                "path": "productName"
+              }
+            ]
+          }
+        },
+        {
+          "name": "productList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  114,
+                  111,
+                  100,
+                  117,
+                  99,
+                  116,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "seller"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint",
+          "docs": [
+            "Token `price` is quoted in."
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "productName",
+          "type": "string"
+        },
+        {
+          "name": "productShortDescription",
+          "type": "string"
+        },
+        {
+          "name": "price",
+          "type": "u32"
+        },
+        {
+          "name": "category",
+          "type": {
+            "defined": {
+              "name": "category"
+            }
+          }
+        },
+        {
+          "name": "division",
+          "type": {
+            "defined": {
+              "name": "division"
+            }
+          }
+        },
+        {
+          "name": "sellerName",
+          "type": "string"
+        },
+        {
+          "name": "productImgurl",
+          "type": "string"
+        }
+      ]
+    },
+    {
+      "name": "createSubscription",
+      "discriminator": [
+        65,
+        71,
+        10,
+        60,
+        249,
+        82,
+        197,
+        12
+      ],
+      "accounts": [
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "subscription",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  117,
+                  98,
+                  115,
+                  99,
+                  114,
+                  105,
+                  112,
+                  116,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "product"
+              }
+            ]
+          }
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "delegate",
+          "docs": [
+            "Shared with relayed payments, since a token account has only one",
+            "delegate."
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "interval",
+          "type": "i64"
+        },
+        {
+          "name": "allowance",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "depositEscrow",
+      "discriminator": [
+        226,
+        112,
+        158,
+        176,
+        178,
+        118,
+        153,
+        128
+      ],
+      "accounts": [
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "userAta",
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "giftCard",
+          "docs": [
+            "Only needed when part of the deposit is drawn from a gift card."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  105,
+                  102,
+                  116,
+                  95,
+                  99,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "gift_card.code_hash",
+                "account": "giftCard"
+              }
+            ]
+          }
+        },
+        {
+          "name": "giftCardVault",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "giftCardAmount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "expireEscrow",
+      "discriminator": [
+        49,
+        150,
+        54,
+        201,
+        45,
+        106,
+        39,
+        175
+      ],
+      "accounts": [
+        {
+          "name": "caller",
+          "docs": [
+            "Anyone; collects the vault's rent as a reward for the crank."
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "docs": [
+            "the shipping check by leaving it out; read only if it has been created."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "mint",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "initKeeperVault",
+      "discriminator": [
+        181,
+        222,
+        240,
+        172,
+        60,
+        100,
+        214,
+        77
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "keeperVault",
+          "docs": [
+            "Keeper fee pool for `mint`, spendable only by the marketplace PDA."
+          ],
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  107,
+                  101,
+                  101,
+                  112,
+                  101,
+                  114,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "initializeLoyalty",
+      "discriminator": [
+        4,
+        243,
+        25,
+        105,
+        14,
+        205,
+        78,
+        113
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "loyalty",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "pointsMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "pointsPerUnit",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "initializeMarketplace",
+      "discriminator": [
+        47,
+        81,
+        64,
+        0,
+        96,
+        56,
+        105,
+        7
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "maxPriceAge",
+          "type": "u64"
+        },
+        {
+          "name": "maxConfidenceBps",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "openDispute",
+      "discriminator": [
+        137,
+        25,
+        99,
+        119,
+        23,
+        223,
+        161,
+        42
+      ],
+      "accounts": [
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "dispute",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  100,
+                  105,
+                  115,
+                  112,
+                  117,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "evidenceUri",
+          "type": "string"
+        }
+      ]
+    },
+    {
+      "name": "payInstallment",
+      "discriminator": [
+        214,
+        118,
+        104,
+        215,
+        242,
+        93,
+        33,
+        60
+      ],
+      "accounts": [
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "paymentPlan",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116,
+                  95,
+                  112,
+                  108,
+                  97,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "userAta",
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "redeemGiftCard",
+      "discriminator": [
+        213,
+        90,
+        9,
+        183,
+        232,
+        155,
+        243,
+        102
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "docs": [
+            "Becomes the holder."
+          ],
+          "signer": true
+        },
+        {
+          "name": "claimAuthority",
+          "signer": true
+        },
+        {
+          "name": "giftCard",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  105,
+                  102,
+                  116,
+                  95,
+                  99,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "gift_card.code_hash",
+                "account": "giftCard"
+              }
+            ]
+          }
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "refundEscrow",
+      "discriminator": [
+        107,
+        186,
+        89,
+        99,
+        26,
+        194,
+        23,
+        204
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "Only the seller refunds; every path that fails an order already",
+            "returns the escrow to the buyer."
+          ],
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyer",
+          "docs": [
+            "Receives the vault's rent once the escrow is fully refunded."
+          ],
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "mint",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "registerPaymentMint",
+      "discriminator": [
+        0,
+        124,
+        187,
+        22,
+        220,
+        49,
+        183,
+        252
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "acceptedMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  99,
+                  99,
+                  101,
+                  112,
+                  116,
+                  101,
+                  100,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "priceFeed"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "relayPayment",
+      "discriminator": [
+        27,
+        79,
+        127,
+        140,
+        130,
+        140,
+        175,
+        108
+      ],
+      "accounts": [
+        {
+          "name": "relayer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "relayNonce",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  108,
+                  97,
+                  121,
+                  95,
+                  110,
+                  111,
+                  110,
+                  99,
+                  101
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "intent.buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "intent.buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "intent.buyer"
+              },
+              {
+                "kind": "account",
+                "path": "purchase_list.payment_count",
+                "account": "purchaseList"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "delegate",
+          "docs": [
+            "Subscriptions bill through the same PDA, since a token account has",
+            "only one delegate."
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116,
+                  95,
+                  100,
+                  101,
+                  108,
+                  101,
+                  103,
+                  97,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "intent.buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "instructions",
+          "address": "Sysvar1nstructions1111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "intent",
+          "type": {
+            "defined": {
+              "name": "paymentIntent"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "releaseMilestones",
+      "discriminator": [
+        169,
+        84,
+        233,
+        21,
+        137,
+        42,
+        92,
+        209
+      ],
+      "accounts": [
+        {
+          "name": "caller",
+          "docs": [
+            "Anyone; the schedule and the order's tracking decide what is due."
+          ],
+          "signer": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "sellerProfile",
+          "docs": [
+            "required so a seller can't leave it out to skip a tier fee; may not",
+            "exist."
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  101,
+                  114,
+                  95,
+                  112,
+                  114,
+                  111,
+                  102,
+                  105,
+                  108,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow.seller_pubkey",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasuryAta",
+          "writable": true
+        },
+        {
+          "name": "keeperVault",
+          "docs": [
+            "Required once the marketplace diverts part of its fee to keepers."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  107,
+                  101,
+                  101,
+                  112,
+                  101,
+                  114,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "resolveDispute",
+      "discriminator": [
+        231,
+        6,
+        202,
+        6,
+        96,
+        103,
+        12,
+        230
+      ],
+      "accounts": [
+        {
+          "name": "arbiter",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "dispute",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  100,
+                  105,
+                  115,
+                  112,
+                  117,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "sellerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  101,
+                  114,
+                  95,
+                  112,
+                  114,
+                  111,
+                  102,
+                  105,
+                  108,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow.seller_pubkey",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyer",
+          "docs": [
+            "Receives the vault's rent."
+          ],
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasuryAta",
+          "writable": true
+        },
+        {
+          "name": "keeperVault",
+          "docs": [
+            "Required once the marketplace diverts part of its fee to keepers."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  107,
+                  101,
+                  101,
+                  112,
+                  101,
+                  114,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint",
+          "writable": true
+        },
+        {
+          "name": "receipt",
+          "docs": [
+            "The buyer's receipt, required when the ruling releases anything to",
+            "the seller."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receiptMint",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerReceiptAta",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "receiptMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "ruling",
+          "type": {
+            "defined": {
+              "name": "disputeRuling"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "setArbiter",
+      "discriminator": [
+        15,
+        205,
+        194,
+        180,
+        172,
+        213,
+        113,
+        211
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "arbiter",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "setArbiterPanel",
+      "discriminator": [
+        159,
+        14,
+        179,
+        123,
+        144,
+        184,
+        124,
+        146
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "arbiters",
+          "type": {
+            "vec": "pubkey"
+          }
+        },
+        {
+          "name": "threshold",
+          "type": "u8"
+        },
+        {
+          "name": "minAmount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "setCategoryFee",
+      "discriminator": [
+        16,
+        68,
+        24,
+        181,
+        91,
+        76,
+        228,
+        123
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "category",
+          "type": {
+            "defined": {
+              "name": "category"
+            }
+          }
+        },
+        {
+          "name": "feeBps",
+          "type": {
+            "option": "u16"
+          }
+        }
+      ]
+    },
+    {
+      "name": "setInspectionPeriod",
+      "discriminator": [
+        171,
+        113,
+        184,
+        144,
+        248,
+        195,
+        87,
+        185
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "inspectionPeriod",
+          "type": "i64"
+        }
+      ]
+    },
+    {
+      "name": "setInstallmentRules",
+      "discriminator": [
+        65,
+        41,
+        149,
+        125,
+        235,
+        8,
+        43,
+        158
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "releaseBps",
+          "type": "u16"
+        },
+        {
+          "name": "gracePeriod",
+          "type": "i64"
+        },
+        {
+          "name": "defaultFeeBps",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "setKeeperRules",
+      "discriminator": [
+        62,
+        42,
+        14,
+        144,
+        101,
+        184,
+        147,
+        229
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "keeperFeeBps",
+          "type": "u16"
+        },
+        {
+          "name": "keeperReward",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "setLoyaltyRate",
+      "discriminator": [
+        135,
+        62,
+        62,
+        188,
+        153,
+        51,
+        94,
+        111
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "loyalty",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "category",
+          "type": {
+            "defined": {
+              "name": "category"
+            }
+          }
+        },
+        {
+          "name": "rateBps",
+          "type": {
+            "option": "u16"
+          }
+        }
+      ]
+    },
+    {
+      "name": "setMarketplaceFee",
+      "discriminator": [
+        95,
+        52,
+        251,
+        66,
+        162,
+        162,
+        118,
+        201
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "feeBps",
+          "type": "u16"
+        },
+        {
+          "name": "treasury",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "setMockPrice",
+      "discriminator": [
+        161,
+        22,
+        71,
+        90,
+        159,
+        254,
+        26,
+        48
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "mockPriceFeed",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  111,
+                  99,
+                  107,
+                  95,
+                  112,
+                  114,
+                  105,
+                  99,
+                  101,
+                  95,
+                  102,
+                  101,
+                  101,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "authority"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": "i64"
+        },
+        {
+          "name": "conf",
+          "type": "u64"
+        },
+        {
+          "name": "expo",
+          "type": "i32"
+        }
+      ]
+    },
+    {
+      "name": "setPayoutSplits",
+      "discriminator": [
+        234,
+        97,
+        151,
+        125,
+        164,
+        32,
+        118,
+        158
+      ],
+      "accounts": [
+        {
+          "name": "seller",
+          "signer": true
+        },
+        {
+          "name": "product",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  114,
+                  111,
+                  100,
+                  117,
+                  99,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "seller"
+              },
+              {
+                "kind": "account",
+                "path": "product.product_name",
+                "account": "product"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "payoutSplits",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "payoutSplit"
+              }
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "setPointsValue",
+      "discriminator": [
+        117,
+        45,
+        112,
+        179,
+        79,
+        63,
+        88,
+        226
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "loyalty",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "pointsPerUnit",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "setPriceRules",
+      "discriminator": [
+        50,
+        110,
+        6,
+        9,
+        92,
+        166,
+        49,
+        141
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "maxPriceAge",
+          "type": "u64"
+        },
+        {
+          "name": "maxConfidenceBps",
+          "type": "u16"
+        }
+      ]
+    },
+    {
+      "name": "setSellerTier",
+      "discriminator": [
+        159,
+        20,
+        116,
+        223,
+        209,
+        25,
+        217,
+        15
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "sellerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  101,
+                  114,
+                  95,
+                  112,
+                  114,
+                  111,
+                  102,
+                  105,
+                  108,
+                  101
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "seller"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "seller",
+          "type": "pubkey"
+        },
+        {
+          "name": "tier",
+          "type": {
+            "defined": {
+              "name": "sellerTier"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "setShippingWindow",
+      "discriminator": [
+        16,
+        216,
+        99,
+        140,
+        224,
+        122,
+        183,
+        20
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "shippingWindow",
+          "type": "i64"
+        }
+      ]
+    },
+    {
+      "name": "setTierFee",
+      "discriminator": [
+        19,
+        53,
+        241,
+        129,
+        48,
+        191,
+        72,
+        211
+      ],
+      "accounts": [
+        {
+          "name": "admin",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "tier",
+          "type": {
+            "defined": {
+              "name": "sellerTier"
+            }
+          }
+        },
+        {
+          "name": "feeBps",
+          "type": {
+            "option": "u16"
+          }
+        }
+      ]
+    },
+    {
+      "name": "transferGiftCard",
+      "discriminator": [
+        86,
+        120,
+        87,
+        166,
+        215,
+        15,
+        212,
+        188
+      ],
+      "accounts": [
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "giftCard",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  103,
+                  105,
+                  102,
+                  116,
+                  95,
+                  99,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "gift_card.code_hash",
+                "account": "giftCard"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "newHolder",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "updateOrderTracking",
+      "discriminator": [
+        52,
+        84,
+        42,
+        155,
+        181,
+        250,
+        252,
+        32
+      ],
+      "accounts": [
+        {
+          "name": "seller",
+          "signer": true
+        },
+        {
+          "name": "payment"
+        },
+        {
+          "name": "order",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "paymentPlan",
+          "docs": [
+            "Required once the payment is on an instalment plan."
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116,
+                  95,
+                  112,
+                  108,
+                  97,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "orderTracking",
+          "type": {
+            "defined": {
+              "name": "orderTracking"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateProductPricing",
+      "discriminator": [
+        42,
+        119,
+        233,
+        161,
+        98,
+        117,
+        129,
+        163
+      ],
+      "accounts": [
+        {
+          "name": "seller",
+          "signer": true
+        },
+        {
+          "name": "product",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  114,
+                  111,
+                  100,
+                  117,
+                  99,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "seller"
+              },
+              {
+                "kind": "account",
+                "path": "product.product_name",
+                "account": "product"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": "u32"
+        },
+        {
+          "name": "priceCurrency",
+          "type": {
+            "defined": {
+              "name": "priceCurrency"
+            }
+          }
+        },
+        {
+          "name": "discountBps",
+          "type": "u16"
+        },
+        {
+          "name": "orderFee",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "updateSubscriptionStatus",
+      "discriminator": [
+        231,
+        3,
+        4,
+        36,
+        207,
+        182,
+        194,
+        60
+      ],
+      "accounts": [
+        {
+          "name": "buyer",
+          "signer": true
+        },
+        {
+          "name": "subscription",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  117,
+                  98,
+                  115,
+                  99,
+                  114,
+                  105,
+                  112,
+                  116,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "subscription.product",
+                "account": "subscription"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "subscriptionStatus",
+          "type": {
+            "defined": {
+              "name": "subscriptionStatus"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "voteDispute",
+      "discriminator": [
+        23,
+        190,
+        211,
+        170,
+        65,
+        223,
+        4,
+        243
+      ],
+      "accounts": [
+        {
+          "name": "arbiter",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "dispute",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  100,
+                  105,
+                  115,
+                  112,
+                  117,
+                  116,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "order",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "sellerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  101,
+                  114,
+                  95,
+                  112,
+                  114,
+                  111,
+                  102,
+                  105,
+                  108,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow.seller_pubkey",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyer",
+          "docs": [
+            "Receives the vault's rent."
+          ],
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasuryAta",
+          "writable": true
+        },
+        {
+          "name": "keeperVault",
+          "docs": [
+            "Required once the marketplace diverts part of its fee to keepers."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  107,
+                  101,
+                  101,
+                  112,
+                  101,
+                  114,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint",
+          "writable": true
+        },
+        {
+          "name": "receipt",
+          "docs": [
+            "The buyer's receipt, required when the ruling releases anything to",
+            "the seller."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receiptMint",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerReceiptAta",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "receiptMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "tokenProgram"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "ruling",
+          "type": {
+            "defined": {
+              "name": "disputeRuling"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "withdrawEscrow",
+      "discriminator": [
+        81,
+        84,
+        226,
+        128,
+        245,
+        47,
+        96,
+        104
+      ],
+      "accounts": [
+        {
+          "name": "authority",
+          "docs": [
+            "The buyer confirming receipt, or anyone else (the seller, or a",
+            "keeper through `crank`) once the inspection window has passed."
+          ],
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "owner",
+          "docs": [
+            "The buyer; receives the vault's rent and the receipt token."
+          ],
+          "writable": true
+        },
+        {
+          "name": "payment",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  97,
+                  121,
+                  109,
+                  101,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              },
+              {
+                "kind": "account",
+                "path": "payment.nonce",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "purchaseList",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101,
+                  95,
+                  108,
+                  105,
+                  115,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment.owner",
+                "account": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "marketplace",
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "product"
+        },
+        {
+          "name": "sellerProfile",
+          "docs": [
+            "required so a seller can't leave it out to skip a tier fee; may not",
+            "exist."
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  108,
+                  108,
+                  101,
+                  114,
+                  95,
+                  112,
+                  114,
+                  111,
+                  102,
+                  105,
+                  108,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow.seller_pubkey",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "userAta",
+          "writable": true
+        },
+        {
+          "name": "escrowAta",
+          "writable": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasuryAta",
+          "writable": true
+        },
+        {
+          "name": "keeperVault",
+          "docs": [
+            "Required once the marketplace diverts part of its fee to keepers."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  107,
+                  101,
+                  101,
+                  112,
+                  101,
+                  114,
+                  95,
+                  118,
+                  97,
+                  117,
+                  108,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint",
+          "writable": true
+        },
+        {
+          "name": "acceptedMint",
+          "docs": [
+            "mints earn loyalty points. May not exist."
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  97,
+                  99,
+                  99,
+                  101,
+                  112,
+                  116,
+                  101,
+                  100,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "loyalty",
+          "docs": [
+            "releases can't leave it out to skip the buyer's points; may not",
+            "exist."
+          ],
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "pointsMint",
+          "docs": [
+            "Required when this release earns points."
+          ],
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  111,
+                  121,
+                  97,
+                  108,
+                  116,
+                  121,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerPoints",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "pointsTokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "pointsMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "pointsTokenProgram"
+        },
+        {
+          "name": "order",
+          "docs": [
+            "Its ID is written to the receipt when the order has been placed.",
+            "Required when the seller releases after delivery."
+          ],
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  111,
+                  114,
+                  100,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receipt",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receiptMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "payment"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerReceiptAta",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "owner"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "receiptMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "tokenProgram"
+        }
+      ],
+      "args": [
+        {
+          "name": "productId",
+          "type": "u32"
+        }
+      ]
+    }
+  ],
+  "accounts": [
+    {
+      "name": "acceptedMint",
+      "discriminator": [
+        130,
+        131,
+        40,
+        22,
+        135,
+        162,
+        252,
+        109
+      ]
+    },
+    {
+      "name": "cart",
+      "discriminator": [
+        110,
+        9,
+        100,
+        44,
+        36,
+        143,
+        131,
+        88
+      ]
+    },
+    {
+      "name": "cartList",
+      "discriminator": [
+        25,
+        206,
+        228,
+        30,
+        190,
+        207,
+        217,
+        135
+      ]
+    },
+    {
+      "name": "coupon",
+      "discriminator": [
+        24,
+        230,
+        224,
+        210,
+        200,
+        206,
+        79,
+        57
+      ]
+    },
+    {
+      "name": "couponRedemption",
+      "discriminator": [
+        202,
+        2,
+        229,
+        196,
+        155,
+        205,
+        249,
+        115
+      ]
+    },
+    {
+      "name": "dispute",
+      "discriminator": [
+        36,
+        49,
+        241,
+        67,
+        40,
+        36,
+        241,
+        74
+      ]
+    },
+    {
+      "name": "escrow",
+      "discriminator": [
+        31,
+        213,
+        123,
+        187,
+        186,
+        22,
+        218,
+        155
+      ]
+    },
+    {
+      "name": "giftCard",
+      "discriminator": [
+        54,
+        20,
+        8,
+        38,
+        75,
+        170,
+        131,
+        139
+      ]
+    },
+    {
+      "name": "loyaltyProgram",
+      "discriminator": [
+        170,
+        241,
+        228,
+        176,
+        113,
+        205,
+        130,
+        222
+      ]
+    },
+    {
+      "name": "marketplace",
+      "discriminator": [
+        70,
+        222,
+        41,
+        62,
+        78,
+        3,
+        32,
+        174
+      ]
+    },
+    {
+      "name": "mockPriceFeed",
+      "discriminator": [
+        73,
+        0,
+        218,
+        41,
+        7,
+        202,
+        200,
+        152
+      ]
+    },
+    {
+      "name": "order",
+      "discriminator": [
+        134,
+        173,
+        223,
+        185,
+        77,
+        86,
+        28,
+        51
+      ]
+    },
+    {
+      "name": "payment",
+      "discriminator": [
+        227,
+        231,
+        51,
+        26,
+        244,
+        88,
+        4,
+        148
+      ]
+    },
+    {
+      "name": "paymentPlan",
+      "discriminator": [
+        128,
+        22,
+        190,
+        86,
+        141,
+        153,
+        33,
+        233
+      ]
+    },
+    {
+      "name": "product",
+      "discriminator": [
+        102,
+        76,
+        55,
+        251,
+        38,
+        73,
+        224,
+        229
+      ]
+    },
+    {
+      "name": "productsList",
+      "discriminator": [
+        149,
+        195,
+        105,
+        191,
+        84,
+        99,
+        84,
+        70
+      ]
+    },
+    {
+      "name": "purchaseArchive",
+      "discriminator": [
+        111,
+        24,
+        172,
+        113,
+        172,
+        59,
+        168,
+        15
+      ]
+    },
+    {
+      "name": "purchaseList",
+      "discriminator": [
+        128,
+        166,
+        181,
+        215,
+        94,
+        33,
+        122,
+        12
+      ]
+    },
+    {
+      "name": "receipt",
+      "discriminator": [
+        39,
+        154,
+        73,
+        106,
+        80,
+        102,
+        145,
+        153
+      ]
+    },
+    {
+      "name": "relayNonce",
+      "discriminator": [
+        8,
+        4,
+        238,
+        138,
+        204,
+        40,
+        192,
+        8
+      ]
+    },
+    {
+      "name": "sellerProfile",
+      "discriminator": [
+        96,
+        227,
+        91,
+        129,
+        3,
+        158,
+        255,
+        21
+      ]
+    },
+    {
+      "name": "subscription",
+      "discriminator": [
+        64,
+        7,
+        26,
+        135,
+        102,
+        132,
+        98,
+        33
+      ]
+    }
+  ],
+  "events": [
+    {
+      "name": "arbiterPanelUpdated",
+      "discriminator": [
+        246,
+        31,
+        33,
+        124,
+        251,
+        85,
+        141,
+        138
+      ]
+    },
+    {
+      "name": "cartCreated",
+      "discriminator": [
+        125,
+        96,
+        231,
+        239,
+        17,
+        235,
+        104,
+        84
+      ]
+    },
+    {
+      "name": "couponCreated",
+      "discriminator": [
+        11,
+        158,
+        13,
+        126,
+        64,
+        79,
+        194,
+        48
+      ]
+    },
+    {
+      "name": "couponRedeemed",
+      "discriminator": [
+        123,
+        241,
+        185,
+        217,
+        117,
+        208,
+        200,
+        89
+      ]
+    },
+    {
+      "name": "crankRun",
+      "discriminator": [
+        195,
+        52,
+        251,
+        132,
+        80,
+        37,
+        198,
+        147
+      ]
+    },
+    {
+      "name": "disputeEvidenceAdded",
+      "discriminator": [
+        178,
+        205,
+        80,
+        45,
+        79,
+        231,
+        182,
+        72
+      ]
+    },
+    {
+      "name": "disputeOpened",
+      "discriminator": [
+        239,
+        222,
+        102,
+        235,
+        193,
+        85,
+        1,
+        214
+      ]
+    },
+    {
+      "name": "disputeResolved",
+      "discriminator": [
+        121,
+        64,
+        249,
+        153,
+        139,
+        128,
+        236,
+        187
+      ]
+    },
+    {
+      "name": "disputeVoteCast",
+      "discriminator": [
+        193,
+        34,
+        94,
+        69,
+        4,
+        179,
+        143,
+        87
+      ]
+    },
+    {
+      "name": "escrowExpired",
+      "discriminator": [
+        189,
+        22,
+        170,
+        250,
+        75,
+        218,
+        58,
+        112
+      ]
+    },
+    {
+      "name": "escrowSettled",
+      "discriminator": [
+        97,
+        27,
+        150,
+        55,
+        203,
+        179,
+        173,
+        23
+      ]
+    },
+    {
+      "name": "escrowStateChanged",
+      "discriminator": [
+        117,
+        80,
+        243,
+        27,
+        8,
+        62,
+        222,
+        99
+      ]
+    },
+    {
+      "name": "giftCardDrawn",
+      "discriminator": [
+        248,
+        88,
+        77,
+        21,
+        244,
+        16,
+        111,
+        250
+      ]
+    },
+    {
+      "name": "giftCardIssued",
+      "discriminator": [
+        80,
+        229,
+        127,
+        104,
+        57,
+        252,
+        114,
+        141
+      ]
+    },
+    {
+      "name": "giftCardTransferred",
+      "discriminator": [
+        107,
+        130,
+        225,
+        213,
+        155,
+        143,
+        29,
+        14
+      ]
+    },
+    {
+      "name": "installmentPaid",
+      "discriminator": [
+        247,
+        32,
+        44,
+        43,
+        84,
+        76,
+        215,
+        84
+      ]
+    },
+    {
+      "name": "keeperRewarded",
+      "discriminator": [
+        90,
+        33,
+        161,
+        53,
+        189,
+        69,
+        8,
+        10
+      ]
+    },
+    {
+      "name": "milestoneReleased",
+      "discriminator": [
+        49,
+        225,
+        91,
+        223,
+        34,
+        165,
+        109,
+        181
+      ]
+    },
+    {
+      "name": "orderTrackingUpdated",
+      "discriminator": [
+        28,
+        12,
+        16,
+        151,
+        21,
+        199,
+        38,
+        2
+      ]
+    },
+    {
+      "name": "paymentCancelled",
+      "discriminator": [
+        137,
+        140,
+        226,
+        59,
+        55,
+        152,
+        253,
+        179
+      ]
+    },
+    {
+      "name": "paymentIntentRelayed",
+      "discriminator": [
+        157,
+        213,
+        170,
+        238,
+        122,
+        45,
+        0,
+        132
+      ]
+    },
+    {
+      "name": "paymentMintRegistered",
+      "discriminator": [
+        139,
+        162,
+        8,
+        119,
+        206,
+        27,
+        121,
+        142
+      ]
+    },
+    {
+      "name": "paymentPlanDefaulted",
+      "discriminator": [
+        11,
+        41,
+        184,
+        242,
+        197,
+        146,
+        18,
+        246
+      ]
+    },
+    {
+      "name": "payoutSent",
+      "discriminator": [
+        118,
+        83,
+        220,
+        84,
+        61,
+        77,
+        124,
+        10
+      ]
+    },
+    {
+      "name": "payoutSplitsUpdated",
+      "discriminator": [
+        156,
+        13,
+        66,
+        134,
+        161,
+        171,
+        65,
+        216
+      ]
+    },
+    {
+      "name": "pointsMinted",
+      "discriminator": [
+        194,
+        74,
+        48,
+        58,
+        130,
+        225,
+        18,
+        43
+      ]
+    },
+    {
+      "name": "pointsRedeemed",
+      "discriminator": [
+        57,
+        161,
+        97,
+        67,
+        102,
+        120,
+        85,
+        147
+      ]
+    },
+    {
+      "name": "productCreated",
+      "discriminator": [
+        41,
+        64,
+        29,
+        113,
+        18,
+        124,
+        58,
+        82
+      ]
+    },
+    {
+      "name": "productPricingUpdated",
+      "discriminator": [
+        152,
+        38,
+        37,
+        192,
+        47,
+        159,
+        2,
+        249
+      ]
+    },
+    {
+      "name": "purchaseClosed",
+      "discriminator": [
+        205,
+        98,
+        1,
+        100,
+        172,
+        74,
+        79,
+        183
+      ]
+    },
+    {
+      "name": "receiptMinted",
+      "discriminator": [
+        100,
+        166,
+        3,
+        33,
+        2,
+        189,
+        140,
+        144
+      ]
+    },
+    {
+      "name": "refunded",
+      "discriminator": [
+        35,
+        103,
+        149,
+        246,
+        196,
+        123,
+        221,
+        99
+      ]
+    },
+    {
+      "name": "subscriptionBilled",
+      "discriminator": [
+        233,
+        232,
+        247,
+        35,
+        182,
+        41,
+        233,
+        106
+      ]
+    },
+    {
+      "name": "subscriptionBillingFailed",
+      "discriminator": [
+        229,
+        117,
+        119,
+        131,
+        231,
+        52,
+        198,
+        110
+      ]
+    },
+    {
+      "name": "subscriptionStatusChanged",
+      "discriminator": [
+        155,
+        214,
+        195,
+        61,
+        65,
+        15,
+        210,
+        54
+      ]
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "invalidPayment",
+      "msg": "Invalid Payment: Payment details are invalid.."
+    },
+    {
+      "code": 6001,
+      "name": "insufficientFunds",
+      "msg": "InsufficientFunds: Not enough funds to complete the transaction."
+    },
+    {
+      "code": 6002,
+      "name": "accountNotInitialized",
+      "msg": "AccountNotInitialized: The account has not been initialized."
+    },
+    {
+      "code": 6003,
+      "name": "accountAlreadyInitialized",
+      "msg": "AccountAlreadyInitialized: The account is already initialized."
+    },
+    {
+      "code": 6004,
+      "name": "fundsNotFound",
+      "msg": "FundsNotFound: The specified DAO does not exist."
+    },
+    {
+      "code": 6005,
+      "name": "escrowError",
+      "msg": "Invalid Payment: Payment details are invalid.."
+    },
+    {
+      "code": 6006,
+      "name": "daoNotActive",
+      "msg": "DAONotActive: The DAO is not active and cannot accept proposals or votes."
+    },
+    {
+      "code": 6007,
+      "name": "proposalNotFound",
+      "msg": "ProposalNotFound: The specified proposal does not exist."
+    },
+    {
+      "code": 6008,
+      "name": "proposalNotActive",
+      "msg": "ProposalNotActive: The proposal is not active and cannot be voted on."
+    },
+    {
+      "code": 6009,
+      "name": "proposalAlreadyExecuted",
+      "msg": "ProposalAlreadyExecuted: The proposal has already been executed."
+    },
+    {
+      "code": 6010,
+      "name": "proposalExpired",
+      "msg": "ProposalExpired: The proposal has expired and can no longer be voted on."
+    },
+    {
+      "code": 6011,
+      "name": "proposalVotingThresholdNotMet",
+      "msg": "ProposalVotingThresholdNotMet: The proposal did not meet the required voting threshold."
+    },
+    {
+      "code": 6012,
+      "name": "proposalAlreadyCanceled",
+      "msg": "ProposalAlreadyCanceled: The proposal has already been canceled."
+    },
+    {
+      "code": 6013,
+      "name": "proposalNotCancelable",
+      "msg": "ProposalNotCancelable: The proposal cannot be canceled in its current state."
+    },
+    {
+      "code": 6014,
+      "name": "memberNotFound",
+      "msg": "MemberNotFound: The specified member does not exist in the DAO."
+    },
+    {
+      "code": 6015,
+      "name": "memberAlreadyExists",
+      "msg": "MemberAlreadyExists: The user is already a member of the DAO."
+    },
+    {
+      "code": 6016,
+      "name": "insufficientStake",
+      "msg": "InsufficientStake: The user has not staked enough tokens to perform this action."
+    },
+    {
+      "code": 6017,
+      "name": "stakeLocked",
+      "msg": "StakeLocked: The staked tokens are locked and cannot be withdrawn yet."
+    },
+    {
+      "code": 6018,
+      "name": "treasuryWithdrawalFailed",
+      "msg": "TreasuryWithdrawalFailed: The treasury withdrawal failed due to insufficient funds or invalid parameters."
+    },
+    {
+      "code": 6019,
+      "name": "treasuryDepositFailed",
+      "msg": "TreasuryDepositFailed: The treasury deposit failed due to invalid parameters."
+    },
+    {
+      "code": 6020,
+      "name": "alreadyVoted",
+      "msg": "AlreadyVoted: The user has already voted on this proposal."
+    },
+    {
+      "code": 6021,
+      "name": "votingNotAllowed",
+      "msg": "VotingNotAllowed: The user is not allowed to vote on this proposal."
+    },
+    {
+      "code": 6022,
+      "name": "memberNotActive",
+      "msg": "MemberNotActive: The user is not active."
+    },
+    {
+      "code": 6023,
+      "name": "cannotDelegateToSelf",
+      "msg": "Cannot delegate vote to self."
+    },
+    {
+      "code": 6024,
+      "name": "alreadyDelegated",
+      "msg": "Vote has already been delegated."
+    },
+    {
+      "code": 6025,
+      "name": "invalidOrganization",
+      "msg": "Member does not belong to this organization."
+    },
+    {
+      "code": 6026,
+      "name": "purchaseListFull",
+      "msg": "PurchaseListFull: Too many open purchases, settle one before starting another."
+    },
+    {
+      "code": 6027,
+      "name": "invalidQuantity",
+      "msg": "InvalidQuantity: Quantity must be greater than zero."
+    },
+    {
+      "code": 6028,
+      "name": "insufficientStock",
+      "msg": "InsufficientStock: The product does not have enough stock for this quantity."
+    },
+    {
+      "code": 6029,
+      "name": "cartProductMismatch",
+      "msg": "CartProductMismatch: The cart entry does not belong to this product."
+    },
+    {
+      "code": 6030,
+      "name": "sellerMismatch",
+      "msg": "SellerMismatch: The seller does not match the product being paid for."
+    },
+    {
+      "code": 6031,
+      "name": "productMintMismatch",
+      "msg": "ProductMintMismatch: The product is priced in a different token."
+    },
+    {
+      "code": 6032,
+      "name": "buyerMismatch",
+      "msg": "BuyerMismatch: The buyer does not match the owner of the payment."
+    },
+    {
+      "code": 6033,
+      "name": "depositAmountMismatch",
+      "msg": "DepositAmountMismatch: The deposit does not match the amount due."
+    },
+    {
+      "code": 6034,
+      "name": "invalidBasisPoints",
+      "msg": "InvalidBasisPoints: Basis points must be between 0 and 10,000."
+    },
+    {
+      "code": 6035,
+      "name": "invalidCoupon",
+      "msg": "InvalidCoupon: Coupons need a discount, a future expiry and non-zero redemption caps."
+    },
+    {
+      "code": 6036,
+      "name": "invalidCouponCode",
+      "msg": "InvalidCouponCode: The coupon code does not match the coupon account."
+    },
+    {
+      "code": 6037,
+      "name": "couponExpired",
+      "msg": "CouponExpired: The coupon has expired."
+    },
+    {
+      "code": 6038,
+      "name": "couponRedemptionLimit",
+      "msg": "CouponRedemptionLimit: The coupon has been redeemed the maximum number of times."
+    },
+    {
+      "code": 6039,
+      "name": "couponWalletLimit",
+      "msg": "CouponWalletLimit: This wallet has already redeemed the coupon the maximum number of times."
+    },
+    {
+      "code": 6040,
+      "name": "couponNotApplicable",
+      "msg": "CouponNotApplicable: The coupon does not apply to this product."
+    },
+    {
+      "code": 6041,
+      "name": "invalidLoyaltyConfig",
+      "msg": "InvalidLoyaltyConfig: Points per unit of discount must be greater than zero."
+    },
+    {
+      "code": 6042,
+      "name": "loyaltyAccountsRequired",
+      "msg": "LoyaltyAccountsRequired: Redeeming or earning points needs the loyalty program, points mint and the buyer's points account."
+    },
+    {
+      "code": 6043,
+      "name": "insufficientPoints",
+      "msg": "InsufficientPoints: Not enough loyalty points for a discount."
+    },
+    {
+      "code": 6044,
+      "name": "pointsMintMismatch",
+      "msg": "PointsMintMismatch: The points mint is not the loyalty programme's."
+    },
+    {
+      "code": 6045,
+      "name": "invalidGiftCard",
+      "msg": "InvalidGiftCard: Gift cards must be funded with a non-zero amount."
+    },
+    {
+      "code": 6046,
+      "name": "invalidGiftCardSecret",
+      "msg": "InvalidGiftCardSecret: The claim key does not match the gift card."
+    },
+    {
+      "code": 6047,
+      "name": "invalidGiftCardClaim",
+      "msg": "InvalidGiftCardClaim: A gift card needs either a holder or a claim key, not both."
+    },
+    {
+      "code": 6048,
+      "name": "giftCardMintMismatch",
+      "msg": "GiftCardMintMismatch: The gift card holds a different token than the payment."
+    },
+    {
+      "code": 6049,
+      "name": "giftCardAlreadyClaimed",
+      "msg": "GiftCardAlreadyClaimed: The gift card already has a holder."
+    },
+    {
+      "code": 6050,
+      "name": "giftCardNotHolder",
+      "msg": "GiftCardNotHolder: Only the gift card holder can use or transfer it."
+    },
+    {
+      "code": 6051,
+      "name": "insufficientGiftCardBalance",
+      "msg": "InsufficientGiftCardBalance: The gift card balance does not cover the requested amount."
+    },
+    {
+      "code": 6052,
+      "name": "giftCardAccountsRequired",
+      "msg": "GiftCardAccountsRequired: Drawing from a gift card needs the card and its vault."
+    },
+    {
+      "code": 6053,
+      "name": "invalidIntentSignature",
+      "msg": "InvalidIntentSignature: The payment intent is not signed by the buyer through the ed25519 program."
+    },
+    {
+      "code": 6054,
+      "name": "intentExpired",
+      "msg": "IntentExpired: The payment intent has expired."
+    },
+    {
+      "code": 6055,
+      "name": "intentNonceMismatch",
+      "msg": "IntentNonceMismatch: The payment intent nonce is not the buyer's next nonce."
+    },
+    {
+      "code": 6056,
+      "name": "intentAmountExceeded",
+      "msg": "IntentAmountExceeded: The amount pulled from the buyer, transfer fees included, is more than the intent allows."
+    },
+    {
+      "code": 6057,
+      "name": "unsupportedIntent",
+      "msg": "UnsupportedIntent: Only token-priced products can be bought through a relayer."
+    },
+    {
+      "code": 6058,
+      "name": "invalidPayoutSplits",
+      "msg": "InvalidPayoutSplits: Payout splits need up to 5 distinct recipients whose shares add up to 10,000 bps."
+    },
+    {
+      "code": 6059,
+      "name": "missingPayoutAccounts",
+      "msg": "MissingPayoutAccounts: Pass one token account per payout recipient, in split order."
+    },
+    {
+      "code": 6060,
+      "name": "payoutRecipientMismatch",
+      "msg": "PayoutRecipientMismatch: A payout token account is not writable or not owned by its recipient."
+    },
+    {
+      "code": 6061,
+      "name": "unauthorized",
+      "msg": "Unauthorized: Only the marketplace admin can perform this action."
+    },
+    {
+      "code": 6062,
+      "name": "invalidTreasuryAccount",
+      "msg": "InvalidTreasuryAccount: The token account is not owned by the marketplace treasury."
+    },
+    {
+      "code": 6063,
+      "name": "priceFeedRequired",
+      "msg": "PriceFeedRequired: USD-priced products need the marketplace, accepted mint and price feed accounts."
+    },
+    {
+      "code": 6064,
+      "name": "untrustedPriceFeed",
+      "msg": "UntrustedPriceFeed: The price feed is not the one registered for this mint."
+    },
+    {
+      "code": 6065,
+      "name": "invalidPriceFeed",
+      "msg": "InvalidPriceFeed: The price feed account could not be read."
+    },
+    {
+      "code": 6066,
+      "name": "stalePrice",
+      "msg": "StalePrice: The price feed has not been updated recently enough."
+    },
+    {
+      "code": 6067,
+      "name": "priceConfidenceTooWide",
+      "msg": "PriceConfidenceTooWide: The price feed confidence interval is too wide."
+    },
+    {
+      "code": 6068,
+      "name": "mockOracleDisabled",
+      "msg": "MockOracleDisabled: Mock price feeds are only available in localnet builds."
+    },
+    {
+      "code": 6069,
+      "name": "unauthorizedRefund",
+      "msg": "UnauthorizedRefund: Only the seller can refund."
+    },
+    {
+      "code": 6070,
+      "name": "refundExceedsEscrow",
+      "msg": "RefundExceedsEscrow: The refund is larger than the amount left in escrow."
+    },
+    {
+      "code": 6071,
+      "name": "invalidPaymentPlan",
+      "msg": "InvalidPaymentPlan: Instalments need 2 to 12 increasing due dates, none in the past."
+    },
+    {
+      "code": 6072,
+      "name": "paymentPlanActive",
+      "msg": "PaymentPlanActive: This payment is paid through its instalment plan."
+    },
+    {
+      "code": 6073,
+      "name": "paymentPlanNotActive",
+      "msg": "PaymentPlanNotActive: The payment plan is already completed or defaulted."
+    },
+    {
+      "code": 6074,
+      "name": "paymentPlanNotInDefault",
+      "msg": "PaymentPlanNotInDefault: No instalment is overdue past the grace period."
+    },
+    {
+      "code": 6075,
+      "name": "installmentThresholdNotMet",
+      "msg": "InstallmentThresholdNotMet: Not enough of the instalment plan has been paid to ship."
+    },
+    {
+      "code": 6076,
+      "name": "invalidSubscription",
+      "msg": "InvalidSubscription: Subscriptions need a token-priced product and an interval of at least a day."
+    },
+    {
+      "code": 6077,
+      "name": "subscriptionNotActive",
+      "msg": "SubscriptionNotActive: The subscription is paused or cancelled."
+    },
+    {
+      "code": 6078,
+      "name": "subscriptionNotDue",
+      "msg": "SubscriptionNotDue: The next billing period has not started yet."
+    },
+    {
+      "code": 6079,
+      "name": "subscriptionCancelled",
+      "msg": "SubscriptionCancelled: A cancelled subscription cannot be changed."
+    },
+    {
+      "code": 6080,
+      "name": "unauthorizedRelease",
+      "msg": "UnauthorizedRelease: Only the buyer or, after delivery, the seller can release the escrow."
+    },
+    {
+      "code": 6081,
+      "name": "orderNotDelivered",
+      "msg": "OrderNotDelivered: The order has not been marked as delivered."
+    },
+    {
+      "code": 6082,
+      "name": "inspectionPeriodActive",
+      "msg": "InspectionPeriodActive: The buyer's inspection window has not passed yet."
+    },
+    {
+      "code": 6083,
+      "name": "invalidInspectionPeriod",
+      "msg": "InvalidInspectionPeriod: The inspection period cannot be negative."
+    },
+    {
+      "code": 6084,
+      "name": "invalidShippingWindow",
+      "msg": "InvalidShippingWindow: The shipping window must be positive."
+    },
+    {
+      "code": 6085,
+      "name": "shippingDeadlineNotReached",
+      "msg": "ShippingDeadlineNotReached: The seller still has time to ship."
+    },
+    {
+      "code": 6086,
+      "name": "orderAlreadyShipped",
+      "msg": "OrderAlreadyShipped: The order has already shipped."
+    },
+    {
+      "code": 6087,
+      "name": "invalidOrderAccount",
+      "msg": "InvalidOrderAccount: The order account is not owned by this program."
+    },
+    {
+      "code": 6088,
+      "name": "escrowNotExpirable",
+      "msg": "EscrowNotExpirable: Only funded, undisputed escrows can expire."
+    },
+    {
+      "code": 6089,
+      "name": "invalidMilestoneSchedule",
+      "msg": "InvalidMilestoneSchedule: Milestones must advance, share 10,000 bps and end on delivery."
+    },
+    {
+      "code": 6090,
+      "name": "noMilestoneDue",
+      "msg": "NoMilestoneDue: No milestone has been reached since the last release."
+    },
+    {
+      "code": 6091,
+      "name": "purchaseNotClosable",
+      "msg": "PurchaseNotClosable: The payment and escrow have not reached a final state."
+    },
+    {
+      "code": 6092,
+      "name": "paymentNotCancellable",
+      "msg": "PaymentNotCancellable: Only a pending payment with no plan and an unfunded escrow, if any, can be cancelled."
+    },
+    {
+      "code": 6093,
+      "name": "escrowAccountsRequired",
+      "msg": "EscrowAccountsRequired: Cancelling a payment with an escrow needs its vault, mint and token program."
+    },
+    {
+      "code": 6094,
+      "name": "keeperVaultRequired",
+      "msg": "KeeperVaultRequired: Pass the keeper vault; part of the fee funds keepers."
+    },
+    {
+      "code": 6095,
+      "name": "unknownCrankTarget",
+      "msg": "UnknownCrankTarget: The crank has no task for this account."
+    },
+    {
+      "code": 6096,
+      "name": "tooManyCrankTasks",
+      "msg": "TooManyCrankTasks: A crank handles at most 8 tasks."
+    },
+    {
+      "code": 6097,
+      "name": "crankTaskMismatch",
+      "msg": "CrankTaskMismatch: A task's accounts don't match its target or keeper."
+    },
+    {
+      "code": 6098,
+      "name": "invalidKeeperVault",
+      "msg": "InvalidKeeperVault: The keeper vault isn't the pool for the task's mint."
+    },
+    {
+      "code": 6099,
+      "name": "escrowDisputed",
+      "msg": "EscrowDisputed: The escrow is frozen by an open dispute."
+    },
+    {
+      "code": 6100,
+      "name": "disputeNotAllowed",
+      "msg": "DisputeNotAllowed: Disputes can only be opened by the buyer while funds are escrowed."
+    },
+    {
+      "code": 6101,
+      "name": "disputeNotOpen",
+      "msg": "DisputeNotOpen: The dispute has already been resolved."
+    },
+    {
+      "code": 6102,
+      "name": "unauthorizedEvidence",
+      "msg": "UnauthorizedEvidence: Only the buyer and seller can submit evidence."
+    },
+    {
+      "code": 6103,
+      "name": "evidenceLimitReached",
+      "msg": "EvidenceLimitReached: Too much evidence, or the URI is too long."
+    },
+    {
+      "code": 6104,
+      "name": "invalidArbiterPanel",
+      "msg": "InvalidArbiterPanel: The panel needs distinct arbiters, at most 7, and a threshold between 1 and its size."
+    },
+    {
+      "code": 6105,
+      "name": "panelRulingRequired",
+      "msg": "PanelRulingRequired: This dispute is decided by the arbiter panel."
+    },
+    {
+      "code": 6106,
+      "name": "panelVoteNotAllowed",
+      "msg": "PanelVoteNotAllowed: This dispute is not decided by the arbiter panel."
+    },
+    {
+      "code": 6107,
+      "name": "unauthorizedArbiter",
+      "msg": "UnauthorizedArbiter: Only the marketplace arbiter or panel can rule on this dispute."
+    },
+    {
+      "code": 6108,
+      "name": "receiptAccountsRequired",
+      "msg": "ReceiptAccountsRequired: A ruling that pays the seller mints the buyer's receipt; pass the receipt accounts."
+    },
+    {
+      "code": 6109,
+      "name": "invalidOrderTransition",
+      "msg": "InvalidOrderTransition: Order tracking can only move forward on a placed order."
+    },
+    {
+      "code": 6110,
+      "name": "invalidEscrowVault",
+      "msg": "InvalidEscrowVault: The escrow token account is not owned by the escrow."
+    },
+    {
+      "code": 6111,
+      "name": "invalidBuyerTokenAccount",
+      "msg": "InvalidBuyerTokenAccount: The token account is not owned by the buyer."
+    },
+    {
+      "code": 6112,
+      "name": "invalidSellerTokenAccount",
+      "msg": "InvalidSellerTokenAccount: The token account is not owned by the seller."
+    },
+    {
+      "code": 6113,
+      "name": "invalidUserTokenAccount",
+      "msg": "InvalidUserTokenAccount: The paying token account is not owned by the signer."
+    },
+    {
+      "code": 6114,
+      "name": "userTokenMintMismatch",
+      "msg": "UserTokenMintMismatch: The signer's token account is not on the payment mint."
+    },
+    {
+      "code": 6115,
+      "name": "escrowVaultMintMismatch",
+      "msg": "EscrowVaultMintMismatch: The escrow vault is not on the payment mint."
+    },
+    {
+      "code": 6116,
+      "name": "buyerTokenMintMismatch",
+      "msg": "BuyerTokenMintMismatch: The buyer token account is not on the payment mint."
+    },
+    {
+      "code": 6117,
+      "name": "sellerTokenMintMismatch",
+      "msg": "SellerTokenMintMismatch: The seller token account is not on the payment mint."
+    },
+    {
+      "code": 6118,
+      "name": "treasuryTokenMintMismatch",
+      "msg": "TreasuryTokenMintMismatch: The treasury token account is not on the payment mint."
+    },
+    {
+      "code": 6119,
+      "name": "invalidMint",
+      "msg": "InvalidMint: The token mint does not match the payment mint."
+    },
+    {
+      "code": 6120,
+      "name": "mathOverflow",
+      "msg": "MathOverflow: Arithmetic overflow while computing token amounts."
+    },
+    {
+      "code": 6121,
+      "name": "escrowAlreadyFunded",
+      "msg": "EscrowAlreadyFunded: The escrow has already been funded."
+    },
+    {
+      "code": 6122,
+      "name": "escrowNotFunded",
+      "msg": "EscrowNotFunded: The escrow has not been funded yet."
+    },
+    {
+      "code": 6123,
+      "name": "escrowAlreadySettled",
+      "msg": "EscrowAlreadySettled: The escrow has already been released, refunded or failed."
+    },
+    {
+      "code": 6124,
+      "name": "invalidEscrowTransition",
+      "msg": "InvalidEscrowTransition: The escrow cannot move to that state from its current one."
+    }
+  ],
+  "types": [
+    {
+      "name": "acceptedMint",
+      "docs": [
+        "A mint buyers may pay with, and the price feed quoting it in USD."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "priceFeed",
+            "type": "pubkey"
+          },
+          {
+            "name": "acceptedMintBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "arbiterPanelUpdated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "arbiters",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "threshold",
+            "type": "u8"
+          },
+          {
+            "name": "minAmount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "arbiterVote",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "arbiter",
+            "type": "pubkey"
+          },
+          {
+            "name": "ruling",
+            "type": {
+              "defined": {
+                "name": "disputeRuling"
+              }
+            }
+          },
+          {
+            "name": "votedAt",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "billingFailure",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "delegateNotApproved"
+          },
+          {
+            "name": "allowanceExceeded"
+          },
+          {
+            "name": "insufficientBalance"
+          },
+          {
+            "name": "outOfStock"
+          }
+        ]
+      }
+    },
+    {
+      "name": "cart",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "productId",
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "productName",
+            "type": "string"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "sellerPubkey",
+            "type": "pubkey"
+          },
+          {
+            "name": "productImgurl",
+            "type": "string"
+          },
+          {
+            "name": "stockStatus",
+            "type": {
+              "defined": {
+                "name": "anchor::states::cart::Stock"
+              }
+            }
+          },
+          {
+            "name": "amount",
+            "type": {
+              "vec": "u64"
+            }
+          },
+          {
+            "name": "cartBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "cartCreated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "productName",
+            "type": "string"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "cartList",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "cartList",
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "totalAmount",
+            "type": "u64"
+          },
+          {
+            "name": "cartListBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "category",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "electronics"
+          },
+          {
+            "name": "beautyAndPersonalCare"
+          },
+          {
+            "name": "snacksAndDrinks"
+          },
+          {
+            "name": "householdEssentials"
+          },
+          {
+            "name": "groceryAndKitchen"
+          }
+        ]
+      }
+    },
+    {
+      "name": "categoryFee",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "category",
+            "type": {
+              "defined": {
+                "name": "category"
+              }
+            }
+          },
+          {
+            "name": "feeBps",
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "categoryRate",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "category",
+            "type": {
+              "defined": {
+                "name": "category"
+              }
+            }
+          },
+          {
+            "name": "rateBps",
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "coupon",
+      "docs": [
+        "Promo code, stored under its creator and the hash of the code, so the",
+        "same code can be issued by different creators without anyone claiming",
+        "it first. Coupons created by a seller only apply to that seller's",
+        "products; the marketplace admin's apply to any product in `scope`."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "codeHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "creator",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "discount",
+            "type": {
+              "defined": {
+                "name": "couponDiscount"
+              }
+            }
+          },
+          {
+            "name": "scope",
+            "type": {
+              "defined": {
+                "name": "couponScope"
+              }
+            }
+          },
+          {
+            "name": "expiresAt",
+            "type": "i64"
+          },
+          {
+            "name": "maxRedemptions",
+            "type": "u32"
+          },
+          {
+            "name": "redemptions",
+            "type": "u32"
+          },
+          {
+            "name": "perWalletCap",
+            "type": "u16"
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "couponBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "couponCreated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "coupon",
+            "type": "pubkey"
+          },
+          {
+            "name": "creator",
+            "type": "pubkey"
+          },
+          {
+            "name": "expiresAt",
+            "type": "i64"
+          },
+          {
+            "name": "maxRedemptions",
+            "type": "u32"
+          }
+        ]
+      }
+    },
+    {
+      "name": "couponDiscount",
+      "docs": [
+        "`Fixed` is in the product's pricing unit (token base units or US cents)."
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "percentage",
+            "fields": [
+              {
+                "name": "bps",
+                "type": "u16"
+              }
+            ]
+          },
+          {
+            "name": "fixed",
+            "fields": [
+              {
+                "name": "amount",
+                "type": "u64"
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "couponRedeemed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "coupon",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "discount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "couponRedemption",
+      "docs": [
+        "Redemptions of one coupon by one wallet."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "coupon",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "count",
+            "type": "u16"
+          },
+          {
+            "name": "couponRedemptionBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "couponScope",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "all"
+          },
+          {
+            "name": "product",
+            "fields": [
+              {
+                "name": "product",
+                "type": "pubkey"
+              }
+            ]
+          },
+          {
+            "name": "category",
+            "fields": [
+              {
+                "name": "category",
+                "type": {
+                  "defined": {
+                    "name": "category"
+                  }
+                }
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "crankRun",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "keeper",
+            "type": "pubkey"
+          },
+          {
+            "name": "tasks",
+            "docs": [
+              "Tasks that earned a reward."
+            ],
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "crankTask",
+      "docs": [
+        "What `crank` does with a task's target."
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "expireEscrow"
+          },
+          {
+            "name": "billSubscription"
+          },
+          {
+            "name": "releaseEscrow"
+          }
+        ]
+      }
+    },
+    {
+      "name": "dispute",
+      "docs": [
+        "Dispute over an escrow. While it is open the escrow cannot be released",
+        "or refunded; the marketplace arbiter settles it with a ruling, or the",
+        "arbiter panel by vote when the escrow was large enough at opening."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyerEvidence",
+            "type": {
+              "vec": "string"
+            }
+          },
+          {
+            "name": "sellerEvidence",
+            "type": {
+              "vec": "string"
+            }
+          },
+          {
+            "name": "disputeStatus",
+            "type": {
+              "defined": {
+                "name": "disputeStatus"
+              }
+            }
+          },
+          {
+            "name": "ruling",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "disputeRuling"
+                }
+              }
+            }
+          },
+          {
+            "name": "panelRequired",
+            "type": "bool"
+          },
+          {
+            "name": "votes",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "arbiterVote"
+                }
+              }
+            }
+          },
+          {
+            "name": "openedAt",
+            "type": "i64"
+          },
+          {
+            "name": "resolvedAt",
+            "type": "i64"
+          },
+          {
+            "name": "disputeBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "disputeEvidenceAdded",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "dispute",
+            "type": "pubkey"
+          },
+          {
+            "name": "submitter",
+            "type": "pubkey"
+          },
+          {
+            "name": "uri",
+            "type": "string"
+          }
+        ]
+      }
+    },
+    {
+      "name": "disputeOpened",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "dispute",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "disputeResolved",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "dispute",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "ruling",
+            "type": {
+              "defined": {
+                "name": "disputeRuling"
+              }
+            }
+          },
+          {
+            "name": "buyerAmount",
+            "type": "u64"
+          },
+          {
+            "name": "sellerAmount",
+            "type": "u64"
+          },
+          {
+            "name": "arbiter",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "disputeRuling",
+      "docs": [
+        "`Split` sends `buyer_bps` of the escrowed funds back to the buyer and",
+        "the rest to the seller."
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "refundBuyer"
+          },
+          {
+            "name": "releaseToSeller"
+          },
+          {
+            "name": "split",
+            "fields": [
+              {
+                "name": "buyerBps",
+                "type": "u16"
+              }
+            ]
+          }
+        ]
+      }
+    },
+    {
+      "name": "disputeStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "open"
+          },
+          {
+            "name": "resolved"
+          }
+        ]
+      }
+    },
+    {
+      "name": "disputeVoteCast",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "dispute",
+            "type": "pubkey"
+          },
+          {
+            "name": "arbiter",
+            "type": "pubkey"
+          },
+          {
+            "name": "ruling",
+            "type": {
+              "defined": {
+                "name": "disputeRuling"
+              }
+            }
+          },
+          {
+            "name": "matchingVotes",
+            "type": "u8"
+          },
+          {
+            "name": "threshold",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "division",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "mobile"
+          },
+          {
+            "name": "laptop"
+          },
+          {
+            "name": "headphone"
+          },
+          {
+            "name": "smartWatch"
+          },
+          {
+            "name": "computerPeripherals"
+          }
+        ]
+      }
+    },
+    {
+      "name": "escrow",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyerPubkey",
+            "type": "pubkey"
+          },
+          {
+            "name": "sellerPubkey",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "depositedAmount",
+            "type": "u64"
+          },
+          {
+            "name": "timeStamp",
+            "type": "i64"
+          },
+          {
+            "name": "updateTimestamp",
+            "type": "i64"
+          },
+          {
+            "name": "shipBy",
+            "docs": [
+              "After this, an order that hasn't shipped can be expired by anyone.",
+              "Counted from creation, then moved to count from funding once the",
+              "escrow is funded."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "milestones",
+            "docs": [
+              "Empty for a single release; otherwise tranches paid out as the order",
+              "progresses, fixed when the escrow is created."
+            ],
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "milestone"
+                }
+              }
+            }
+          },
+          {
+            "name": "releasedAmount",
+            "docs": [
+              "Paid out to the seller so far through milestones."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "escrowStatus",
+            "type": {
+              "defined": {
+                "name": "escrowStatus"
+              }
+            }
+          },
+          {
+            "name": "escrowBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "escrowExpired",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "caller",
+            "type": "pubkey"
+          },
+          {
+            "name": "refunded",
+            "type": "u64"
+          },
+          {
+            "name": "reward",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "escrowSettled",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "sellerAmount",
+            "type": "u64"
+          },
+          {
+            "name": "feeAmount",
+            "type": "u64"
+          },
+          {
+            "name": "feeBps",
+            "type": "u16"
+          },
+          {
+            "name": "treasury",
+            "type": "pubkey"
+          },
+          {
+            "name": "releasedBy",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "escrowStateChanged",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "from",
+            "type": {
+              "defined": {
+                "name": "escrowStatus"
+              }
+            }
+          },
+          {
+            "name": "to",
+            "type": {
+              "defined": {
+                "name": "escrowStatus"
+              }
+            }
+          },
+          {
+            "name": "at",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "escrowStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "swapPending"
+          },
+          {
+            "name": "fundsReceived"
+          },
+          {
+            "name": "swapSuccess"
+          },
+          {
+            "name": "transferFailed"
+          },
+          {
+            "name": "partiallyRefunded"
+          },
+          {
+            "name": "refunded"
+          },
+          {
+            "name": "disputed"
+          },
+          {
+            "name": "partiallyFunded"
+          }
+        ]
+      }
+    },
+    {
+      "name": "fundingRecord",
+      "docs": [
+        "Where and when a payment was funded. `deposited` is what left the payer's",
+        "accounts, `received` what the vault was credited after transfer fees."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "slot",
+            "type": "u64"
+          },
+          {
+            "name": "fundedAt",
+            "type": "i64"
+          },
+          {
+            "name": "vault",
+            "type": "pubkey"
+          },
+          {
+            "name": "deposited",
+            "type": "u64"
+          },
+          {
+            "name": "received",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "giftCard",
+      "docs": [
+        "Prepaid balance held in a vault owned by this account. A card issued",
+        "without a holder is a bearer card: whoever holds the secret behind",
+        "`code_hash` claims it."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "codeHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "issuer",
+            "type": "pubkey"
+          },
+          {
+            "name": "holder",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "claimKey",
+            "docs": [
+              "Bearer cards only: public key of a keypair the client derives from",
+              "the secret. Claiming takes its signature, so the secret itself never",
+              "appears in a transaction that could be copied from the mempool."
+            ],
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "balance",
+            "type": "u64"
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "giftCardBump",
+            "type": "u8"
+          },
+          {
+            "name": "vaultBump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "giftCardDrawn",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "giftCard",
+            "type": "pubkey"
+          },
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "remaining",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "giftCardIssued",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "giftCard",
+            "type": "pubkey"
+          },
+          {
+            "name": "issuer",
+            "type": "pubkey"
+          },
+          {
+            "name": "holder",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "giftCardTransferred",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "giftCard",
+            "type": "pubkey"
+          },
+          {
+            "name": "from",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "to",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "installment",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "dueAt",
+            "type": "i64"
+          },
+          {
+            "name": "paid",
+            "type": "bool"
+          }
+        ]
+      }
+    },
     {
-      "name": "cartCreated",
-      "discriminator": [
-        125,
-        96,
-        231,
-        239,
-        17,
-        235,
-        104,
-        84
-      ]
+      "name": "installmentPaid",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "index",
+            "type": "u8"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "paidAmount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "name": "productCreated",
-      "discriminator": [
-        41,
-        64,
-        29,
-        113,
-        18,
-        124,
-        58,
-        82
-      ]
-    }
-  ],
-  "errors": [
+      "name": "keeperRewarded",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "keeper",
+            "type": "pubkey"
+          },
+          {
+            "name": "target",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "reward",
+            "type": "u64"
+          }
+        ]
+      }
+    },
     {
-      "code": 6000,
-      "name": "invalidPayment",
-      "msg": "Invalid Payment: Payment details are invalid.."
+      "name": "loyaltyProgram",
+      "docs": [
+        "Repeat-buyer programme. The points mint is a PDA whose mint authority is",
+        "this account, so points can only be minted by a settled escrow release."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "pointsMint",
+            "type": "pubkey"
+          },
+          {
+            "name": "pointsPerUnit",
+            "docs": [
+              "Points burned for one unit of discount, in the product's pricing unit."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "categoryRates",
+            "docs": [
+              "Points minted per release, in basis points of the released amount."
+            ],
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "categoryRate"
+                }
+              }
+            }
+          },
+          {
+            "name": "loyaltyBump",
+            "type": "u8"
+          },
+          {
+            "name": "pointsMintBump",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6001,
-      "name": "insufficientFunds",
-      "msg": "InsufficientFunds: Not enough funds to complete the transaction."
+      "name": "marketplace",
+      "docs": [
+        "Program-wide settings owned by the marketplace admin."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "admin",
+            "type": "pubkey"
+          },
+          {
+            "name": "maxPriceAge",
+            "type": "u64"
+          },
+          {
+            "name": "maxConfidenceBps",
+            "type": "u16"
+          },
+          {
+            "name": "installmentReleaseBps",
+            "type": "u16"
+          },
+          {
+            "name": "installmentGracePeriod",
+            "type": "i64"
+          },
+          {
+            "name": "installmentDefaultFeeBps",
+            "type": "u16"
+          },
+          {
+            "name": "treasury",
+            "type": "pubkey"
+          },
+          {
+            "name": "feeBps",
+            "type": "u16"
+          },
+          {
+            "name": "categoryFees",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "categoryFee"
+                }
+              }
+            }
+          },
+          {
+            "name": "tierFees",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "tierFee"
+                }
+              }
+            }
+          },
+          {
+            "name": "inspectionPeriod",
+            "docs": [
+              "Seconds after delivery before the seller may release funds themselves."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "arbiter",
+            "docs": [
+              "Rules on disputes; the admin until one is appointed."
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "shippingWindow",
+            "docs": [
+              "Seconds a seller has to ship after the escrow is funded."
+            ],
+            "type": "i64"
+          },
+          {
+            "name": "arbiterPanel",
+            "docs": [
+              "Votes on disputes over escrows worth at least `panel_min_amount`;",
+              "empty to leave every dispute to `arbiter`."
+            ],
+            "type": {
+              "vec": "pubkey"
+            }
+          },
+          {
+            "name": "panelThreshold",
+            "type": "u8"
+          },
+          {
+            "name": "panelMinAmount",
+            "type": "u64"
+          },
+          {
+            "name": "keeperFeeBps",
+            "docs": [
+              "Share of every release fee diverted to the keeper vault of its mint."
+            ],
+            "type": "u16"
+          },
+          {
+            "name": "keeperReward",
+            "docs": [
+              "Paid per task `crank` completes, from the keeper vault of the task's",
+              "mint and in its base units, but never more than the marketplace fee",
+              "the task produced."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "marketplaceBump",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6002,
-      "name": "accountNotInitialized",
-      "msg": "AccountNotInitialized: The account has not been initialized."
+      "name": "milestone",
+      "docs": [
+        "A tranche of the escrow, due once order tracking reaches `stage`."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "stage",
+            "type": {
+              "defined": {
+                "name": "orderTracking"
+              }
+            }
+          },
+          {
+            "name": "releaseBps",
+            "type": "u16"
+          },
+          {
+            "name": "releasedAmount",
+            "type": "u64"
+          },
+          {
+            "name": "releasedAt",
+            "type": {
+              "option": "i64"
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6003,
-      "name": "accountAlreadyInitialized",
-      "msg": "AccountAlreadyInitialized: The account is already initialized."
+      "name": "milestoneReleased",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "stage",
+            "type": {
+              "defined": {
+                "name": "orderTracking"
+              }
+            }
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "feeAmount",
+            "type": "u64"
+          },
+          {
+            "name": "remaining",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6004,
-      "name": "fundsNotFound",
-      "msg": "FundsNotFound: The specified DAO does not exist."
+      "name": "milestoneTerm",
+      "docs": [
+        "A tranche as requested at escrow creation."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "stage",
+            "type": {
+              "defined": {
+                "name": "orderTracking"
+              }
+            }
+          },
+          {
+            "name": "releaseBps",
+            "type": "u16"
+          }
+        ]
+      }
     },
     {
-      "code": 6005,
-      "name": "escrowError",
-      "msg": "Invalid Payment: Payment details are invalid.."
+      "name": "mockPriceFeed",
+      "docs": [
+        "Locally writable price feed, only read when the program is built with",
+        "the `localnet` feature."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "price",
+            "type": "i64"
+          },
+          {
+            "name": "conf",
+            "type": "u64"
+          },
+          {
+            "name": "expo",
+            "type": "i32"
+          },
+          {
+            "name": "publishTime",
+            "type": "i64"
+          },
+          {
+            "name": "mockPriceFeedBump",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6006,
-      "name": "daoNotActive",
-      "msg": "DAONotActive: The DAO is not active and cannot accept proposals or votes."
+      "name": "order",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "orderId",
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "paymentId",
+            "type": "string"
+          },
+          {
+            "name": "trackingId",
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "orderStatus",
+            "type": {
+              "defined": {
+                "name": "orderStatus"
+              }
+            }
+          },
+          {
+            "name": "orderTracking",
+            "type": {
+              "defined": {
+                "name": "orderTracking"
+              }
+            }
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "updatedAt",
+            "type": "i64"
+          },
+          {
+            "name": "deliveredAt",
+            "docs": [
+              "Set when tracking reaches `Delivered`; starts the inspection window."
+            ],
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "orderBump",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6007,
-      "name": "proposalNotFound",
-      "msg": "ProposalNotFound: The specified proposal does not exist."
+      "name": "orderStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "pending"
+          },
+          {
+            "name": "placed"
+          },
+          {
+            "name": "failed"
+          },
+          {
+            "name": "returned"
+          }
+        ]
+      }
     },
     {
-      "code": 6008,
-      "name": "proposalNotActive",
-      "msg": "ProposalNotActive: The proposal is not active and cannot be voted on."
+      "name": "orderTracking",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "watingForOrders"
+          },
+          {
+            "name": "booked"
+          },
+          {
+            "name": "inTransit"
+          },
+          {
+            "name": "shipped"
+          },
+          {
+            "name": "outForDelivery"
+          },
+          {
+            "name": "delivered"
+          }
+        ]
+      }
     },
     {
-      "code": 6009,
-      "name": "proposalAlreadyExecuted",
-      "msg": "ProposalAlreadyExecuted: The proposal has already been executed."
+      "name": "orderTrackingUpdated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "order",
+            "type": "pubkey"
+          },
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "orderTracking",
+            "type": {
+              "defined": {
+                "name": "orderTracking"
+              }
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6010,
-      "name": "proposalExpired",
-      "msg": "ProposalExpired: The proposal has expired and can no longer be voted on."
+      "name": "payment",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "paymentId",
+            "type": {
+              "array": [
+                "u8",
+                16
+              ]
+            }
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "nonce",
+            "type": "u64"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "paymentAmount",
+            "type": "u64"
+          },
+          {
+            "name": "pricedAmount",
+            "docs": [
+              "`payment_amount` in the product's pricing unit: token base units, or",
+              "cents for USD-priced products."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "coupon",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "couponDiscount",
+            "type": "u64"
+          },
+          {
+            "name": "pointsRedeemed",
+            "type": "u64"
+          },
+          {
+            "name": "refundedAmount",
+            "type": "u64"
+          },
+          {
+            "name": "marketplaceFee",
+            "type": "u64"
+          },
+          {
+            "name": "productPubkey",
+            "type": "pubkey"
+          },
+          {
+            "name": "paymentMethod",
+            "type": {
+              "defined": {
+                "name": "paymentMethod"
+              }
+            }
+          },
+          {
+            "name": "paymentStatus",
+            "type": {
+              "defined": {
+                "name": "paymentStatus"
+              }
+            }
+          },
+          {
+            "name": "timeStamp",
+            "type": "i64"
+          },
+          {
+            "name": "funding",
+            "docs": [
+              "Set by the program when tokens land in the escrow vault."
+            ],
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "fundingRecord"
+                }
+              }
+            }
+          },
+          {
+            "name": "paymentPlan",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "rentPayer",
+            "docs": [
+              "Paid the rent for this payment, its escrow and its order, and gets",
+              "it back when they are closed."
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "paymentBump",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6011,
-      "name": "proposalVotingThresholdNotMet",
-      "msg": "ProposalVotingThresholdNotMet: The proposal did not meet the required voting threshold."
+      "name": "paymentCancelled",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "rentPayer",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "paymentIntent",
+      "docs": [
+        "What a buyer signs off-chain to let a relayer check out on their behalf.",
+        "The signed message is `signed_message`: a domain tag and the program ID,",
+        "then the Borsh encoding of this struct."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "product",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u64"
+          },
+          {
+            "name": "maxAmount",
+            "docs": [
+              "Most the buyer agrees to have pulled from their account, transfer",
+              "fees included, so a price change can't overcharge them."
+            ],
+            "type": "u64"
+          },
+          {
+            "name": "nonce",
+            "type": "u64"
+          },
+          {
+            "name": "expiresAt",
+            "type": "i64"
+          }
+        ]
+      }
     },
     {
-      "code": 6012,
-      "name": "proposalAlreadyCanceled",
-      "msg": "ProposalAlreadyCanceled: The proposal has already been canceled."
+      "name": "paymentIntentRelayed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "relayer",
+            "type": "pubkey"
+          },
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "nonce",
+            "type": "u64"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6013,
-      "name": "proposalNotCancelable",
-      "msg": "ProposalNotCancelable: The proposal cannot be canceled in its current state."
+      "name": "paymentMethod",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "sol"
+          },
+          {
+            "name": "eth"
+          },
+          {
+            "name": "btc"
+          },
+          {
+            "name": "usdt"
+          },
+          {
+            "name": "usdc"
+          }
+        ]
+      }
     },
     {
-      "code": 6014,
-      "name": "memberNotFound",
-      "msg": "MemberNotFound: The specified member does not exist in the DAO."
+      "name": "paymentMintRegistered",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "priceFeed",
+            "type": "pubkey"
+          }
+        ]
+      }
     },
     {
-      "code": 6015,
-      "name": "memberAlreadyExists",
-      "msg": "MemberAlreadyExists: The user is already a member of the DAO."
+      "name": "paymentPlan",
+      "docs": [
+        "Instalment schedule for a payment. The escrow is topped up one",
+        "instalment at a time and the order may only ship once",
+        "`release_share_bps` of the total has been paid."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "totalAmount",
+            "type": "u64"
+          },
+          {
+            "name": "paidAmount",
+            "type": "u64"
+          },
+          {
+            "name": "installments",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "installment"
+                }
+              }
+            }
+          },
+          {
+            "name": "releaseShareBps",
+            "type": "u16"
+          },
+          {
+            "name": "gracePeriod",
+            "type": "i64"
+          },
+          {
+            "name": "defaultFeeBps",
+            "type": "u16"
+          },
+          {
+            "name": "planStatus",
+            "type": {
+              "defined": {
+                "name": "planStatus"
+              }
+            }
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "paymentPlanBump",
+            "type": "u8"
+          }
+        ]
+      }
     },
     {
-      "code": 6016,
-      "name": "insufficientStake",
-      "msg": "InsufficientStake: The user has not staked enough tokens to perform this action."
+      "name": "paymentPlanDefaulted",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "refunded",
+            "type": "u64"
+          },
+          {
+            "name": "retained",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6017,
-      "name": "stakeLocked",
-      "msg": "StakeLocked: The staked tokens are locked and cannot be withdrawn yet."
+      "name": "paymentStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "success"
+          },
+          {
+            "name": "pending"
+          },
+          {
+            "name": "failed"
+          },
+          {
+            "name": "partiallyRefunded"
+          },
+          {
+            "name": "refunded"
+          }
+        ]
+      }
     },
     {
-      "code": 6018,
-      "name": "treasuryWithdrawalFailed",
-      "msg": "TreasuryWithdrawalFailed: The treasury withdrawal failed due to insufficient funds or invalid parameters."
+      "name": "payoutSent",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "recipient",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6019,
-      "name": "treasuryDepositFailed",
-      "msg": "TreasuryDepositFailed: The treasury deposit failed due to invalid parameters."
+      "name": "payoutSplit",
+      "docs": [
+        "Share of the seller's proceeds paid to `recipient` on release. When a",
+        "product has splits, shares add up to 10,000 bps and any rounding",
+        "remainder goes to the first recipient."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "recipient",
+            "type": "pubkey"
+          },
+          {
+            "name": "shareBps",
+            "type": "u16"
+          }
+        ]
+      }
     },
     {
-      "code": 6020,
-      "name": "alreadyVoted",
-      "msg": "AlreadyVoted: The user has already voted on this proposal."
+      "name": "payoutSplitsUpdated",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "productPubkey",
+            "type": "pubkey"
+          },
+          {
+            "name": "payoutSplits",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "payoutSplit"
+                }
+              }
+            }
+          }
+        ]
+      }
     },
     {
-      "code": 6021,
-      "name": "votingNotAllowed",
-      "msg": "VotingNotAllowed: The user is not allowed to vote on this proposal."
+      "name": "planStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "active"
+          },
+          {
+            "name": "completed"
+          },
+          {
+            "name": "defaulted"
+          }
+        ]
+      }
     },
     {
-      "code": 6022,
-      "name": "memberNotActive",
-      "msg": "MemberNotActive: The user is not active."
+      "name": "pointsMinted",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "points",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6023,
-      "name": "cannotDelegateToSelf",
-      "msg": "Cannot delegate vote to self."
+      "name": "pointsRedeemed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "points",
+            "type": "u64"
+          },
+          {
+            "name": "discount",
+            "type": "u64"
+          }
+        ]
+      }
     },
     {
-      "code": 6024,
-      "name": "alreadyDelegated",
-      "msg": "Vote has already been delegated."
+      "name": "priceCurrency",
+      "docs": [
+        "Unit of `Product.price` and `Product.order_fee`: base units of the",
+        "payment mint, or US cents converted at checkout through a price feed."
+      ],
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "token"
+          },
+          {
+            "name": "usdCents"
+          }
+        ]
+      }
     },
     {
-      "code": 6025,
-      "name": "invalidOrganization",
-      "msg": "Member does not belong to this organization."
-    }
-  ],
-  "types": [
-    {
-      "name": "cart",
+      "name": "product",
       "type": {
         "kind": "struct",
         "fields": [
@@ -921,509 +9884,693 @@ export type EcomDapp = {
             "name": "productName",
             "type": "string"
           },
+          {
+            "name": "category",
+            "type": {
+              "defined": {
+                "name": "category"
+              }
+            }
+          },
+          {
+            "name": "division",
+            "type": {
+              "defined": {
+                "name": "division"
+              }
+            }
+          },
           {
             "name": "quantity",
-            "type": "u64"
+            "type": "u32"
           },
           {
             "name": "sellerPubkey",
             "type": "pubkey"
           },
+          {
+            "name": "sellerName",
+            "type": "string"
+          },
+          {
+            "name": "productShortDescription",
+            "type": "string"
+          },
           {
             "name": "productImgurl",
             "type": "string"
           },
           {
-            "name": "stockStatus",
+            "name": "price",
+            "type": "u32"
+          },
+          {
+            "name": "priceCurrency",
             "type": {
               "defined": {
-                "name": "anchor::states::cart::Stock"
+                "name": "priceCurrency"
               }
             }
           },
           {
-            "name": "amount",
+            "name": "mint",
+            "docs": [
+              "The only mint a token-priced product can be paid in. USD-priced",
+              "products take any mint the marketplace has registered instead."
+            ],
+            "type": "pubkey"
+          },
+          {
+            "name": "discountBps",
+            "type": "u16"
+          },
+          {
+            "name": "orderFee",
+            "type": "u32"
+          },
+          {
+            "name": "payoutSplits",
             "type": {
-              "vec": "u64"
+              "vec": {
+                "defined": {
+                  "name": "payoutSplit"
+                }
+              }
             }
           },
           {
-            "name": "cartBump",
+            "name": "rating",
+            "type": "f32"
+          },
+          {
+            "name": "stockStatus",
+            "type": {
+              "defined": {
+                "name": "anchor::states::product::Stock"
+              }
+            }
+          },
+          {
+            "name": "creationBump",
             "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "cartCreated",
+      "name": "productCreated",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "seller",
+            "name": "productPubkey",
             "type": "pubkey"
           },
           {
-            "name": "quantity",
-            "type": "u64"
+            "name": "seller",
+            "type": "pubkey"
           },
           {
             "name": "productName",
             "type": "string"
           },
           {
-            "name": "amount",
-            "type": "u64"
-          }
-        ]
-      }
-    },
-    {
-      "name": "cartList",
-      "type": {
-        "kind": "struct",
-        "fields": [
+            "name": "price",
+            "type": "u32"
+          },
           {
-            "name": "cartList",
+            "name": "category",
             "type": {
-              "vec": "pubkey"
+              "defined": {
+                "name": "category"
+              }
             }
           },
           {
-            "name": "totalAmount",
-            "type": "u64"
-          },
-          {
-            "name": "cartListBump",
-            "type": "u8"
+            "name": "division",
+            "type": {
+              "defined": {
+                "name": "division"
+              }
+            }
           }
         ]
       }
     },
     {
-      "name": "category",
+      "name": "productPricingUpdated",
       "type": {
-        "kind": "enum",
-        "variants": [
+        "kind": "struct",
+        "fields": [
           {
-            "name": "electronics"
+            "name": "productPubkey",
+            "type": "pubkey"
           },
           {
-            "name": "beautyAndPersonalCare"
+            "name": "price",
+            "type": "u32"
           },
           {
-            "name": "snacksAndDrinks"
+            "name": "priceCurrency",
+            "type": {
+              "defined": {
+                "name": "priceCurrency"
+              }
+            }
           },
           {
-            "name": "householdEssentials"
+            "name": "discountBps",
+            "type": "u16"
           },
           {
-            "name": "groceryAndKitchen"
+            "name": "orderFee",
+            "type": "u32"
           }
         ]
       }
     },
     {
-      "name": "division",
+      "name": "productsList",
       "type": {
-        "kind": "enum",
-        "variants": [
-          {
-            "name": "mobile"
-          },
-          {
-            "name": "laptop"
-          },
-          {
-            "name": "headphone"
-          },
+        "kind": "struct",
+        "fields": [
           {
-            "name": "smartWatch"
+            "name": "products",
+            "type": {
+              "vec": "pubkey"
+            }
           },
           {
-            "name": "computerPeripherals"
+            "name": "productListBump",
+            "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "escrow",
+      "name": "purchaseArchive",
+      "docs": [
+        "What's left of a purchase once its payment, escrow and order are closed."
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "owner",
+            "name": "payment",
             "type": "pubkey"
           },
           {
-            "name": "buyerPubkey",
+            "name": "buyer",
             "type": "pubkey"
           },
           {
-            "name": "sellerPubkey",
+            "name": "seller",
             "type": "pubkey"
           },
           {
-            "name": "amount",
-            "type": "u64"
+            "name": "product",
+            "type": "pubkey"
           },
           {
-            "name": "releaseFund",
-            "type": "bool"
+            "name": "mint",
+            "type": "pubkey"
           },
           {
-            "name": "timeStamp",
-            "type": "i64"
+            "name": "orderId",
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  16
+                ]
+              }
+            }
           },
           {
-            "name": "updateTimestamp",
-            "type": "i64"
+            "name": "amount",
+            "type": "u64"
           },
           {
-            "name": "escrowStatus",
+            "name": "refundedAmount",
+            "type": "u64"
+          },
+          {
+            "name": "marketplaceFee",
+            "type": "u64"
+          },
+          {
+            "name": "outcome",
             "type": {
               "defined": {
-                "name": "escrowStatus"
+                "name": "paymentStatus"
               }
             }
           },
           {
-            "name": "escrowBump",
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "closedAt",
+            "type": "i64"
+          },
+          {
+            "name": "archiveBump",
             "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "escrowStatus",
+      "name": "purchaseClosed",
       "type": {
-        "kind": "enum",
-        "variants": [
+        "kind": "struct",
+        "fields": [
           {
-            "name": "swapPending"
+            "name": "payment",
+            "type": "pubkey"
           },
           {
-            "name": "fundsReceived"
+            "name": "rentPayer",
+            "type": "pubkey"
           },
           {
-            "name": "swapSuccess"
+            "name": "archived",
+            "type": "bool"
+          }
+        ]
+      }
+    },
+    {
+      "name": "purchaseList",
+      "docs": [
+        "Per-buyer index of purchases. `payment_count` is the nonce of the next payment",
+        "and `open_purchases` holds the payments that have not been settled yet."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "openPurchases",
+            "type": {
+              "vec": "pubkey"
+            }
           },
           {
-            "name": "transferFailed"
+            "name": "paymentCount",
+            "type": "u64"
+          },
+          {
+            "name": "purchaseListBump",
+            "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "order",
+      "name": "receipt",
+      "docs": [
+        "Proof of purchase behind a one-of-one receipt token. The receipt mint's",
+        "authority is this PDA, and the program only mints from it once, at",
+        "settlement, so anyone can verify a receipt by deriving",
+        "`[b\"receipt\", payment]` and checking it against the mint."
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "orderId",
-            "type": {
-              "array": [
-                "u8",
-                16
-              ]
-            }
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
           },
           {
-            "name": "paymentId",
-            "type": "string"
+            "name": "buyer",
+            "type": "pubkey"
           },
           {
-            "name": "trackingId",
-            "type": {
-              "array": [
-                "u8",
-                16
-              ]
-            }
+            "name": "seller",
+            "type": "pubkey"
           },
           {
-            "name": "orderStatus",
-            "type": {
-              "defined": {
-                "name": "orderStatus"
-              }
-            }
+            "name": "product",
+            "type": "pubkey"
           },
           {
-            "name": "orderTracking",
+            "name": "orderId",
+            "docs": [
+              "Missing when the order was never placed before settlement."
+            ],
             "type": {
-              "defined": {
-                "name": "orderTracking"
+              "option": {
+                "array": [
+                  "u8",
+                  16
+                ]
               }
             }
           },
           {
-            "name": "createdAt",
-            "type": "i64"
+            "name": "amount",
+            "type": "u64"
           },
           {
-            "name": "updatedAt",
+            "name": "settledAt",
             "type": "i64"
           },
           {
-            "name": "orderBump",
+            "name": "receiptBump",
             "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "orderStatus",
+      "name": "receiptMinted",
       "type": {
-        "kind": "enum",
-        "variants": [
+        "kind": "struct",
+        "fields": [
           {
-            "name": "pending"
+            "name": "receipt",
+            "type": "pubkey"
           },
           {
-            "name": "placed"
+            "name": "mint",
+            "type": "pubkey"
           },
           {
-            "name": "failed"
+            "name": "payment",
+            "type": "pubkey"
           },
           {
-            "name": "returned"
+            "name": "buyer",
+            "type": "pubkey"
           }
         ]
       }
     },
     {
-      "name": "orderTracking",
+      "name": "refundReason",
       "type": {
         "kind": "enum",
         "variants": [
           {
-            "name": "watingForOrders"
-          },
-          {
-            "name": "booked"
-          },
-          {
-            "name": "inTransit"
-          },
-          {
-            "name": "shipped"
-          },
-          {
-            "name": "outForDelivery"
+            "name": "sellerCancelled"
           },
           {
-            "name": "delivered"
+            "name": "paymentPlanDefaulted"
           }
         ]
       }
     },
     {
-      "name": "payment",
+      "name": "refunded",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "paymentId",
-            "type": {
-              "array": [
-                "u8",
-                16
-              ]
-            }
+            "name": "payment",
+            "type": "pubkey"
           },
           {
-            "name": "paymentAmount",
-            "type": "u64"
+            "name": "escrow",
+            "type": "pubkey"
           },
           {
-            "name": "productPubkey",
+            "name": "buyer",
             "type": "pubkey"
           },
           {
-            "name": "paymentMethod",
-            "type": {
-              "defined": {
-                "name": "paymentMethod"
-              }
-            }
+            "name": "amount",
+            "type": "u64"
           },
           {
-            "name": "paymentStatus",
+            "name": "remaining",
+            "type": "u64"
+          },
+          {
+            "name": "reason",
             "type": {
               "defined": {
-                "name": "paymentStatus"
+                "name": "refundReason"
               }
             }
-          },
+          }
+        ]
+      }
+    },
+    {
+      "name": "relayNonce",
+      "docs": [
+        "Next intent nonce for a buyer. Intents must be relayed in nonce order,",
+        "and each nonce is only accepted once."
+      ],
+      "type": {
+        "kind": "struct",
+        "fields": [
           {
-            "name": "timeStamp",
-            "type": "i64"
+            "name": "buyer",
+            "type": "pubkey"
           },
           {
-            "name": "txSignature",
-            "type": {
-              "option": "string"
-            }
+            "name": "nextNonce",
+            "type": "u64"
           },
           {
-            "name": "paymentBump",
+            "name": "relayNonceBump",
             "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "paymentMethod",
+      "name": "sellerProfile",
+      "docs": [
+        "Marketplace-maintained record of a seller, created by the admin or on",
+        "the seller's first dispute ruling."
+      ],
       "type": {
-        "kind": "enum",
-        "variants": [
+        "kind": "struct",
+        "fields": [
           {
-            "name": "sol"
+            "name": "seller",
+            "type": "pubkey"
           },
           {
-            "name": "eth"
+            "name": "tier",
+            "type": {
+              "defined": {
+                "name": "sellerTier"
+              }
+            }
           },
           {
-            "name": "btc"
+            "name": "disputesWon",
+            "docs": [
+              "Dispute rulings: released to the seller, refunded to the buyer, split."
+            ],
+            "type": "u32"
           },
           {
-            "name": "usdt"
+            "name": "disputesLost",
+            "type": "u32"
           },
           {
-            "name": "usdc"
+            "name": "disputesSplit",
+            "type": "u32"
+          },
+          {
+            "name": "sellerProfileBump",
+            "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "paymentStatus",
+      "name": "sellerTier",
       "type": {
         "kind": "enum",
         "variants": [
           {
-            "name": "success"
+            "name": "standard"
           },
           {
-            "name": "pending"
+            "name": "silver"
           },
           {
-            "name": "failed"
+            "name": "gold"
           }
         ]
       }
     },
     {
-      "name": "product",
+      "name": "subscription",
+      "docs": [
+        "Recurring order of `quantity` units of `product`, billed every `interval`",
+        "seconds from the buyer's token account through the delegate PDA",
+        "`[b\"payment_delegate\", buyer]`, which relayed payments also spend through."
+      ],
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "productId",
-            "type": {
-              "array": [
-                "u8",
-                16
-              ]
-            }
+            "name": "buyer",
+            "type": "pubkey"
           },
           {
-            "name": "productName",
-            "type": "string"
+            "name": "seller",
+            "type": "pubkey"
           },
           {
-            "name": "category",
-            "type": {
-              "defined": {
-                "name": "category"
-              }
-            }
+            "name": "product",
+            "type": "pubkey"
           },
           {
-            "name": "division",
-            "type": {
-              "defined": {
-                "name": "division"
-              }
-            }
+            "name": "mint",
+            "type": "pubkey"
           },
           {
             "name": "quantity",
-            "type": "u32"
+            "type": "u64"
           },
           {
-            "name": "sellerPubkey",
-            "type": "pubkey"
+            "name": "interval",
+            "type": "i64"
           },
           {
-            "name": "sellerName",
-            "type": "string"
+            "name": "nextBillingAt",
+            "type": "i64"
           },
           {
-            "name": "productShortDescription",
-            "type": "string"
+            "name": "billedCount",
+            "type": "u64"
           },
           {
-            "name": "productImgurl",
-            "type": "string"
+            "name": "failedCount",
+            "type": "u64"
           },
           {
-            "name": "price",
-            "type": "u32"
+            "name": "consecutiveFailures",
+            "type": "u8"
           },
           {
-            "name": "rating",
-            "type": "f32"
+            "name": "lastFailure",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "billingFailure"
+                }
+              }
+            }
           },
           {
-            "name": "stockStatus",
+            "name": "lastFailureAt",
+            "type": "i64"
+          },
+          {
+            "name": "subscriptionStatus",
             "type": {
               "defined": {
-                "name": "anchor::states::product::Stock"
+                "name": "subscriptionStatus"
               }
             }
           },
           {
-            "name": "creationBump",
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "subscriptionBump",
             "type": "u8"
           }
         ]
       }
     },
     {
-      "name": "productCreated",
+      "name": "subscriptionBilled",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "productPubkey",
+            "name": "subscription",
             "type": "pubkey"
           },
           {
-            "name": "seller",
+            "name": "payment",
             "type": "pubkey"
           },
           {
-            "name": "productName",
-            "type": "string"
+            "name": "amount",
+            "type": "u64"
           },
           {
-            "name": "price",
-            "type": "u32"
+            "name": "nextBillingAt",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "subscriptionBillingFailed",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "subscription",
+            "type": "pubkey"
           },
           {
-            "name": "category",
+            "name": "payment",
+            "type": "pubkey"
+          },
+          {
+            "name": "reason",
             "type": {
               "defined": {
-                "name": "category"
+                "name": "billingFailure"
               }
             }
           },
           {
-            "name": "division",
+            "name": "consecutiveFailures",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "subscriptionStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "active"
+          },
+          {
+            "name": "paused"
+          },
+          {
+            "name": "cancelled"
+          }
+        ]
+      }
+    },
+    {
+      "name": "subscriptionStatusChanged",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "subscription",
+            "type": "pubkey"
+          },
+          {
+            "name": "subscriptionStatus",
             "type": {
               "defined": {
-                "name": "division"
+                "name": "subscriptionStatus"
               }
             }
           }
@@ -1431,19 +10578,21 @@ export type EcomDapp = {
       }
     },
     {
-      "name": "productsList",
+      "name": "tierFee",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "products",
+            "name": "tier",
             "type": {
-              "vec": "pubkey"
+              "defined": {
+                "name": "sellerTier"
+              }
             }
           },
           {
-            "name": "productListBump",
-            "type": "u8"
+            "name": "feeBps",
+            "type": "u16"
           }
         ]
       }
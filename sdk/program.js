@@ -6,16 +6,19 @@ import {
   TOKEN_PROGRAM_ID,           
   getAssociatedTokenAddressSync,
   createAssociatedTokenAccountInstruction,
+  createAssociatedTokenAccountIdempotentInstruction,
   ASSOCIATED_TOKEN_PROGRAM_ID,
-  createMint,
-  getOrCreateAssociatedTokenAccount,
-  mintTo,
 } from "@solana/spl-token";
 
 const ECOM_PROGRAM_ID = new PublicKey(
   "FYo4gi69vTJZJMnNxj2mZz2Q9CbUu12rQDVtHNUFQ2o7"
 );
 
+// Token new products are priced in; defaults to devnet USDC.
+const PAYMENT_MINT = new PublicKey(
+  process.env.NEXT_PUBLIC_PAYMENT_MINT || "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"
+);
+
 import {
 
 } from "@solana/spl-token";
@@ -34,6 +37,67 @@ function createProvider(wallet) {
     preflightCommitment: "confirmed",
   });
 }
+
+function marketplacePda() {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("marketplace")],
+    ECOM_PROGRAM_ID
+  )[0];
+}
+
+function productPdaFor(seller, productName) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("product"), seller.toBuffer(), Buffer.from(productName)],
+    ECOM_PROGRAM_ID
+  )[0];
+}
+
+export function purchaseListPdaFor(owner) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("purchase_list"), owner.toBuffer()],
+    ECOM_PROGRAM_ID
+  )[0];
+}
+
+// Each purchase is keyed by the buyer's purchase count when it was made.
+export function paymentPdaFor(owner, nonce) {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("payment"),
+      owner.toBuffer(),
+      new BN(nonce).toArrayLike(Buffer, "le", 8),
+    ],
+    ECOM_PROGRAM_ID
+  )[0];
+}
+
+// The escrow, order and receipt of a purchase hang off its payment.
+export function escrowPdaFor(paymentPda) {
+  return paymentChildPda("escrow", paymentPda);
+}
+
+function paymentChildPda(seed, paymentPda) {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(seed), paymentPda.toBuffer()],
+    ECOM_PROGRAM_ID
+  )[0];
+}
+
+async function nextPaymentPda(ecomProgram, owner) {
+  const purchaseList = await ecomProgram.account.purchaseList.fetchNullable(
+    purchaseListPdaFor(owner)
+  );
+  const nonce = purchaseList ? Number(purchaseList.paymentCount) : 0;
+  return { paymentPda: paymentPdaFor(owner, nonce), nonce };
+}
+
+async function tokenProgramFor(connection, mint) {
+  const mintInfo = await connection.getAccountInfo(mint);
+  if (!mintInfo) {
+    throw new Error(`Mint not found: ${mint.toString()}`);
+  }
+  return mintInfo.owner;
+}
 export function getCategoryVariant(category) {
   if (!category || category.trim() === "") {
     category = "Electronics";
@@ -180,6 +244,7 @@ export async function initCreateProduct(
         product: productPda,
         productList: productListPda,
         seller: walletAdapter.publicKey,
+        mint: PAYMENT_MINT,
         systemProgram: SystemProgram.programId,
       })
       .rpc({
@@ -538,42 +603,41 @@ export const fetchCartList = async (walletAdapter) => {
   }
 };
 
-export const initCreatePayment = async (walletAdapter, totalAmount) => {
+export const initCreatePayment = async (walletAdapter, cartPdaString) => {
   const provider = createProvider(walletAdapter);
   anchor.setProvider(provider);
 
   const ecomProgram = new anchor.Program(IDL, provider);
 
   try {
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), walletAdapter.publicKey.toBuffer()],
-      ECOM_PROGRAM_ID
-    );
-
-    // Check if payment account already exists
-    try {
-      const existingAccount = await provider.connection.getAccountInfo(paymentPda);
-      if (existingAccount) {
-        console.log("Payment account already exists, skipping creation");
-        return {
-          success: true,
-          transaction: "Account already exists",
-          payment: paymentPda.toString(),
-        };
-      }
-    } catch (checkError) {
-      // Account doesn't exist, proceed with creation
-    }
+    const owner = walletAdapter.publicKey;
+    const cartPda = new PublicKey(cartPdaString);
+    const cart = await ecomProgram.account.cart.fetch(cartPda);
+    const productPda = productPdaFor(cart.sellerPubkey, cart.productName);
+    const product = await ecomProgram.account.product.fetch(productPda);
+    const { paymentPda, nonce } = await nextPaymentPda(ecomProgram, owner);
+    console.log("Payment PDA:", paymentPda.toString(), "nonce:", nonce);
 
+    // The program prices the cart's quantity of the product itself.
     const tx = await ecomProgram.methods
-      .createPayment(
-        new BN(totalAmount), 
-        paymentPda, 
-        null
-      ).accounts({
-        signer: walletAdapter.publicKey,
-        payment: paymentPda,
-        system_program: SystemProgram.programId,
+      .createPayment(Number(cart.quantity), null, new BN(0))
+      .accounts({
+        signer: owner,
+        purchaseList: purchaseListPdaFor(owner),
+        payments: paymentPda,
+        product: productPda,
+        cart: cartPda,
+        mint: product.mint,
+        marketplace: null,
+        acceptedMint: null,
+        priceFeed: null,
+        coupon: null,
+        couponRedemption: null,
+        loyalty: null,
+        pointsMint: null,
+        buyerPoints: null,
+        pointsTokenProgram: null,
+        systemProgram: SystemProgram.programId,
       })
       .rpc({
         skipPreflight: false,
@@ -594,30 +658,11 @@ export const initCreatePayment = async (walletAdapter, totalAmount) => {
   }
 };
 
-export const initCreateEscrow = async (walletAdapter, sellerPubkey, totalAmount) => {
-  console.log("initCreateEscrow called with:", {
-    walletAdapter,
-    walletAdapterType: typeof walletAdapter,
-    hasPublicKey: !!walletAdapter?.publicKey,
-    sellerPubkey,
-    totalAmount
-  });
-  console.log("WalletAdapter Payer: ",walletAdapter.payer);
-  
-  console.log("Wallet Adapter Details: ",walletAdapter);
-  
+export const initCreateEscrow = async (walletAdapter, paymentPdaString) => {
   const provider = createProvider(walletAdapter);
   anchor.setProvider(provider);
 
   const ecomProgram = new anchor.Program(IDL, provider);
-  const owner = walletAdapter; 
-  const buyer = walletAdapter;
-  const seller = new PublicKey(sellerPubkey); 
-  
-  console.log("Buyer Pubkey: ",walletAdapter.publicKey.toString());
-  console.log("Seller Pubkey: ",sellerPubkey);
-  console.log("Total Amount: ",totalAmount);
-  console.log("Calling initCreateEscrow, walletAdapter:", walletAdapter);
 
   if (!walletAdapter || !walletAdapter.publicKey) {
     console.error("Wallet adapter not ready. Aborting escrow creation.");
@@ -625,266 +670,100 @@ export const initCreateEscrow = async (walletAdapter, sellerPubkey, totalAmount)
   }
 
   try {
-    let escrowPda, paymentPda;
-    try {
-      [escrowPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow"), owner.publicKey.toBuffer()],
-        ECOM_PROGRAM_ID
-      );
-      console.log("Escrow PDA:", escrowPda.toString());
-
-      [paymentPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("payment"), owner.publicKey.toBuffer()],
-        ECOM_PROGRAM_ID
-      );
-      console.log("Payment PDA:", paymentPda.toString());
-    } catch (pdaError) {
-      console.error("PDA creation failed:", pdaError);
-      throw new Error(`PDA creation failed: ${pdaError.message}`);
-    }
-
-    if (!walletAdapter || !sellerPubkey) throw new Error("Required pubkeys missing");
-
-    let escrowAta, buyerAta, sellerAta, userAta;
-    
-    const mint = await createMint(
-      provider.connection,
-      walletAdapter.payer,
-      owner.publicKey,
-      null,
-      6
-    );
-    escrowAta = (
-      await getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        walletAdapter.payer,
-        mint,
-        escrowPda,
-        true
-      )
-    ).address;
+    const owner = walletAdapter.publicKey;
+    const paymentPda = new PublicKey(paymentPdaString);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const payment = await ecomProgram.account.payment.fetch(paymentPda);
+    const mint = payment.mint;
+    const tokenProgram = await tokenProgramFor(provider.connection, mint);
+    console.log("Escrow PDA:", escrowPda.toString());
 
-    buyerAta = (
-      await getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        walletAdapter.payer,
-        mint,
-        buyer.publicKey
-      )
-    ).address;
+    const userAta = getAssociatedTokenAddressSync(mint, owner, false, tokenProgram);
+    const escrowAta = getAssociatedTokenAddressSync(mint, escrowPda, true, tokenProgram);
+    const sellerAta = getAssociatedTokenAddressSync(mint, payment.seller, false, tokenProgram);
 
-    sellerAta = (
-      await getOrCreateAssociatedTokenAccount(
-        provider.connection,
-        walletAdapter.payer,
+    const tx = await ecomProgram.methods
+      .createEscrow(owner, payment.seller, [])
+      .accounts({
+        owner,
+        payment: paymentPda,
+        escrow: escrowPda,
+        marketplace: marketplacePda(),
+        userAta,
+        escrowAta,
+        buyerAta: userAta,
+        sellerAta,
         mint,
-        seller.publicKey
-      )
-    ).address;
-
-    userAta = (
-      await getOrCreateAssociatedTokenAccount(
-        provider.connection,
-            walletAdapter.payer,
-            mint,
-            owner.publicKey
-          )
-        ).address;
-    
-    await mintTo(
-      provider.connection,
-      walletAdapter.payer ?? Keypair.generate(),
-      mint,
-      userAta,
-      walletAdapter.payer,
-
-      100_000_000_000
-    );
-    console.log("Escrow ATA:", escrowAta.toString());
-    console.log("Buyer ATA:", buyerAta.toString());
-    console.log("Seller ATA:", sellerAta.toString());
-    console.log("User ATA:", userAta.toString());
-
-    let paymentResult;
-    try {
-      console.log("Creating payment account...");
-      paymentResult = await initCreatePayment(walletAdapter, totalAmount);
-      
-      if (!paymentResult.success) {
-        throw new Error(`Payment creation failed: ${paymentResult.error}`);
-      }
-      
-      console.log("Payment Result:", paymentResult);
-    } catch (paymentError) {
-      console.error("Payment creation failed:", paymentError);
-      throw new Error(`Payment creation failed: ${paymentError.message}`);
-    }
-
-    let escrowExists = false;
-    try {
-      const existingEscrow = await provider.connection.getAccountInfo(escrowPda);
-      if (existingEscrow) {
-        console.log("Escrow account already exists, skipping creation");
-        escrowExists = true;
-      }
-    } catch (error) {
-      console.log("Escrow account doesn't exist, will create new one");
-    }
-
-    let tx;
-
-    if (escrowExists) {
-      console.log("Escrow already exists - returning success");
-      tx = "Escrow account already exists";
-    } else {
-      try {
-        tx = await ecomProgram.methods
-          .createEscrow(buyer, seller, new anchor.BN(totalAmount))
-          .accounts({
-            owner,
-            escrow: escrowPda,
-            payment: paymentPda,
-            userAta,
-            escrowAta,
-            buyerAta,
-            sellerAta,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-          })
-          .rpc({
-            skipPreflight: false,
-            preflightCommitment: "confirmed",
-            commitment: "confirmed",
-          });
-      } catch (escrowError) {
-        console.error("Deposit escrow transaction failed:", escrowError);
-        throw new Error(`Deposit escrow transaction failed: ${escrowError.message}`);
-      }
-    }
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([
+        createAssociatedTokenAccountIdempotentInstruction(owner, sellerAta, payment.seller, mint, tokenProgram),
+      ])
+      .rpc({
+        skipPreflight: false,
+        preflightCommitment: "confirmed",
+        commitment: "confirmed",
+      });
     console.log("Escrow Transaction Signature:", tx);
     console.log("--- CREATED ESCROW ---");
 
     return {
-      success:true,
-      data:escrowPda,
+      success: true,
+      data: escrowPda,
+      payment: paymentPda.toString(),
       mint: mint.toString(),
-    }
+    };
   } catch (error) {
     console.error("Escrow creation failed:", error.message);
     return { success: false, error: error.message };
   }
 };
 
-
-
-
-export const initDepositeEscrow = async(walletAdapter, sellerPubkey, totalAmount, mint) => {
+export const initDepositeEscrow = async (walletAdapter, paymentPdaString) => {
   const provider = createProvider(walletAdapter);
   anchor.setProvider(provider);
 
-  const ecomProgram = new anchor.Program(IDL,provider);
+  const ecomProgram = new anchor.Program(IDL, provider);
 
-  const owner = walletAdapter.publicKey; 
-  const buyer = walletAdapter.publicKey;
-  const seller = new PublicKey(sellerPubkey); 
-  
-  console.log("Buyer Pubkey: ",walletAdapter.publicKey);
-  console.log("Seller Pubkey: ",sellerPubkey);
-  console.log("Total Amount: ",totalAmount);
-  
   try {
-    console.log("Creating PDAs with owner:", owner.toString());
-    let escrowPda, paymentPda;
-    try {
-      [escrowPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow"), owner.toBuffer()],
-        ECOM_PROGRAM_ID
-      );
-      console.log("Escrow PDA:", escrowPda.toString());
-
-      [paymentPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("payment"), owner.toBuffer()],
-        ECOM_PROGRAM_ID
-      );
-      console.log("Payment PDA:", paymentPda.toString());
-    } catch (pdaError) {
-      console.error("PDA creation failed:", pdaError);
-      throw new Error(`PDA creation failed: ${pdaError.message}`);
-    }
-
-    let paymentResult;
-    try {
-      console.log("Creating payment account...");
-      paymentResult = await initCreatePayment(walletAdapter, totalAmount);
-      
-      if (!paymentResult.success) {
-        throw new Error(`Payment creation failed: ${paymentResult.error}`);
-      }
-      
-      console.log("Payment Result:", paymentResult);
-    } catch (paymentError) {
-      console.error("Payment creation failed:", paymentError);
-      throw new Error(`Payment creation failed: ${paymentError.message}`);
-    }
-
- 
-    let escrowAta, buyerAta, sellerAta, userAta;
-    
-    escrowAta = new PublicKey("So11111111111111111111111111111111111111112"); 
-    buyerAta = new PublicKey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"); 
-    sellerAta = new PublicKey("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"); 
-    userAta = new PublicKey("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"); 
-    
-    console.log("Using dummy token accounts:");
-    console.log("Escrow ATA:", escrowAta.toString());
-    console.log("Buyer ATA:", buyerAta.toString());
-    console.log("Seller ATA:", sellerAta.toString());
-    console.log("User ATA:", userAta.toString());
+    const owner = walletAdapter.publicKey;
+    const paymentPda = new PublicKey(paymentPdaString);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const payment = await ecomProgram.account.payment.fetch(paymentPda);
+    const mint = payment.mint;
+    const tokenProgram = await tokenProgramFor(provider.connection, mint);
+    const userAta = getAssociatedTokenAddressSync(mint, owner, false, tokenProgram);
 
-  let despositEscrowExists = false;
-    try {
-      const existingDepositeEscrow = await provider.connection.getAccountInfo(escrowPda);
-      if (existingDepositeEscrow) {
-        console.log("Escrow account already exists, skipping creation");
-        despositEscrowExists = true;
-      }
-    } catch (error) {
-      console.log("Escrow account doesn't exist, will create new one");
-    }
-    let tx;
-    if (despositEscrowExists) {
-      console.log("Escrow already exists - returning success");
-      tx = "Escrow account already exists";
-    } else {
-      try {
-        tx = await ecomProgram.methods
-          .depositEscrow(
-            1,
-          ).accounts({
-            owner,
-            escrow: escrowPda,
-            payment: paymentPda,
-            userAta,
-            escrowAta,
-            buyerAta,
-            sellerAta,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-          }).rpc({
-              skipPreflight: false,
-              preflightCommitment: "confirmed",
-              commitment: "confirmed",
-          });
-      } catch (escrowError) {
-        console.error("Deposit escrow transaction failed:", escrowError);
-        throw new Error(`Deposit escrow transaction failed: ${escrowError.message}`);
-      }
-    }
+    // The escrow only accepts exactly the amount the payment was priced at.
+    const tx = await ecomProgram.methods
+      .depositEscrow(payment.paymentAmount, new BN(0))
+      .accounts({
+        owner,
+        payment: paymentPda,
+        escrow: escrowPda,
+        userAta,
+        escrowAta: getAssociatedTokenAddressSync(mint, escrowPda, true, tokenProgram),
+        buyerAta: userAta,
+        sellerAta: getAssociatedTokenAddressSync(mint, payment.seller, false, tokenProgram),
+        mint,
+        giftCard: null,
+        giftCardVault: null,
+        tokenProgram,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc({
+        skipPreflight: false,
+        preflightCommitment: "confirmed",
+        commitment: "confirmed",
+      });
     console.log("Escrow Transaction Signature:", tx);
     console.log("--- CREATED DEPOSITE ESCROW ---");
     return {
-      success:true,
-      data:escrowPda,
+      success: true,
+      transaction: tx,
+      data: escrowPda,
       mint: mint.toString(),
     };
   } catch (error) {
@@ -893,110 +772,104 @@ export const initDepositeEscrow = async(walletAdapter, sellerPubkey, totalAmount
   }
 }
 
-export const initWithdrawEscrow = async(walletAdapter, sellerPubkey, totalAmount, mint) => {
+export const initWithdrawEscrow = async (walletAdapter, paymentPdaString) => {
   const provider = createProvider(walletAdapter);
   anchor.setProvider(provider);
 
-  const ecomProgram = new anchor.Program(IDL,provider);
+  const ecomProgram = new anchor.Program(IDL, provider);
 
-  const owner = walletAdapter.publicKey; 
-  const buyer = walletAdapter.publicKey;
-  const seller = new PublicKey(sellerPubkey); 
-  
-  console.log("Buyer Pubkey: ",walletAdapter.publicKey);
-  console.log("Seller Pubkey: ",sellerPubkey);
-  console.log("Total Amount: ",totalAmount);
-  
   try {
-    console.log("Creating PDAs with owner:", owner.toString());
-    let escrowPda, paymentPda;
-
-    try {
-      [escrowPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("escrow"), owner.toBuffer()],
-        ECOM_PROGRAM_ID
-      );
-      console.log("Escrow PDA:", escrowPda.toString());
-
-      [paymentPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("payment"), owner.toBuffer()],
-        ECOM_PROGRAM_ID
-      );
-      console.log("Payment PDA:", paymentPda.toString());
-    } catch (pdaError) {
-      console.error("PDA creation failed:", pdaError);
-      throw new Error(`PDA creation failed: ${pdaError.message}`);
-    }
-
-
-    let paymentResult;
-    try {
-      console.log("Creating payment account...");
-      paymentResult = await initCreatePayment(walletAdapter, totalAmount);
-      
-      if (!paymentResult.success) {
-        throw new Error(`Payment creation failed: ${paymentResult.error}`);
-      }
-      
-      console.log("Payment Result:", paymentResult);
-    } catch (paymentError) {
-      console.error("Payment creation failed:", paymentError);
-      throw new Error(`Payment creation failed: ${paymentError.message}`);
-    }
+    const authority = walletAdapter.publicKey;
+    const paymentPda = new PublicKey(paymentPdaString);
+    const escrowPda = escrowPdaFor(paymentPda);
+    const payment = await ecomProgram.account.payment.fetch(paymentPda);
+    const owner = payment.owner;
+    const mint = payment.mint;
+    const tokenProgram = await tokenProgramFor(provider.connection, mint);
+    const product = await ecomProgram.account.product.fetch(payment.productPubkey);
+    const marketplace = await ecomProgram.account.marketplace.fetch(marketplacePda());
+
+    const ataFor = (wallet) => getAssociatedTokenAddressSync(mint, wallet, true, tokenProgram);
+    const userAta = ataFor(owner);
+    const sellerAta = ataFor(payment.seller);
+    const treasuryAta = ataFor(marketplace.treasury);
+    const splitAtas = product.payoutSplits.map((split) => ataFor(split.recipient));
+    const createAtas = [
+      [sellerAta, payment.seller],
+      [treasuryAta, marketplace.treasury],
+      ...product.payoutSplits.map((split, index) => [splitAtas[index], split.recipient]),
+    ].map(([ata, wallet]) =>
+      createAssociatedTokenAccountIdempotentInstruction(authority, ata, wallet, mint, tokenProgram)
+    );
 
-    
-    let escrowAta, buyerAta, sellerAta, userAta;
-    
-    escrowAta = new PublicKey("So11111111111111111111111111111111111111112"); 
-    buyerAta = new PublicKey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"); 
-    sellerAta = new PublicKey("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
-    userAta = new PublicKey("DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"); 
+    // Points are only minted when the marketplace runs a loyalty program.
+    const [loyaltyPda] = PublicKey.findProgramAddressSync([Buffer.from("loyalty")], ECOM_PROGRAM_ID);
+    const [pointsMintPda] = PublicKey.findProgramAddressSync([Buffer.from("loyalty_mint")], ECOM_PROGRAM_ID);
+    const loyalty = await ecomProgram.account.loyaltyProgram.fetchNullable(loyaltyPda);
+    const pointsTokenProgram = loyalty
+      ? await tokenProgramFor(provider.connection, pointsMintPda)
+      : TOKEN_PROGRAM_ID;
 
+    const orderPda = paymentChildPda("order", paymentPda);
+    const hasOrder = (await provider.connection.getAccountInfo(orderPda)) !== null;
+    const receiptMint = paymentChildPda("receipt_mint", paymentPda);
 
-  let withdrawEscrowExists = false;
-    try {
-      const existingWithdrawEscrow = await provider.connection.getAccountInfo(escrowPda);
-      if (existingWithdrawEscrow) {
-        console.log("Escrow account already exists, skipping creation");
-        withdrawEscrowExists = true;
-      }
-    } catch (error) {
-      console.log("Escrow account doesn't exist, will create new one");
-    }
-    let tx;
-    if (withdrawEscrowExists) {
-      console.log("Escrow already exists - returning success");
-      tx = "Escrow account already exists";
-    } else {
-      try {
-        tx = await ecomProgram.methods
-          .withdrawEscrow(
-            1,
-          ).accounts({
-            owner,
-            escrow: escrowPda,
-            payment: paymentPda,
-            userAta,
-            escrowAta,
-            buyerAta,
-            sellerAta,
-            tokenProgram: TOKEN_PROGRAM_ID,
-            systemProgram: SystemProgram.programId,
-          }).rpc({
-              skipPreflight: false,
-              preflightCommitment: "confirmed",
-              commitment: "confirmed",
-          });
-      } catch (escrowError) {
-        console.error("Deposit escrow transaction failed:", escrowError);
-        throw new Error(`Deposit escrow transaction failed: ${escrowError.message}`);
-      }
-    }
+    const tx = await ecomProgram.methods
+      .withdrawEscrow(1)
+      .accounts({
+        authority,
+        owner,
+        payment: paymentPda,
+        escrow: escrowPda,
+        purchaseList: purchaseListPdaFor(owner),
+        marketplace: marketplacePda(),
+        product: payment.productPubkey,
+        sellerProfile: PublicKey.findProgramAddressSync(
+          [Buffer.from("seller_profile"), payment.seller.toBuffer()],
+          ECOM_PROGRAM_ID
+        )[0],
+        userAta,
+        escrowAta: getAssociatedTokenAddressSync(mint, escrowPda, true, tokenProgram),
+        buyerAta: userAta,
+        sellerAta,
+        treasuryAta,
+        keeperVault: marketplace.keeperFeeBps > 0
+          ? PublicKey.findProgramAddressSync([Buffer.from("keeper_vault"), mint.toBuffer()], ECOM_PROGRAM_ID)[0]
+          : null,
+        mint,
+        acceptedMint: PublicKey.findProgramAddressSync(
+          [Buffer.from("accepted_mint"), mint.toBuffer()],
+          ECOM_PROGRAM_ID
+        )[0],
+        loyalty: loyaltyPda,
+        pointsMint: loyalty ? pointsMintPda : null,
+        buyerPoints: loyalty
+          ? getAssociatedTokenAddressSync(pointsMintPda, owner, false, pointsTokenProgram)
+          : null,
+        pointsTokenProgram,
+        order: hasOrder ? orderPda : null,
+        receipt: paymentChildPda("receipt", paymentPda),
+        receiptMint,
+        buyerReceiptAta: getAssociatedTokenAddressSync(receiptMint, owner, false, tokenProgram),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        tokenProgram,
+      })
+      .remainingAccounts(
+        splitAtas.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+      )
+      .preInstructions(createAtas)
+      .rpc({
+        skipPreflight: false,
+        preflightCommitment: "confirmed",
+        commitment: "confirmed",
+      });
     console.log("Escrow Transaction Signature:", tx);
     console.log("--- CREATED WITHDRAW ESCROW ---");
     return {
-      success:true,
-      data:escrowPda,
+      success: true,
+      transaction: tx,
+      data: escrowPda,
       mint: mint.toString(),
     };
   } catch (error) {
@@ -1053,23 +926,20 @@ export const fetchAccountBalances = async (walletAdapter, buyerPubkey, sellerPub
   }
 };
 
-export const fetchConfirmPayment = async(walletAdapter) => {
+export const fetchConfirmPayment = async(walletAdapter, paymentPdaString) => {
   const provider = createProvider(walletAdapter);
   anchor.setProvider(provider);
 
   try {
     const ecomProgram = new anchor.Program(IDL,provider); 
-    const owner = walletAdapter.publicKey;
-    const [paymentPda] = PublicKey.findProgramAddressSync(
-      [Buffer.from("payment"), owner.toBuffer()],
-      ECOM_PROGRAM_ID
-    );
+    const paymentPda = new PublicKey(paymentPdaString);
       
     const payment = await ecomProgram.account.payment.fetch(paymentPda);
-    if (payment.paymentStatus && payment.paymentStatus.success) {
-      payment.txSignature = payment_tx;
+    // The program records the deposit itself; there is no client-side signature.
+    if (payment.funding) {
+      console.log("Funded at slot: ", Number(payment.funding.slot));
     }else{
-      console.log("Payment Not Intialized!");
+      console.log("Payment Not Funded!");
     }
     console.log("Payment Details: ",payment);
     console.log("Payment Amount: ",Number(payment.paymentAmount));